@@ -0,0 +1,243 @@
+//! Common Vulnerability Scoring System vectors and scores.
+//!
+//! Vectors like `CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:H/A:H` are parsed
+//! into [`CvssV3`] or [`CvssV4`], which compute the scores of the
+//! specification. Metrics are public, so environmental adjustments like an
+//! attack vector that is not network reachable can be applied before
+//! scoring:
+//!
+//! ```
+//! use phylum_types::cvss::v3::{AttackVector, CvssV3};
+//!
+//! let mut vector: CvssV3 = "CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:H/A:H".parse().unwrap();
+//! assert_eq!(vector.base_score(), 9.8);
+//!
+//! vector.modified_attack_vector = Some(AttackVector::Local);
+//! assert_eq!(vector.environmental_score(), 8.4);
+//! ```
+
+/// Define a metric with its values and their abbreviations in vectors.
+macro_rules! metric {
+    (
+        $(#[$meta:meta])*
+        $name:ident {
+            $($(#[$variant_meta:meta])* $variant:ident = $code:literal,)+
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Copy, Clone, Debug)]
+        pub enum $name {
+            $($(#[$variant_meta])* $variant,)+
+        }
+
+        impl crate::cvss::Metric for $name {
+            fn from_code(code: &str) -> Option<Self> {
+                match code {
+                    $($code => Some($name::$variant),)+
+                    _ => None,
+                }
+            }
+
+            fn code(&self) -> &'static str {
+                match self {
+                    $($name::$variant => $code,)+
+                }
+            }
+        }
+    };
+}
+
+pub mod v3;
+pub mod v4;
+
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+use schemars::gen::SchemaGenerator;
+use schemars::schema::Schema;
+use schemars::JsonSchema;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::cvss::v3::CvssV3;
+use crate::cvss::v4::CvssV4;
+use crate::error::Error;
+use crate::types::package::RiskLevel;
+
+/// A CVSS vector of any supported version.
+#[derive(PartialEq, Clone, Debug)]
+pub enum CvssVector {
+    V3(CvssV3),
+    V4(CvssV4),
+}
+
+impl CvssVector {
+    /// The score of the base metrics.
+    pub fn base_score(&self) -> f64 {
+        match self {
+            CvssVector::V3(vector) => vector.base_score(),
+            CvssVector::V4(vector) => vector.base_score(),
+        }
+    }
+
+    /// The score including all threat, temporal and environmental metrics
+    /// of the vector.
+    pub fn score(&self) -> f64 {
+        match self {
+            CvssVector::V3(vector) => vector.score(),
+            CvssVector::V4(vector) => vector.score(),
+        }
+    }
+
+    /// The severity of [`CvssVector::score`].
+    pub fn risk_level(&self) -> RiskLevel {
+        RiskLevel::from_cvss_score(self.score())
+    }
+}
+
+impl FromStr for CvssVector {
+    type Err = Error;
+
+    fn from_str(vector: &str) -> Result<Self, Self::Err> {
+        if vector.starts_with("CVSS:4.0/") {
+            vector.parse().map(CvssVector::V4)
+        } else {
+            vector.parse().map(CvssVector::V3)
+        }
+    }
+}
+
+impl fmt::Display for CvssVector {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CvssVector::V3(vector) => vector.fmt(f),
+            CvssVector::V4(vector) => vector.fmt(f),
+        }
+    }
+}
+
+impl Serialize for CvssVector {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for CvssVector {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let vector = String::deserialize(deserializer)?;
+        vector.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+impl JsonSchema for CvssVector {
+    fn schema_name() -> String {
+        "CvssVector".into()
+    }
+
+    fn json_schema(gen: &mut SchemaGenerator) -> Schema {
+        String::json_schema(gen)
+    }
+}
+
+impl RiskLevel {
+    /// The severity of a CVSS score, using the qualitative rating scale of
+    /// the specification. Scores of `0.0` are informational.
+    pub fn from_cvss_score(score: f64) -> Self {
+        if score < 0.1 {
+            RiskLevel::Info
+        } else if score < 4.0 {
+            RiskLevel::Low
+        } else if score < 7.0 {
+            RiskLevel::Medium
+        } else if score < 9.0 {
+            RiskLevel::High
+        } else {
+            RiskLevel::Critical
+        }
+    }
+}
+
+/// A metric value with its abbreviation in vectors, like `N` for a network
+/// attack vector.
+pub(crate) trait Metric: Sized {
+    fn from_code(code: &str) -> Option<Self>;
+    fn code(&self) -> &'static str;
+}
+
+/// The metrics of a vector by abbreviation, without the version prefix.
+struct Metrics<'a> {
+    vector: &'a str,
+    values: HashMap<&'a str, &'a str>,
+}
+
+impl<'a> Metrics<'a> {
+    /// Split a vector like `CVSS:3.1/AV:N/...` into its metrics, checking
+    /// the version prefix.
+    fn parse(vector: &'a str, prefixes: &[&str]) -> Result<(&'a str, Self), Error> {
+        let mut parts = vector.split('/');
+        let prefix = parts.next().unwrap_or_default();
+        if !prefixes.contains(&prefix) {
+            return Err(invalid(vector, "unsupported CVSS version"));
+        }
+
+        let mut values = HashMap::new();
+        for part in parts {
+            let (key, value) = match part.split_once(':') {
+                Some(metric) => metric,
+                None => return Err(invalid(vector, &format!("malformed metric {part:?}"))),
+            };
+            if values.insert(key, value).is_some() {
+                return Err(invalid(vector, &format!("duplicate metric {key}")));
+            }
+        }
+
+        Ok((prefix, Self { vector, values }))
+    }
+
+    /// Remove a mandatory metric.
+    fn required<M: Metric>(&mut self, key: &str) -> Result<M, Error> {
+        match self.optional(key)? {
+            Some(metric) => Ok(metric),
+            None => Err(invalid(self.vector, &format!("missing metric {key}"))),
+        }
+    }
+
+    /// Remove an optional metric, which is `X` if not defined.
+    fn optional<M: Metric>(&mut self, key: &str) -> Result<Option<M>, Error> {
+        match self.values.remove(key) {
+            None | Some("X") => Ok(None),
+            Some(code) => match M::from_code(code) {
+                Some(metric) => Ok(Some(metric)),
+                None => Err(invalid(
+                    self.vector,
+                    &format!("invalid value {code:?} for {key}"),
+                )),
+            },
+        }
+    }
+
+    /// Make sure all metrics were read.
+    fn finish(self) -> Result<(), Error> {
+        let mut unknown: Vec<&str> = self.values.keys().copied().collect();
+        unknown.sort_unstable();
+        match unknown.first() {
+            Some(key) => Err(invalid(self.vector, &format!("unknown metric {key}"))),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Write a metric to a vector, if it is defined.
+fn write_metric<M: Metric>(f: &mut fmt::Formatter, key: &str, metric: Option<M>) -> fmt::Result {
+    match metric {
+        Some(metric) => write!(f, "/{key}:{}", metric.code()),
+        None => Ok(()),
+    }
+}
+
+fn invalid(vector: &str, reason: &str) -> Error {
+    Error::InvalidCvss {
+        vector: vector.into(),
+        reason: reason.into(),
+    }
+}
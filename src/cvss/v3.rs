@@ -0,0 +1,398 @@
+//! CVSS v3.0 and v3.1 vectors.
+//!
+//! See <https://www.first.org/cvss/v3.1/specification-document>.
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::cvss::{write_metric, Metrics};
+use crate::error::Error;
+use crate::types::package::RiskLevel;
+
+metric! {
+    /// How the vulnerable component can be reached.
+    AttackVector {
+        Network = "N",
+        Adjacent = "A",
+        Local = "L",
+        Physical = "P",
+    }
+}
+
+metric! {
+    AttackComplexity {
+        Low = "L",
+        High = "H",
+    }
+}
+
+metric! {
+    PrivilegesRequired {
+        None = "N",
+        Low = "L",
+        High = "H",
+    }
+}
+
+metric! {
+    UserInteraction {
+        None = "N",
+        Required = "R",
+    }
+}
+
+metric! {
+    /// Whether the impact extends beyond the vulnerable component.
+    Scope {
+        Unchanged = "U",
+        Changed = "C",
+    }
+}
+
+metric! {
+    /// Impact on confidentiality, integrity or availability.
+    Impact {
+        None = "N",
+        Low = "L",
+        High = "H",
+    }
+}
+
+metric! {
+    ExploitCodeMaturity {
+        Unproven = "U",
+        ProofOfConcept = "P",
+        Functional = "F",
+        High = "H",
+    }
+}
+
+metric! {
+    RemediationLevel {
+        OfficialFix = "O",
+        TemporaryFix = "T",
+        Workaround = "W",
+        Unavailable = "U",
+    }
+}
+
+metric! {
+    ReportConfidence {
+        Unknown = "U",
+        Reasonable = "R",
+        Confirmed = "C",
+    }
+}
+
+metric! {
+    /// Importance of confidentiality, integrity or availability of the
+    /// affected system.
+    Requirement {
+        Low = "L",
+        Medium = "M",
+        High = "H",
+    }
+}
+
+/// A CVSS v3 vector.
+///
+/// Temporal and environmental metrics which are not defined are `None`.
+/// Modified metrics which are not defined take the value of the base
+/// metric.
+#[derive(PartialEq, Eq, Hash, Clone, Debug)]
+pub struct CvssV3 {
+    /// `0` for CVSS v3.0 and `1` for CVSS v3.1.
+    pub minor_version: u8,
+    pub attack_vector: AttackVector,
+    pub attack_complexity: AttackComplexity,
+    pub privileges_required: PrivilegesRequired,
+    pub user_interaction: UserInteraction,
+    pub scope: Scope,
+    pub confidentiality: Impact,
+    pub integrity: Impact,
+    pub availability: Impact,
+    pub exploit_code_maturity: Option<ExploitCodeMaturity>,
+    pub remediation_level: Option<RemediationLevel>,
+    pub report_confidence: Option<ReportConfidence>,
+    pub confidentiality_requirement: Option<Requirement>,
+    pub integrity_requirement: Option<Requirement>,
+    pub availability_requirement: Option<Requirement>,
+    pub modified_attack_vector: Option<AttackVector>,
+    pub modified_attack_complexity: Option<AttackComplexity>,
+    pub modified_privileges_required: Option<PrivilegesRequired>,
+    pub modified_user_interaction: Option<UserInteraction>,
+    pub modified_scope: Option<Scope>,
+    pub modified_confidentiality: Option<Impact>,
+    pub modified_integrity: Option<Impact>,
+    pub modified_availability: Option<Impact>,
+}
+
+impl CvssV3 {
+    pub fn base_score(&self) -> f64 {
+        let impact = impact_subscore(
+            self.scope,
+            self.confidentiality,
+            self.integrity,
+            self.availability,
+        );
+        let exploitability = exploitability(
+            self.attack_vector,
+            self.attack_complexity,
+            self.privileges_required,
+            self.user_interaction,
+            self.scope,
+        );
+
+        if impact <= 0. {
+            return 0.;
+        }
+        match self.scope {
+            Scope::Unchanged => self.round_up(f64::min(impact + exploitability, 10.)),
+            Scope::Changed => self.round_up(f64::min(1.08 * (impact + exploitability), 10.)),
+        }
+    }
+
+    /// The base score adjusted by the temporal metrics.
+    pub fn temporal_score(&self) -> f64 {
+        self.round_up(self.base_score() * self.temporal_multiplier())
+    }
+
+    /// The score with modified base metrics and security requirements of the
+    /// affected system.
+    pub fn environmental_score(&self) -> f64 {
+        let scope = self.modified_scope.unwrap_or(self.scope);
+        let requirement = |requirement: Option<Requirement>| match requirement {
+            Some(Requirement::Low) => 0.5,
+            Some(Requirement::Medium) | None => 1.,
+            Some(Requirement::High) => 1.5,
+        };
+
+        let modified = 1.
+            - (1.
+                - requirement(self.confidentiality_requirement)
+                    * impact_weight(
+                        self.modified_confidentiality
+                            .unwrap_or(self.confidentiality),
+                    ))
+                * (1.
+                    - requirement(self.integrity_requirement)
+                        * impact_weight(self.modified_integrity.unwrap_or(self.integrity)))
+                * (1.
+                    - requirement(self.availability_requirement)
+                        * impact_weight(self.modified_availability.unwrap_or(self.availability)));
+        let modified = f64::min(modified, 0.915);
+
+        let impact = match scope {
+            Scope::Unchanged => 6.42 * modified,
+            Scope::Changed if self.minor_version == 0 => {
+                7.52 * (modified - 0.029) - 3.25 * (modified - 0.02).powi(15)
+            }
+            Scope::Changed => {
+                7.52 * (modified - 0.029) - 3.25 * (modified * 0.9731 - 0.02).powi(13)
+            }
+        };
+        let exploitability = exploitability(
+            self.modified_attack_vector.unwrap_or(self.attack_vector),
+            self.modified_attack_complexity
+                .unwrap_or(self.attack_complexity),
+            self.modified_privileges_required
+                .unwrap_or(self.privileges_required),
+            self.modified_user_interaction
+                .unwrap_or(self.user_interaction),
+            scope,
+        );
+
+        if impact <= 0. {
+            return 0.;
+        }
+        let score = match scope {
+            Scope::Unchanged => self.round_up(f64::min(impact + exploitability, 10.)),
+            Scope::Changed => self.round_up(f64::min(1.08 * (impact + exploitability), 10.)),
+        };
+        self.round_up(score * self.temporal_multiplier())
+    }
+
+    /// The environmental score if any environmental metric is defined, the
+    /// temporal score otherwise.
+    pub fn score(&self) -> f64 {
+        let environmental = self.confidentiality_requirement.is_some()
+            || self.integrity_requirement.is_some()
+            || self.availability_requirement.is_some()
+            || self.modified_attack_vector.is_some()
+            || self.modified_attack_complexity.is_some()
+            || self.modified_privileges_required.is_some()
+            || self.modified_user_interaction.is_some()
+            || self.modified_scope.is_some()
+            || self.modified_confidentiality.is_some()
+            || self.modified_integrity.is_some()
+            || self.modified_availability.is_some();
+
+        match environmental {
+            true => self.environmental_score(),
+            false => self.temporal_score(),
+        }
+    }
+
+    /// The severity of [`CvssV3::score`].
+    pub fn risk_level(&self) -> RiskLevel {
+        RiskLevel::from_cvss_score(self.score())
+    }
+
+    fn temporal_multiplier(&self) -> f64 {
+        let exploit_code_maturity = match self.exploit_code_maturity {
+            Some(ExploitCodeMaturity::Unproven) => 0.91,
+            Some(ExploitCodeMaturity::ProofOfConcept) => 0.94,
+            Some(ExploitCodeMaturity::Functional) => 0.97,
+            Some(ExploitCodeMaturity::High) | None => 1.,
+        };
+        let remediation_level = match self.remediation_level {
+            Some(RemediationLevel::OfficialFix) => 0.95,
+            Some(RemediationLevel::TemporaryFix) => 0.96,
+            Some(RemediationLevel::Workaround) => 0.97,
+            Some(RemediationLevel::Unavailable) | None => 1.,
+        };
+        let report_confidence = match self.report_confidence {
+            Some(ReportConfidence::Unknown) => 0.92,
+            Some(ReportConfidence::Reasonable) => 0.96,
+            Some(ReportConfidence::Confirmed) | None => 1.,
+        };
+        exploit_code_maturity * remediation_level * report_confidence
+    }
+
+    /// Round up to one decimal.
+    ///
+    /// CVSS v3.1 rounds on an integer representation to avoid floating point
+    /// errors like `ceil(4.0000001 * 10) = 41`.
+    fn round_up(&self, value: f64) -> f64 {
+        if self.minor_version == 0 {
+            return (value * 10.).ceil() / 10.;
+        }
+
+        let integer = (value * 100_000.).round() as i64;
+        if integer % 10_000 == 0 {
+            integer as f64 / 100_000.
+        } else {
+            (integer / 10_000 + 1) as f64 / 10.
+        }
+    }
+}
+
+fn impact_subscore(
+    scope: Scope,
+    confidentiality: Impact,
+    integrity: Impact,
+    availability: Impact,
+) -> f64 {
+    let base = 1.
+        - (1. - impact_weight(confidentiality))
+            * (1. - impact_weight(integrity))
+            * (1. - impact_weight(availability));
+    match scope {
+        Scope::Unchanged => 6.42 * base,
+        Scope::Changed => 7.52 * (base - 0.029) - 3.25 * (base - 0.02).powi(15),
+    }
+}
+
+fn impact_weight(impact: Impact) -> f64 {
+    match impact {
+        Impact::None => 0.,
+        Impact::Low => 0.22,
+        Impact::High => 0.56,
+    }
+}
+
+fn exploitability(
+    attack_vector: AttackVector,
+    attack_complexity: AttackComplexity,
+    privileges_required: PrivilegesRequired,
+    user_interaction: UserInteraction,
+    scope: Scope,
+) -> f64 {
+    let attack_vector = match attack_vector {
+        AttackVector::Network => 0.85,
+        AttackVector::Adjacent => 0.62,
+        AttackVector::Local => 0.55,
+        AttackVector::Physical => 0.2,
+    };
+    let attack_complexity = match attack_complexity {
+        AttackComplexity::Low => 0.77,
+        AttackComplexity::High => 0.44,
+    };
+    let privileges_required = match (privileges_required, scope) {
+        (PrivilegesRequired::None, _) => 0.85,
+        (PrivilegesRequired::Low, Scope::Unchanged) => 0.62,
+        (PrivilegesRequired::Low, Scope::Changed) => 0.68,
+        (PrivilegesRequired::High, Scope::Unchanged) => 0.27,
+        (PrivilegesRequired::High, Scope::Changed) => 0.5,
+    };
+    let user_interaction = match user_interaction {
+        UserInteraction::None => 0.85,
+        UserInteraction::Required => 0.62,
+    };
+    8.22 * attack_vector * attack_complexity * privileges_required * user_interaction
+}
+
+impl FromStr for CvssV3 {
+    type Err = Error;
+
+    fn from_str(vector: &str) -> Result<Self, Self::Err> {
+        let (prefix, mut metrics) = Metrics::parse(vector, &["CVSS:3.0", "CVSS:3.1"])?;
+        let parsed = Self {
+            minor_version: if prefix == "CVSS:3.0" { 0 } else { 1 },
+            attack_vector: metrics.required("AV")?,
+            attack_complexity: metrics.required("AC")?,
+            privileges_required: metrics.required("PR")?,
+            user_interaction: metrics.required("UI")?,
+            scope: metrics.required("S")?,
+            confidentiality: metrics.required("C")?,
+            integrity: metrics.required("I")?,
+            availability: metrics.required("A")?,
+            exploit_code_maturity: metrics.optional("E")?,
+            remediation_level: metrics.optional("RL")?,
+            report_confidence: metrics.optional("RC")?,
+            confidentiality_requirement: metrics.optional("CR")?,
+            integrity_requirement: metrics.optional("IR")?,
+            availability_requirement: metrics.optional("AR")?,
+            modified_attack_vector: metrics.optional("MAV")?,
+            modified_attack_complexity: metrics.optional("MAC")?,
+            modified_privileges_required: metrics.optional("MPR")?,
+            modified_user_interaction: metrics.optional("MUI")?,
+            modified_scope: metrics.optional("MS")?,
+            modified_confidentiality: metrics.optional("MC")?,
+            modified_integrity: metrics.optional("MI")?,
+            modified_availability: metrics.optional("MA")?,
+        };
+        metrics.finish()?;
+        Ok(parsed)
+    }
+}
+
+/// Write the vector with its defined metrics in the order of the
+/// specification.
+impl fmt::Display for CvssV3 {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "CVSS:3.{}", self.minor_version)?;
+        write_metric(f, "AV", Some(self.attack_vector))?;
+        write_metric(f, "AC", Some(self.attack_complexity))?;
+        write_metric(f, "PR", Some(self.privileges_required))?;
+        write_metric(f, "UI", Some(self.user_interaction))?;
+        write_metric(f, "S", Some(self.scope))?;
+        write_metric(f, "C", Some(self.confidentiality))?;
+        write_metric(f, "I", Some(self.integrity))?;
+        write_metric(f, "A", Some(self.availability))?;
+        write_metric(f, "E", self.exploit_code_maturity)?;
+        write_metric(f, "RL", self.remediation_level)?;
+        write_metric(f, "RC", self.report_confidence)?;
+        write_metric(f, "CR", self.confidentiality_requirement)?;
+        write_metric(f, "IR", self.integrity_requirement)?;
+        write_metric(f, "AR", self.availability_requirement)?;
+        write_metric(f, "MAV", self.modified_attack_vector)?;
+        write_metric(f, "MAC", self.modified_attack_complexity)?;
+        write_metric(f, "MPR", self.modified_privileges_required)?;
+        write_metric(f, "MUI", self.modified_user_interaction)?;
+        write_metric(f, "MS", self.modified_scope)?;
+        write_metric(f, "MC", self.modified_confidentiality)?;
+        write_metric(f, "MI", self.modified_integrity)?;
+        write_metric(f, "MA", self.modified_availability)
+    }
+}
@@ -0,0 +1,636 @@
+//! CVSS v4.0 vectors.
+//!
+//! Scores are interpolated between the scores of "macro vectors", which group
+//! vectors of similar severity, following the reference calculator at
+//! <https://github.com/FIRSTdotorg/cvss-v4-calculator>. See
+//! <https://www.first.org/cvss/v4.0/specification-document>.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+pub use crate::cvss::v3::{
+    AttackComplexity, AttackVector, Impact, PrivilegesRequired, Requirement,
+};
+use crate::cvss::{write_metric, Metric, Metrics};
+use crate::error::Error;
+use crate::types::package::RiskLevel;
+
+metric! {
+    /// Whether the attack depends on conditions of the deployment.
+    AttackRequirements {
+        None = "N",
+        Present = "P",
+    }
+}
+
+metric! {
+    UserInteraction {
+        None = "N",
+        Passive = "P",
+        Active = "A",
+    }
+}
+
+metric! {
+    /// Modified impact on subsequent systems, which can affect human safety.
+    SubsequentImpact {
+        None = "N",
+        Low = "L",
+        High = "H",
+        Safety = "S",
+    }
+}
+
+metric! {
+    ExploitMaturity {
+        Unreported = "U",
+        ProofOfConcept = "P",
+        Attacked = "A",
+    }
+}
+
+metric! {
+    Safety {
+        Negligible = "N",
+        Present = "P",
+    }
+}
+
+metric! {
+    Automatable {
+        No = "N",
+        Yes = "Y",
+    }
+}
+
+metric! {
+    Recovery {
+        Automatic = "A",
+        User = "U",
+        Irrecoverable = "I",
+    }
+}
+
+metric! {
+    ValueDensity {
+        Diffuse = "D",
+        Concentrated = "C",
+    }
+}
+
+metric! {
+    ResponseEffort {
+        Low = "L",
+        Moderate = "M",
+        High = "H",
+    }
+}
+
+metric! {
+    ProviderUrgency {
+        Clear = "Clear",
+        Green = "Green",
+        Amber = "Amber",
+        Red = "Red",
+    }
+}
+
+/// A CVSS v4.0 vector.
+///
+/// Threat, environmental and supplemental metrics which are not defined are
+/// `None`. Modified metrics which are not defined take the value of the base
+/// metric. Supplemental metrics don't affect the score.
+#[derive(PartialEq, Eq, Hash, Clone, Debug)]
+pub struct CvssV4 {
+    pub attack_vector: AttackVector,
+    pub attack_complexity: AttackComplexity,
+    pub attack_requirements: AttackRequirements,
+    pub privileges_required: PrivilegesRequired,
+    pub user_interaction: UserInteraction,
+    pub vulnerable_confidentiality: Impact,
+    pub vulnerable_integrity: Impact,
+    pub vulnerable_availability: Impact,
+    pub subsequent_confidentiality: Impact,
+    pub subsequent_integrity: Impact,
+    pub subsequent_availability: Impact,
+    pub exploit_maturity: Option<ExploitMaturity>,
+    pub confidentiality_requirement: Option<Requirement>,
+    pub integrity_requirement: Option<Requirement>,
+    pub availability_requirement: Option<Requirement>,
+    pub modified_attack_vector: Option<AttackVector>,
+    pub modified_attack_complexity: Option<AttackComplexity>,
+    pub modified_attack_requirements: Option<AttackRequirements>,
+    pub modified_privileges_required: Option<PrivilegesRequired>,
+    pub modified_user_interaction: Option<UserInteraction>,
+    pub modified_vulnerable_confidentiality: Option<Impact>,
+    pub modified_vulnerable_integrity: Option<Impact>,
+    pub modified_vulnerable_availability: Option<Impact>,
+    pub modified_subsequent_confidentiality: Option<Impact>,
+    pub modified_subsequent_integrity: Option<SubsequentImpact>,
+    pub modified_subsequent_availability: Option<SubsequentImpact>,
+    pub safety: Option<Safety>,
+    pub automatable: Option<Automatable>,
+    pub recovery: Option<Recovery>,
+    pub value_density: Option<ValueDensity>,
+    pub response_effort: Option<ResponseEffort>,
+    pub provider_urgency: Option<ProviderUrgency>,
+}
+
+impl CvssV4 {
+    /// The score of the base metrics alone, the `CVSS-B` score.
+    pub fn base_score(&self) -> f64 {
+        let base = Self {
+            exploit_maturity: None,
+            confidentiality_requirement: None,
+            integrity_requirement: None,
+            availability_requirement: None,
+            modified_attack_vector: None,
+            modified_attack_complexity: None,
+            modified_attack_requirements: None,
+            modified_privileges_required: None,
+            modified_user_interaction: None,
+            modified_vulnerable_confidentiality: None,
+            modified_vulnerable_integrity: None,
+            modified_vulnerable_availability: None,
+            modified_subsequent_confidentiality: None,
+            modified_subsequent_integrity: None,
+            modified_subsequent_availability: None,
+            ..self.clone()
+        };
+        base.score()
+    }
+
+    /// The score of all metrics of the vector, see [`CvssV4::nomenclature`].
+    pub fn score(&self) -> f64 {
+        const EPSILON: f64 = 1e-6;
+        let score = score(&self.effective_metrics()).clamp(0., 10.);
+        ((score + EPSILON) * 10.).round() / 10.
+    }
+
+    /// The severity of [`CvssV4::score`].
+    pub fn risk_level(&self) -> RiskLevel {
+        RiskLevel::from_cvss_score(self.score())
+    }
+
+    /// The kind of score, `CVSS-B` for base metrics, `CVSS-BT` with threat
+    /// metrics, `CVSS-BE` with environmental metrics or `CVSS-BTE` with
+    /// both.
+    pub fn nomenclature(&self) -> &'static str {
+        let threat = self.exploit_maturity.is_some();
+        let environmental = self.confidentiality_requirement.is_some()
+            || self.integrity_requirement.is_some()
+            || self.availability_requirement.is_some()
+            || self.modified_attack_vector.is_some()
+            || self.modified_attack_complexity.is_some()
+            || self.modified_attack_requirements.is_some()
+            || self.modified_privileges_required.is_some()
+            || self.modified_user_interaction.is_some()
+            || self.modified_vulnerable_confidentiality.is_some()
+            || self.modified_vulnerable_integrity.is_some()
+            || self.modified_vulnerable_availability.is_some()
+            || self.modified_subsequent_confidentiality.is_some()
+            || self.modified_subsequent_integrity.is_some()
+            || self.modified_subsequent_availability.is_some();
+
+        match (threat, environmental) {
+            (false, false) => "CVSS-B",
+            (true, false) => "CVSS-BT",
+            (false, true) => "CVSS-BE",
+            (true, true) => "CVSS-BTE",
+        }
+    }
+
+    /// The values used for scoring by abbreviation, with modified metrics
+    /// applied and the worst case for undefined threat and environmental
+    /// metrics.
+    fn effective_metrics(&self) -> HashMap<&'static str, &'static str> {
+        fn effective<B: Metric, M: Metric>(base: B, modified: Option<M>) -> &'static str {
+            modified.map_or(base.code(), |modified| modified.code())
+        }
+
+        let mut metrics = HashMap::new();
+        metrics.insert(
+            "AV",
+            effective(self.attack_vector, self.modified_attack_vector),
+        );
+        metrics.insert(
+            "AC",
+            effective(self.attack_complexity, self.modified_attack_complexity),
+        );
+        metrics.insert(
+            "AT",
+            effective(self.attack_requirements, self.modified_attack_requirements),
+        );
+        metrics.insert(
+            "PR",
+            effective(self.privileges_required, self.modified_privileges_required),
+        );
+        metrics.insert(
+            "UI",
+            effective(self.user_interaction, self.modified_user_interaction),
+        );
+        metrics.insert(
+            "VC",
+            effective(
+                self.vulnerable_confidentiality,
+                self.modified_vulnerable_confidentiality,
+            ),
+        );
+        metrics.insert(
+            "VI",
+            effective(
+                self.vulnerable_integrity,
+                self.modified_vulnerable_integrity,
+            ),
+        );
+        metrics.insert(
+            "VA",
+            effective(
+                self.vulnerable_availability,
+                self.modified_vulnerable_availability,
+            ),
+        );
+        metrics.insert(
+            "SC",
+            effective(
+                self.subsequent_confidentiality,
+                self.modified_subsequent_confidentiality,
+            ),
+        );
+        metrics.insert(
+            "SI",
+            effective(
+                self.subsequent_integrity,
+                self.modified_subsequent_integrity,
+            ),
+        );
+        metrics.insert(
+            "SA",
+            effective(
+                self.subsequent_availability,
+                self.modified_subsequent_availability,
+            ),
+        );
+        metrics.insert(
+            "E",
+            self.exploit_maturity.map_or("A", |metric| metric.code()),
+        );
+        metrics.insert(
+            "CR",
+            self.confidentiality_requirement
+                .map_or("H", |metric| metric.code()),
+        );
+        metrics.insert(
+            "IR",
+            self.integrity_requirement
+                .map_or("H", |metric| metric.code()),
+        );
+        metrics.insert(
+            "AR",
+            self.availability_requirement
+                .map_or("H", |metric| metric.code()),
+        );
+        metrics
+    }
+}
+
+/// Score effective metrics with the algorithm of the reference calculator.
+fn score(m: &HashMap<&str, &str>) -> f64 {
+    let is = |key: &str, code: &str| m[key] == code;
+
+    if ["VC", "VI", "VA", "SC", "SI", "SA"]
+        .iter()
+        .all(|key| is(key, "N"))
+    {
+        return 0.;
+    }
+
+    let eq1 = if is("AV", "N") && is("PR", "N") && is("UI", "N") {
+        0
+    } else if (is("AV", "N") || is("PR", "N") || is("UI", "N")) && !is("AV", "P") {
+        1
+    } else {
+        2
+    };
+    let eq2 = if is("AC", "L") && is("AT", "N") { 0 } else { 1 };
+    let eq3 = if is("VC", "H") && is("VI", "H") {
+        0
+    } else if is("VC", "H") || is("VI", "H") || is("VA", "H") {
+        1
+    } else {
+        2
+    };
+    let eq4 = if is("SI", "S") || is("SA", "S") {
+        0
+    } else if is("SC", "H") || is("SI", "H") || is("SA", "H") {
+        1
+    } else {
+        2
+    };
+    let eq5 = match m["E"] {
+        "A" => 0,
+        "P" => 1,
+        _ => 2,
+    };
+    let eq6 = if (is("CR", "H") && is("VC", "H"))
+        || (is("IR", "H") && is("VI", "H"))
+        || (is("AR", "H") && is("VA", "H"))
+    {
+        0
+    } else {
+        1
+    };
+
+    let macro_vector = [eq1, eq2, eq3, eq4, eq5, eq6];
+    let value = lookup(macro_vector).expect("every macro vector has a score");
+
+    // Scores of the next lower macro vector for each equivalence set, if it
+    // exists.
+    let lower = |index: usize| {
+        let mut lower = macro_vector;
+        lower[index] += 1;
+        lookup(lower)
+    };
+    let lower_eq3_eq6 = match (eq3, eq6) {
+        (0, 0) => match (lower(5), lower(2)) {
+            (Some(left), Some(right)) => Some(f64::max(left, right)),
+            (left, right) => left.or(right),
+        },
+        (1, 0) => lower(5),
+        _ => lower(2),
+    };
+    let lower_scores = [lower(0), lower(1), lower_eq3_eq6, lower(3), lower(4)];
+
+    // Distance from the highest severity vector of the macro vector, using
+    // the first highest severity vector the scored vector is below of.
+    let max_vectors = max_vectors(macro_vector);
+    let mut distances = HashMap::new();
+    for max_vector in &max_vectors {
+        distances.clear();
+        for metric in max_vector.split('/') {
+            let (key, code) = metric.split_once(':').expect("valid max vector");
+            distances.insert(key, level(key, m[key]) - level(key, code));
+        }
+        if distances.values().all(|distance| *distance >= 0.) {
+            break;
+        }
+    }
+    let distance = |keys: &[&str]| keys.iter().map(|key| distances[key]).sum::<f64>();
+    let current = [
+        distance(&["AV", "PR", "UI"]),
+        distance(&["AC", "AT"]),
+        distance(&["VC", "VI", "VA", "CR", "IR", "AR"]),
+        distance(&["SC", "SI", "SA"]),
+        0.,
+    ];
+
+    let max_severity = max_severity(macro_vector);
+    let mut total = 0.;
+    let mut count = 0;
+    for ((lower, current), max_severity) in lower_scores.iter().zip(current).zip(max_severity) {
+        if let Some(lower) = lower {
+            total += (value - lower) * current / (max_severity as f64 * 0.1);
+            count += 1;
+        }
+    }
+
+    match count {
+        0 => value,
+        _ => value - total / count as f64,
+    }
+}
+
+/// Severity levels of metric values, lower is more severe.
+fn level(key: &str, code: &str) -> f64 {
+    match (key, code) {
+        ("AV", "N") | ("PR", "N") | ("UI", "N") | ("AC", "L") | ("AT", "N") => 0.,
+        ("AV", "A") | ("PR", "L") | ("UI", "P") | ("AC", "H") | ("AT", "P") => 0.1,
+        ("AV", "L") | ("PR", "H") | ("UI", "A") => 0.2,
+        ("AV", "P") => 0.3,
+        ("VC", "H") | ("VI", "H") | ("VA", "H") => 0.,
+        ("VC", "L") | ("VI", "L") | ("VA", "L") => 0.1,
+        ("VC", "N") | ("VI", "N") | ("VA", "N") => 0.2,
+        ("SI", "S") | ("SA", "S") => 0.,
+        ("SC", "H") | ("SI", "H") | ("SA", "H") => 0.1,
+        ("SC", "L") | ("SI", "L") | ("SA", "L") => 0.2,
+        ("SC", "N") | ("SI", "N") | ("SA", "N") => 0.3,
+        ("CR", "H") | ("IR", "H") | ("AR", "H") | ("E", "A") => 0.,
+        ("CR", "M") | ("IR", "M") | ("AR", "M") | ("E", "P") => 0.1,
+        ("CR", "L") | ("IR", "L") | ("AR", "L") | ("E", "U") => 0.2,
+        _ => unreachable!("unexpected metric {}:{}", key, code),
+    }
+}
+
+/// The highest severity vectors of a macro vector.
+fn max_vectors(macro_vector: [u8; 6]) -> Vec<String> {
+    let [eq1, eq2, eq3, eq4, eq5, eq6] = macro_vector;
+    let eq1: &[&str] = match eq1 {
+        0 => &["AV:N/PR:N/UI:N"],
+        1 => &["AV:A/PR:N/UI:N", "AV:N/PR:L/UI:N", "AV:N/PR:N/UI:P"],
+        _ => &["AV:P/PR:N/UI:N", "AV:A/PR:L/UI:P"],
+    };
+    let eq2: &[&str] = match eq2 {
+        0 => &["AC:L/AT:N"],
+        _ => &["AC:H/AT:N", "AC:L/AT:P"],
+    };
+    let eq3_eq6: &[&str] = match (eq3, eq6) {
+        (0, 0) => &["VC:H/VI:H/VA:H/CR:H/IR:H/AR:H"],
+        (0, _) => &[
+            "VC:H/VI:H/VA:L/CR:M/IR:M/AR:H",
+            "VC:H/VI:H/VA:H/CR:M/IR:M/AR:M",
+        ],
+        (1, 0) => &[
+            "VC:L/VI:H/VA:H/CR:H/IR:H/AR:H",
+            "VC:H/VI:L/VA:H/CR:H/IR:H/AR:H",
+        ],
+        (1, _) => &[
+            "VC:L/VI:H/VA:L/CR:H/IR:M/AR:H",
+            "VC:L/VI:H/VA:H/CR:H/IR:M/AR:M",
+            "VC:H/VI:L/VA:H/CR:M/IR:H/AR:M",
+            "VC:H/VI:L/VA:L/CR:M/IR:H/AR:H",
+            "VC:L/VI:L/VA:H/CR:H/IR:H/AR:M",
+        ],
+        _ => &["VC:L/VI:L/VA:L/CR:H/IR:H/AR:H"],
+    };
+    let eq4 = match eq4 {
+        0 => "SC:H/SI:S/SA:S",
+        1 => "SC:H/SI:H/SA:H",
+        _ => "SC:L/SI:L/SA:L",
+    };
+    let eq5 = match eq5 {
+        0 => "E:A",
+        1 => "E:P",
+        _ => "E:U",
+    };
+
+    let mut vectors = Vec::new();
+    for eq1 in eq1 {
+        for eq2 in eq2 {
+            for eq3_eq6 in eq3_eq6 {
+                vectors.push(format!("{eq1}/{eq2}/{eq3_eq6}/{eq4}/{eq5}"));
+            }
+        }
+    }
+    vectors
+}
+
+/// The depth of a macro vector for each equivalence set, in steps of `0.1`.
+fn max_severity(macro_vector: [u8; 6]) -> [u8; 5] {
+    let [eq1, eq2, eq3, eq4, _, eq6] = macro_vector;
+    [
+        [1, 4, 5][eq1 as usize],
+        [1, 2][eq2 as usize],
+        match (eq3, eq6) {
+            (0, 0) => 7,
+            (0, _) => 6,
+            (1, _) => 8,
+            _ => 10,
+        },
+        [6, 5, 4][eq4 as usize],
+        1,
+    ]
+}
+
+fn lookup(macro_vector: [u8; 6]) -> Option<f64> {
+    let key: String = macro_vector.iter().map(|eq| eq.to_string()).collect();
+    MACRO_VECTOR_SCORES
+        .iter()
+        .find(|(macro_vector, _)| *macro_vector == key)
+        .map(|(_, score)| *score)
+}
+
+/// Scores of macro vectors, from `cvss_lookup.js` of the reference
+/// calculator.
+#[rustfmt::skip]
+const MACRO_VECTOR_SCORES: [(&str, f64); 270] = [
+    ("000000", 10.0), ("000001", 9.9), ("000010", 9.8), ("000011", 9.5), ("000020", 9.5), ("000021", 9.2),
+    ("000100", 10.0), ("000101", 9.6), ("000110", 9.3), ("000111", 8.7), ("000120", 9.1), ("000121", 8.1),
+    ("000200", 9.3), ("000201", 9.0), ("000210", 8.9), ("000211", 8.0), ("000220", 8.1), ("000221", 6.8),
+    ("001000", 9.8), ("001001", 9.5), ("001010", 9.5), ("001011", 9.2), ("001020", 9.0), ("001021", 8.4),
+    ("001100", 9.3), ("001101", 9.2), ("001110", 8.9), ("001111", 8.1), ("001120", 8.1), ("001121", 6.5),
+    ("001200", 8.8), ("001201", 8.0), ("001210", 7.8), ("001211", 7.0), ("001220", 6.9), ("001221", 4.8),
+    ("002001", 9.2), ("002011", 8.2), ("002021", 7.2), ("002101", 7.9), ("002111", 6.9), ("002121", 5.0),
+    ("002201", 6.9), ("002211", 5.5), ("002221", 2.7), ("010000", 9.9), ("010001", 9.7), ("010010", 9.5),
+    ("010011", 9.2), ("010020", 9.2), ("010021", 8.5), ("010100", 9.5), ("010101", 9.1), ("010110", 9.0),
+    ("010111", 8.3), ("010120", 8.4), ("010121", 7.1), ("010200", 9.2), ("010201", 8.1), ("010210", 8.2),
+    ("010211", 7.1), ("010220", 7.2), ("010221", 5.3), ("011000", 9.5), ("011001", 9.3), ("011010", 9.2),
+    ("011011", 8.5), ("011020", 8.5), ("011021", 7.3), ("011100", 9.2), ("011101", 8.2), ("011110", 8.0),
+    ("011111", 7.2), ("011120", 7.0), ("011121", 5.9), ("011200", 8.4), ("011201", 7.0), ("011210", 7.1),
+    ("011211", 5.2), ("011220", 5.0), ("011221", 3.0), ("012001", 8.6), ("012011", 7.5), ("012021", 5.2),
+    ("012101", 7.1), ("012111", 5.2), ("012121", 2.9), ("012201", 6.3), ("012211", 2.9), ("012221", 1.7),
+    ("100000", 9.8), ("100001", 9.5), ("100010", 9.4), ("100011", 8.7), ("100020", 9.1), ("100021", 8.1),
+    ("100100", 9.4), ("100101", 8.9), ("100110", 8.6), ("100111", 7.4), ("100120", 7.7), ("100121", 6.4),
+    ("100200", 8.7), ("100201", 7.5), ("100210", 7.4), ("100211", 6.3), ("100220", 6.3), ("100221", 4.9),
+    ("101000", 9.4), ("101001", 8.9), ("101010", 8.8), ("101011", 7.7), ("101020", 7.6), ("101021", 6.7),
+    ("101100", 8.6), ("101101", 7.6), ("101110", 7.4), ("101111", 5.8), ("101120", 5.9), ("101121", 5.0),
+    ("101200", 7.2), ("101201", 5.7), ("101210", 5.7), ("101211", 5.2), ("101220", 5.2), ("101221", 2.5),
+    ("102001", 8.3), ("102011", 7.0), ("102021", 5.4), ("102101", 6.5), ("102111", 5.8), ("102121", 2.6),
+    ("102201", 5.3), ("102211", 2.1), ("102221", 1.3), ("110000", 9.5), ("110001", 9.0), ("110010", 8.8),
+    ("110011", 7.6), ("110020", 7.6), ("110021", 7.0), ("110100", 9.0), ("110101", 7.7), ("110110", 7.5),
+    ("110111", 6.2), ("110120", 6.1), ("110121", 5.3), ("110200", 7.7), ("110201", 6.6), ("110210", 6.8),
+    ("110211", 5.9), ("110220", 5.2), ("110221", 3.0), ("111000", 8.9), ("111001", 7.8), ("111010", 7.6),
+    ("111011", 6.7), ("111020", 6.2), ("111021", 5.8), ("111100", 7.4), ("111101", 5.9), ("111110", 5.7),
+    ("111111", 5.7), ("111120", 4.7), ("111121", 2.3), ("111200", 6.1), ("111201", 5.2), ("111210", 5.7),
+    ("111211", 2.9), ("111220", 2.4), ("111221", 1.6), ("112001", 7.1), ("112011", 5.9), ("112021", 3.0),
+    ("112101", 5.8), ("112111", 2.6), ("112121", 1.5), ("112201", 2.3), ("112211", 1.3), ("112221", 0.6),
+    ("200000", 9.3), ("200001", 8.7), ("200010", 8.6), ("200011", 7.2), ("200020", 7.5), ("200021", 5.8),
+    ("200100", 8.6), ("200101", 7.4), ("200110", 7.4), ("200111", 6.1), ("200120", 5.6), ("200121", 3.4),
+    ("200200", 7.0), ("200201", 5.4), ("200210", 5.2), ("200211", 4.0), ("200220", 4.0), ("200221", 2.2),
+    ("201000", 8.5), ("201001", 7.5), ("201010", 7.4), ("201011", 5.5), ("201020", 6.2), ("201021", 5.1),
+    ("201100", 7.2), ("201101", 5.7), ("201110", 5.5), ("201111", 4.1), ("201120", 4.6), ("201121", 1.9),
+    ("201200", 5.3), ("201201", 3.6), ("201210", 3.4), ("201211", 1.9), ("201220", 1.9), ("201221", 0.8),
+    ("202001", 6.4), ("202011", 5.1), ("202021", 2.0), ("202101", 4.7), ("202111", 2.1), ("202121", 1.1),
+    ("202201", 2.4), ("202211", 0.9), ("202221", 0.4), ("210000", 8.8), ("210001", 7.5), ("210010", 7.3),
+    ("210011", 5.3), ("210020", 6.0), ("210021", 5.0), ("210100", 7.3), ("210101", 5.5), ("210110", 5.9),
+    ("210111", 4.0), ("210120", 4.1), ("210121", 2.0), ("210200", 5.4), ("210201", 4.3), ("210210", 4.5),
+    ("210211", 2.2), ("210220", 2.0), ("210221", 1.1), ("211000", 7.5), ("211001", 5.5), ("211010", 5.8),
+    ("211011", 4.5), ("211020", 4.0), ("211021", 2.1), ("211100", 6.1), ("211101", 5.1), ("211110", 4.8),
+    ("211111", 1.8), ("211120", 2.0), ("211121", 0.9), ("211200", 4.6), ("211201", 1.8), ("211210", 1.7),
+    ("211211", 0.7), ("211220", 0.8), ("211221", 0.2), ("212001", 5.3), ("212011", 2.4), ("212021", 1.4),
+    ("212101", 2.4), ("212111", 1.2), ("212121", 0.5), ("212201", 1.0), ("212211", 0.3), ("212221", 0.1),
+];
+
+impl FromStr for CvssV4 {
+    type Err = Error;
+
+    fn from_str(vector: &str) -> Result<Self, Self::Err> {
+        let (_, mut metrics) = Metrics::parse(vector, &["CVSS:4.0"])?;
+        let parsed = Self {
+            attack_vector: metrics.required("AV")?,
+            attack_complexity: metrics.required("AC")?,
+            attack_requirements: metrics.required("AT")?,
+            privileges_required: metrics.required("PR")?,
+            user_interaction: metrics.required("UI")?,
+            vulnerable_confidentiality: metrics.required("VC")?,
+            vulnerable_integrity: metrics.required("VI")?,
+            vulnerable_availability: metrics.required("VA")?,
+            subsequent_confidentiality: metrics.required("SC")?,
+            subsequent_integrity: metrics.required("SI")?,
+            subsequent_availability: metrics.required("SA")?,
+            exploit_maturity: metrics.optional("E")?,
+            confidentiality_requirement: metrics.optional("CR")?,
+            integrity_requirement: metrics.optional("IR")?,
+            availability_requirement: metrics.optional("AR")?,
+            modified_attack_vector: metrics.optional("MAV")?,
+            modified_attack_complexity: metrics.optional("MAC")?,
+            modified_attack_requirements: metrics.optional("MAT")?,
+            modified_privileges_required: metrics.optional("MPR")?,
+            modified_user_interaction: metrics.optional("MUI")?,
+            modified_vulnerable_confidentiality: metrics.optional("MVC")?,
+            modified_vulnerable_integrity: metrics.optional("MVI")?,
+            modified_vulnerable_availability: metrics.optional("MVA")?,
+            modified_subsequent_confidentiality: metrics.optional("MSC")?,
+            modified_subsequent_integrity: metrics.optional("MSI")?,
+            modified_subsequent_availability: metrics.optional("MSA")?,
+            safety: metrics.optional("S")?,
+            automatable: metrics.optional("AU")?,
+            recovery: metrics.optional("R")?,
+            value_density: metrics.optional("V")?,
+            response_effort: metrics.optional("RE")?,
+            provider_urgency: metrics.optional("U")?,
+        };
+        metrics.finish()?;
+        Ok(parsed)
+    }
+}
+
+/// Write the vector with its defined metrics in the order of the
+/// specification.
+impl fmt::Display for CvssV4 {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("CVSS:4.0")?;
+        write_metric(f, "AV", Some(self.attack_vector))?;
+        write_metric(f, "AC", Some(self.attack_complexity))?;
+        write_metric(f, "AT", Some(self.attack_requirements))?;
+        write_metric(f, "PR", Some(self.privileges_required))?;
+        write_metric(f, "UI", Some(self.user_interaction))?;
+        write_metric(f, "VC", Some(self.vulnerable_confidentiality))?;
+        write_metric(f, "VI", Some(self.vulnerable_integrity))?;
+        write_metric(f, "VA", Some(self.vulnerable_availability))?;
+        write_metric(f, "SC", Some(self.subsequent_confidentiality))?;
+        write_metric(f, "SI", Some(self.subsequent_integrity))?;
+        write_metric(f, "SA", Some(self.subsequent_availability))?;
+        write_metric(f, "E", self.exploit_maturity)?;
+        write_metric(f, "CR", self.confidentiality_requirement)?;
+        write_metric(f, "IR", self.integrity_requirement)?;
+        write_metric(f, "AR", self.availability_requirement)?;
+        write_metric(f, "MAV", self.modified_attack_vector)?;
+        write_metric(f, "MAC", self.modified_attack_complexity)?;
+        write_metric(f, "MAT", self.modified_attack_requirements)?;
+        write_metric(f, "MPR", self.modified_privileges_required)?;
+        write_metric(f, "MUI", self.modified_user_interaction)?;
+        write_metric(f, "MVC", self.modified_vulnerable_confidentiality)?;
+        write_metric(f, "MVI", self.modified_vulnerable_integrity)?;
+        write_metric(f, "MVA", self.modified_vulnerable_availability)?;
+        write_metric(f, "MSC", self.modified_subsequent_confidentiality)?;
+        write_metric(f, "MSI", self.modified_subsequent_integrity)?;
+        write_metric(f, "MSA", self.modified_subsequent_availability)?;
+        write_metric(f, "S", self.safety)?;
+        write_metric(f, "AU", self.automatable)?;
+        write_metric(f, "R", self.recovery)?;
+        write_metric(f, "V", self.value_density)?;
+        write_metric(f, "RE", self.response_effort)?;
+        write_metric(f, "U", self.provider_urgency)
+    }
+}
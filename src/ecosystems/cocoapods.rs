@@ -0,0 +1,102 @@
+// This is a reference for the CocoaPods lockfile.
+// https://guides.cocoapods.org/using/using-cocoapods.html#what-is-podfilelock
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::Error;
+use crate::types::package::{PackageDescriptor, PackageType};
+
+/// A `Podfile.lock` file.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING-KEBAB-CASE")]
+pub struct PodfileLock {
+    #[serde(rename = "PODS", default)]
+    pub pods: Vec<PodEntry>,
+    #[serde(default)]
+    pub dependencies: Vec<String>,
+    #[serde(rename = "SPEC REPOS", default)]
+    pub spec_repos: BTreeMap<String, Vec<String>>,
+    #[serde(rename = "EXTERNAL SOURCES", default)]
+    pub external_sources: BTreeMap<String, BTreeMap<String, String>>,
+    #[serde(rename = "SPEC CHECKSUMS", default)]
+    pub spec_checksums: BTreeMap<String, String>,
+    #[serde(rename = "PODFILE CHECKSUM")]
+    pub podfile_checksum: Option<String>,
+    #[serde(rename = "COCOAPODS")]
+    pub cocoapods: Option<String>,
+}
+
+impl PodfileLock {
+    /// Descriptors of all pods fetched from a spec repository.
+    ///
+    /// Subspecs are collapsed into their root spec, and pods from external
+    /// sources like git or local paths are skipped.
+    pub fn packages(&self) -> Vec<PackageDescriptor> {
+        let mut packages = BTreeSet::new();
+        for entry in &self.pods {
+            let (name, version) = match parse_pod(entry.pod()) {
+                Some(pod) => pod,
+                None => continue,
+            };
+
+            let root = name.split('/').next().unwrap_or(name);
+            if self.external_sources.contains_key(root) {
+                continue;
+            }
+
+            packages.insert(PackageDescriptor {
+                name: root.to_owned(),
+                version: version.to_owned(),
+                package_type: PackageType::CocoaPods,
+            });
+        }
+        packages.into_iter().collect()
+    }
+}
+
+impl FromStr for PodfileLock {
+    type Err = Error;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        serde_yaml::from_str(input).map_err(|err| Error::InvalidLockfile {
+            lockfile: "Podfile.lock",
+            reason: err.to_string(),
+        })
+    }
+}
+
+/// An entry in the `PODS` section, optionally listing the pod's own
+/// dependencies.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum PodEntry {
+    Pod(String),
+    WithDependencies(BTreeMap<String, Vec<String>>),
+}
+
+impl PodEntry {
+    /// The `Name (version)` string of this pod.
+    pub fn pod(&self) -> &str {
+        match self {
+            PodEntry::Pod(pod) => pod,
+            PodEntry::WithDependencies(pod) => pod.keys().next().map_or("", String::as_str),
+        }
+    }
+
+    /// The dependencies of this pod, in `Name (requirement)` form.
+    pub fn dependencies(&self) -> &[String] {
+        match self {
+            PodEntry::Pod(_) => &[],
+            PodEntry::WithDependencies(pod) => pod.values().next().map_or(&[], Vec::as_slice),
+        }
+    }
+}
+
+/// Split `Name (1.2.3)` into its name and version.
+fn parse_pod(pod: &str) -> Option<(&str, &str)> {
+    let (name, version) = pod.split_once(" (")?;
+    Some((name.trim(), version.strip_suffix(')')?.trim()))
+}
@@ -0,0 +1,81 @@
+// This is a reference for the Composer lockfile.
+// https://getcomposer.org/doc/01-basic-usage.md#commit-your-composer-lock-file-to-version-control
+
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::Error;
+use crate::types::package::{PackageDescriptor, PackageType};
+
+/// A `composer.lock` file.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ComposerLock {
+    #[serde(rename = "content-hash")]
+    pub content_hash: Option<String>,
+    #[serde(default)]
+    pub packages: Vec<ComposerPackage>,
+    #[serde(rename = "packages-dev")]
+    pub packages_dev: Option<Vec<ComposerPackage>>,
+    #[serde(rename = "minimum-stability")]
+    pub minimum_stability: Option<String>,
+}
+
+impl ComposerLock {
+    /// Descriptors of all locked packages, including development packages.
+    pub fn packages(&self) -> Vec<PackageDescriptor> {
+        self.packages
+            .iter()
+            .chain(self.packages_dev.iter().flatten())
+            .map(ComposerPackage::package_descriptor)
+            .collect()
+    }
+}
+
+impl FromStr for ComposerLock {
+    type Err = Error;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        serde_json::from_str(input).map_err(|err| Error::InvalidLockfile {
+            lockfile: "composer.lock",
+            reason: err.to_string(),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ComposerPackage {
+    /// Package name in `vendor/package` form.
+    pub name: String,
+    pub version: String,
+    pub source: Option<ComposerSource>,
+    pub dist: Option<ComposerSource>,
+    #[serde(default)]
+    pub require: HashMap<String, String>,
+    #[serde(rename = "require-dev", default)]
+    pub require_dev: HashMap<String, String>,
+    #[serde(rename = "type")]
+    pub package_type: Option<String>,
+    #[serde(default)]
+    pub license: Vec<String>,
+}
+
+impl ComposerPackage {
+    pub fn package_descriptor(&self) -> PackageDescriptor {
+        PackageDescriptor {
+            name: self.name.clone(),
+            version: self.version.clone(),
+            package_type: PackageType::Composer,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ComposerSource {
+    #[serde(rename = "type")]
+    pub source_type: String,
+    pub url: String,
+    pub reference: Option<String>,
+    pub shasum: Option<String>,
+}
@@ -0,0 +1,208 @@
+// This is a reference for conda environment files and explicit lockfiles.
+// https://conda.io/projects/conda/en/latest/user-guide/tasks/manage-environments.html
+
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::Error;
+use crate::types::package::{PackageDescriptor, PackageType};
+
+/// An `environment.yml` file.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CondaEnvironment {
+    pub name: Option<String>,
+    #[serde(default)]
+    pub channels: Vec<String>,
+    #[serde(default)]
+    pub dependencies: Vec<CondaDependency>,
+}
+
+impl CondaEnvironment {
+    /// Descriptors of all dependencies pinned to an exact version.
+    ///
+    /// Conda specs like `numpy==1.21.0` produce conda packages, while entries
+    /// in the `pip` section like `requests==2.26.0` produce PyPI packages.
+    /// Dependencies with a version range can't be resolved without a solver
+    /// and are skipped.
+    pub fn packages(&self) -> Vec<PackageDescriptor> {
+        let mut packages = Vec::new();
+        for dependency in &self.dependencies {
+            match dependency {
+                CondaDependency::Spec(spec) => {
+                    if let Some((name, version)) = parse_conda_spec(spec) {
+                        packages.push(PackageDescriptor {
+                            name: name.into(),
+                            version: version.into(),
+                            package_type: PackageType::Conda,
+                        });
+                    }
+                }
+                CondaDependency::Pip { pip } => {
+                    packages.extend(pip.iter().filter_map(|spec| {
+                        let (name, version) = spec.split_once("==")?;
+                        Some(PackageDescriptor {
+                            name: name.trim().into(),
+                            version: version.trim().into(),
+                            package_type: PackageType::PyPi,
+                        })
+                    }));
+                }
+            }
+        }
+        packages
+    }
+}
+
+impl FromStr for CondaEnvironment {
+    type Err = Error;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        serde_yaml::from_str(input).map_err(|err| Error::InvalidLockfile {
+            lockfile: "conda environment",
+            reason: err.to_string(),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum CondaDependency {
+    /// A match spec like `conda-forge::numpy=1.21.0=py39h_0`.
+    Spec(String),
+    Pip {
+        pip: Vec<String>,
+    },
+}
+
+/// Extract name and exact version from a conda match spec.
+///
+/// `numpy==1.21.0` and `numpy=1.21.0=py39h_0` are exact, but without a build
+/// string a single `=` is a prefix match: `numpy=1.21` also matches
+/// `1.21.5`.
+fn parse_conda_spec(spec: &str) -> Option<(&str, &str)> {
+    let spec = spec.rsplit("::").next()?.trim();
+    let operator = spec.find(['=', '<', '>', '!', '~', ' '])?;
+    let (name, rest) = spec.split_at(operator);
+    let (equals, rest) = match rest.strip_prefix("==") {
+        Some(rest) => (true, rest),
+        None => (false, rest.strip_prefix('=')?),
+    };
+    let (version, build) = rest.split_once('=').unwrap_or((rest, ""));
+    let version = version.trim();
+
+    let exact = (equals || !build.trim().is_empty())
+        && !version.is_empty()
+        && !version.contains(['*', '<', '>', ',', '|', '!', '~']);
+    exact.then_some((name, version))
+}
+
+/// An explicit lockfile, as written by `conda list --explicit`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CondaExplicitLock {
+    pub platform: Option<String>,
+    pub packages: Vec<CondaExplicitPackage>,
+}
+
+impl CondaExplicitLock {
+    /// Descriptors of all locked packages.
+    pub fn packages(&self) -> Vec<PackageDescriptor> {
+        self.packages
+            .iter()
+            .map(|package| PackageDescriptor {
+                name: package.name.clone(),
+                version: package.version.clone(),
+                package_type: PackageType::Conda,
+            })
+            .collect()
+    }
+}
+
+impl FromStr for CondaExplicitLock {
+    type Err = Error;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let mut lock = CondaExplicitLock::default();
+        let mut explicit = false;
+
+        for line in input.lines().map(str::trim) {
+            if let Some(platform) = line.strip_prefix("# platform:") {
+                lock.platform = Some(platform.trim().into());
+            } else if line == "@EXPLICIT" {
+                explicit = true;
+            } else if !line.is_empty() && !line.starts_with('#') {
+                if !explicit {
+                    return Err(missing_marker());
+                }
+                lock.packages.push(line.parse()?);
+            }
+        }
+
+        if !explicit {
+            return Err(missing_marker());
+        }
+
+        Ok(lock)
+    }
+}
+
+fn missing_marker() -> Error {
+    Error::InvalidLockfile {
+        lockfile: "conda lockfile",
+        reason: String::from("missing @EXPLICIT marker"),
+    }
+}
+
+/// A package URL in an explicit lockfile, like
+/// `https://conda.anaconda.org/conda-forge/linux-64/numpy-1.21.0-py39h_0.tar.bz2#<md5>`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CondaExplicitPackage {
+    pub url: String,
+    /// The channel URL, without the platform subdirectory.
+    pub channel: String,
+    pub subdir: String,
+    pub name: String,
+    pub version: String,
+    pub build: String,
+    pub md5: Option<String>,
+}
+
+impl FromStr for CondaExplicitPackage {
+    type Err = Error;
+
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        let invalid = || Error::InvalidLockfile {
+            lockfile: "conda lockfile",
+            reason: format!("malformed package URL {line:?}"),
+        };
+
+        let (url, md5) = match line.split_once('#') {
+            Some((url, md5)) => (url, Some(md5.to_owned())),
+            None => (line, None),
+        };
+
+        let (base, filename) = url.rsplit_once('/').ok_or_else(invalid)?;
+        let (channel, subdir) = base.rsplit_once('/').ok_or_else(invalid)?;
+        let stem = filename
+            .strip_suffix(".tar.bz2")
+            .or_else(|| filename.strip_suffix(".conda"))
+            .ok_or_else(invalid)?;
+
+        // Package names may contain dashes, but versions and builds can't.
+        let mut parts = stem.rsplitn(3, '-');
+        let (build, version, name) = match (parts.next(), parts.next(), parts.next()) {
+            (Some(build), Some(version), Some(name)) => (build, version, name),
+            _ => return Err(invalid()),
+        };
+
+        Ok(CondaExplicitPackage {
+            url: url.into(),
+            channel: channel.into(),
+            subdir: subdir.into(),
+            name: name.into(),
+            version: version.into(),
+            build: build.into(),
+            md5,
+        })
+    }
+}
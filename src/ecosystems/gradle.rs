@@ -0,0 +1,324 @@
+// This is a reference for the Gradle dependency lockfile and version catalog.
+// https://docs.gradle.org/current/userguide/dependency_locking.html
+// https://docs.gradle.org/current/userguide/platforms.html
+
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::Error;
+use crate::types::package::{PackageDescriptor, PackageType};
+
+/// A dependency resolved by Gradle, together with the configurations which
+/// resolved it.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct GradleDependency {
+    #[serde(flatten)]
+    pub package_descriptor: PackageDescriptor,
+    #[serde(default)]
+    pub configurations: Vec<String>,
+}
+
+impl GradleDependency {
+    /// Check if the dependency is only used by test configurations.
+    pub fn is_test_only(&self) -> bool {
+        !self.configurations.is_empty()
+            && self.configurations.iter().all(|c| is_test_configuration(c))
+    }
+
+    /// Check if the dependency was resolved by the given configuration.
+    pub fn in_configuration(&self, configuration: &str) -> bool {
+        self.configurations.iter().any(|c| c == configuration)
+    }
+}
+
+/// Check if a configuration belongs to a test source set, like
+/// `testRuntimeClasspath` or `integrationTestCompileClasspath`.
+pub fn is_test_configuration(configuration: &str) -> bool {
+    configuration.starts_with("test") || configuration.contains("Test")
+}
+
+/// A `gradle.lockfile`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct GradleLockfile {
+    pub dependencies: Vec<GradleDependency>,
+    /// Configurations which did not resolve any dependencies.
+    #[serde(default)]
+    pub empty: Vec<String>,
+}
+
+impl GradleLockfile {
+    /// Descriptors of all locked dependencies.
+    pub fn packages(&self) -> Vec<PackageDescriptor> {
+        self.dependencies
+            .iter()
+            .map(|dep| dep.package_descriptor.clone())
+            .collect()
+    }
+
+    /// Descriptors of all locked dependencies which are used outside of test
+    /// configurations.
+    pub fn non_test_packages(&self) -> Vec<PackageDescriptor> {
+        self.dependencies
+            .iter()
+            .filter(|dep| !dep.is_test_only())
+            .map(|dep| dep.package_descriptor.clone())
+            .collect()
+    }
+}
+
+impl FromStr for GradleLockfile {
+    type Err = Error;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let mut lockfile = GradleLockfile::default();
+
+        for (i, line) in input.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let invalid = |reason: String| Error::InvalidLockfile {
+                lockfile: "gradle.lockfile",
+                reason: format!("{reason} on line {}", i + 1),
+            };
+
+            let (coordinates, configurations) = line
+                .split_once('=')
+                .ok_or_else(|| invalid(String::from("missing configurations")))?;
+            let configurations = split_configurations(configurations);
+
+            if coordinates == "empty" {
+                lockfile.empty = configurations;
+                continue;
+            }
+
+            let mut parts = coordinates.split(':');
+            let (group, artifact, version) = match (parts.next(), parts.next(), parts.next()) {
+                (Some(group), Some(artifact), Some(version)) if parts.next().is_none() => {
+                    (group, artifact, version)
+                }
+                _ => {
+                    return Err(invalid(format!(
+                        "malformed dependency coordinates {coordinates:?}"
+                    )))
+                }
+            };
+
+            lockfile.dependencies.push(GradleDependency {
+                package_descriptor: maven_descriptor(group, artifact, version),
+                configurations,
+            });
+        }
+
+        Ok(lockfile)
+    }
+}
+
+fn split_configurations(configurations: &str) -> Vec<String> {
+    configurations
+        .split(',')
+        .map(str::trim)
+        .filter(|c| !c.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+fn maven_descriptor(group: &str, artifact: &str, version: &str) -> PackageDescriptor {
+    PackageDescriptor {
+        name: format!("{group}:{artifact}"),
+        version: version.into(),
+        package_type: PackageType::Maven,
+    }
+}
+
+/// A `libs.versions.toml` version catalog.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct VersionCatalog {
+    #[serde(default)]
+    pub versions: HashMap<String, VersionDeclaration>,
+    #[serde(default)]
+    pub libraries: HashMap<String, LibraryDeclaration>,
+    #[serde(default)]
+    pub bundles: HashMap<String, Vec<String>>,
+}
+
+impl VersionCatalog {
+    /// Descriptors of all libraries with a declared version.
+    ///
+    /// Libraries without a version are expected to be constrained by a
+    /// platform and are skipped.
+    pub fn packages(&self) -> Result<Vec<PackageDescriptor>, Error> {
+        let mut aliases: Vec<&String> = self.libraries.keys().collect();
+        aliases.sort();
+
+        let mut packages = Vec::new();
+        for alias in aliases {
+            if let Some(package) = self.library(alias)? {
+                packages.push(package);
+            }
+        }
+        Ok(packages)
+    }
+
+    /// Resolve a library alias, as used in bundles or `libs.<alias>`
+    /// accessors.
+    pub fn library(&self, alias: &str) -> Result<Option<PackageDescriptor>, Error> {
+        let alias = normalize_alias(alias);
+        let library = self
+            .libraries
+            .iter()
+            .find(|(name, _)| normalize_alias(name) == alias)
+            .map(|(_, library)| library)
+            .ok_or_else(|| invalid_catalog(format!("unknown library alias {alias:?}")))?;
+
+        let (group, artifact, version) = match library {
+            LibraryDeclaration::Notation(notation) => {
+                let mut parts = notation.splitn(3, ':');
+                match (parts.next(), parts.next(), parts.next()) {
+                    (Some(group), Some(artifact), version) => {
+                        (group, artifact, version.map(String::from))
+                    }
+                    _ => {
+                        return Err(invalid_catalog(format!(
+                            "malformed library notation {notation:?}"
+                        )))
+                    }
+                }
+            }
+            LibraryDeclaration::Module { module, version } => {
+                let (group, artifact) = module.split_once(':').ok_or_else(|| {
+                    invalid_catalog(format!("malformed library module {module:?}"))
+                })?;
+                (group, artifact, self.resolve_version(version.as_ref())?)
+            }
+            LibraryDeclaration::Coordinates {
+                group,
+                name,
+                version,
+            } => (
+                group.as_str(),
+                name.as_str(),
+                self.resolve_version(version.as_ref())?,
+            ),
+        };
+
+        Ok(version.map(|version| maven_descriptor(group, artifact, &version)))
+    }
+
+    /// Resolve all libraries in a bundle.
+    pub fn bundle(&self, name: &str) -> Result<Vec<PackageDescriptor>, Error> {
+        let name = normalize_alias(name);
+        let bundle = self
+            .bundles
+            .iter()
+            .find(|(bundle, _)| normalize_alias(bundle) == name)
+            .map(|(_, bundle)| bundle)
+            .ok_or_else(|| invalid_catalog(format!("unknown bundle {name:?}")))?;
+
+        let mut packages = Vec::new();
+        for alias in bundle {
+            if let Some(package) = self.library(alias)? {
+                packages.push(package);
+            }
+        }
+        Ok(packages)
+    }
+
+    fn resolve_version(
+        &self,
+        version: Option<&VersionDeclaration>,
+    ) -> Result<Option<String>, Error> {
+        let rich = match version {
+            None => return Ok(None),
+            Some(VersionDeclaration::Simple(version)) => return Ok(Some(version.clone())),
+            Some(VersionDeclaration::Rich(rich)) => rich,
+        };
+
+        match &rich.reference {
+            Some(reference) => {
+                let version = self.versions.get(reference).ok_or_else(|| {
+                    invalid_catalog(format!("unknown version reference {reference:?}"))
+                })?;
+                match version {
+                    // Versions can't reference other versions.
+                    VersionDeclaration::Rich(RichVersion {
+                        reference: Some(_), ..
+                    }) => Err(invalid_catalog(format!(
+                        "nested version reference {reference:?}"
+                    ))),
+                    version => self.resolve_version(Some(version)),
+                }
+            }
+            None => Ok(rich.preferred()),
+        }
+    }
+}
+
+impl FromStr for VersionCatalog {
+    type Err = Error;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        toml::from_str(input).map_err(|err| invalid_catalog(err.to_string()))
+    }
+}
+
+fn invalid_catalog(reason: String) -> Error {
+    Error::InvalidLockfile {
+        lockfile: "version catalog",
+        reason,
+    }
+}
+
+/// Gradle treats `-`, `_` and `.` in aliases as equivalent separators.
+fn normalize_alias(alias: &str) -> String {
+    alias.replace(['-', '_'], ".")
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum VersionDeclaration {
+    Simple(String),
+    Rich(RichVersion),
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct RichVersion {
+    #[serde(rename = "ref")]
+    pub reference: Option<String>,
+    pub strictly: Option<String>,
+    pub require: Option<String>,
+    pub prefer: Option<String>,
+    #[serde(default)]
+    pub reject: Vec<String>,
+    #[serde(rename = "rejectAll", default)]
+    pub reject_all: bool,
+}
+
+impl RichVersion {
+    /// The version Gradle would select without any other constraints.
+    pub fn preferred(&self) -> Option<String> {
+        self.prefer
+            .clone()
+            .or_else(|| self.strictly.clone())
+            .or_else(|| self.require.clone())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum LibraryDeclaration {
+    /// `"group:artifact:version"` shorthand.
+    Notation(String),
+    Module {
+        module: String,
+        version: Option<VersionDeclaration>,
+    },
+    Coordinates {
+        group: String,
+        name: String,
+        version: Option<VersionDeclaration>,
+    },
+}
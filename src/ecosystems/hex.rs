@@ -0,0 +1,412 @@
+// This is a reference for the Mix lockfile used with the Hex package manager.
+// https://hexdocs.pm/mix/Mix.Tasks.Deps.html
+//
+// `mix.lock` is an Elixir map literal, so it is parsed with a small reader for
+// the subset of Elixir terms Mix writes.
+
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::Error;
+use crate::types::package::{PackageDescriptor, PackageType};
+
+/// A `mix.lock` file.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct MixLock {
+    pub dependencies: BTreeMap<String, MixDependency>,
+}
+
+impl MixLock {
+    /// Descriptors of all dependencies fetched from a Hex repository.
+    pub fn packages(&self) -> Vec<PackageDescriptor> {
+        self.dependencies
+            .values()
+            .filter_map(|dependency| match dependency {
+                MixDependency::Hex { name, version, .. } => Some(PackageDescriptor {
+                    name: name.clone(),
+                    version: version.clone(),
+                    package_type: PackageType::Hex,
+                }),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+impl FromStr for MixLock {
+    type Err = Error;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        parse_mix_lock(input).map_err(|reason| Error::InvalidLockfile {
+            lockfile: "mix.lock",
+            reason,
+        })
+    }
+}
+
+fn parse_mix_lock(input: &str) -> Result<MixLock, String> {
+    let mut reader = TermReader {
+        input,
+        pos: 0,
+        depth: 0,
+    };
+    let term = reader.term()?;
+    reader.skip_whitespace();
+    if reader.pos != input.len() {
+        return Err(reader.error("trailing data"));
+    }
+
+    let entries = match term {
+        Term::Map(entries) => entries,
+        _ => return Err(String::from("expected a map")),
+    };
+
+    let mut dependencies = BTreeMap::new();
+    for (key, value) in entries {
+        let key = key
+            .as_atom()
+            .or_else(|| key.as_str())
+            .ok_or_else(|| String::from("expected package name keys"))?;
+        dependencies.insert(key.to_owned(), MixDependency::from_term(value)?);
+    }
+
+    Ok(MixLock { dependencies })
+}
+
+/// A single locked dependency.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(tag = "scm", rename_all = "lowercase")]
+pub enum MixDependency {
+    Hex {
+        name: String,
+        version: String,
+        inner_checksum: String,
+        managers: Vec<String>,
+        dependencies: Vec<MixRequirement>,
+        repo: String,
+        outer_checksum: Option<String>,
+    },
+    Git {
+        url: String,
+        revision: String,
+    },
+}
+
+impl MixDependency {
+    fn from_term(term: Term) -> Result<Self, String> {
+        let mut items = match term {
+            Term::Tuple(items) => items.into_iter(),
+            _ => return Err(String::from("expected a tuple")),
+        };
+
+        match items.next().as_ref().and_then(Term::as_atom) {
+            Some("hex") => {
+                let name = next_atom(&mut items)?;
+                let version = next_str(&mut items)?;
+                let inner_checksum = next_str(&mut items)?;
+                let managers = match items.next() {
+                    Some(Term::List(managers)) => managers
+                        .iter()
+                        .filter_map(|manager| manager.as_atom().map(String::from))
+                        .collect(),
+                    _ => Vec::new(),
+                };
+                let dependencies = match items.next() {
+                    Some(Term::List(dependencies)) => dependencies
+                        .into_iter()
+                        .map(MixRequirement::from_term)
+                        .collect::<Result<_, _>>()?,
+                    _ => Vec::new(),
+                };
+                let repo = next_str(&mut items).unwrap_or_else(|_| String::from("hexpm"));
+                let outer_checksum = next_str(&mut items).ok();
+
+                Ok(MixDependency::Hex {
+                    name,
+                    version,
+                    inner_checksum,
+                    managers,
+                    dependencies,
+                    repo,
+                    outer_checksum,
+                })
+            }
+            Some("git") => Ok(MixDependency::Git {
+                url: next_str(&mut items)?,
+                revision: next_str(&mut items)?,
+            }),
+            _ => Err(String::from("unknown dependency source")),
+        }
+    }
+}
+
+/// A requirement of a locked dependency on another package.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct MixRequirement {
+    pub name: String,
+    pub requirement: String,
+    pub optional: bool,
+}
+
+impl MixRequirement {
+    fn from_term(term: Term) -> Result<Self, String> {
+        let mut items = match term {
+            Term::Tuple(items) => items.into_iter(),
+            _ => return Err(String::from("expected a requirement tuple")),
+        };
+
+        let name = next_atom(&mut items)?;
+        let requirement = next_str(&mut items)?;
+        let optional = match items.next() {
+            Some(Term::List(options)) => options.iter().any(|option| {
+                matches!(option, Term::Tuple(pair)
+                    if pair.first().and_then(Term::as_atom) == Some("optional")
+                        && pair.get(1).and_then(Term::as_atom) == Some("true"))
+            }),
+            _ => false,
+        };
+
+        Ok(MixRequirement {
+            name,
+            requirement,
+            optional,
+        })
+    }
+}
+
+fn next_atom(items: &mut impl Iterator<Item = Term>) -> Result<String, String> {
+    match items.next() {
+        Some(Term::Atom(atom)) => Ok(atom),
+        _ => Err(String::from("expected an atom")),
+    }
+}
+
+fn next_str(items: &mut impl Iterator<Item = Term>) -> Result<String, String> {
+    match items.next() {
+        Some(Term::String(string)) => Ok(string),
+        _ => Err(String::from("expected a string")),
+    }
+}
+
+/// The subset of Elixir terms used by `mix.lock`.
+///
+/// Keyword list entries (`key: value`) are represented as two element
+/// tuples, like Elixir does.
+#[derive(Debug, Clone, PartialEq)]
+enum Term {
+    Atom(String),
+    String(String),
+    Number(String),
+    List(Vec<Term>),
+    Tuple(Vec<Term>),
+    Map(Vec<(Term, Term)>),
+}
+
+impl Term {
+    fn as_atom(&self) -> Option<&str> {
+        match self {
+            Term::Atom(atom) => Some(atom),
+            _ => None,
+        }
+    }
+
+    fn as_str(&self) -> Option<&str> {
+        match self {
+            Term::String(string) => Some(string),
+            _ => None,
+        }
+    }
+}
+
+/// Deepest nesting of maps, tuples and lists, far more than Mix writes.
+const MAX_DEPTH: usize = 32;
+
+struct TermReader<'a> {
+    input: &'a str,
+    pos: usize,
+    depth: usize,
+}
+
+impl<'a> TermReader<'a> {
+    fn error(&self, message: &str) -> String {
+        format!("{message} at offset {}", self.pos)
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.input[self.pos..].chars().next()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(c) = self.peek() {
+            if c == '#' {
+                let rest = &self.input[self.pos..];
+                self.pos += rest.find('\n').unwrap_or(rest.len());
+            } else if c.is_whitespace() {
+                self.pos += c.len_utf8();
+            } else {
+                break;
+            }
+        }
+    }
+
+    fn eat(&mut self, token: &str) -> bool {
+        self.skip_whitespace();
+        if self.input[self.pos..].starts_with(token) {
+            self.pos += token.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, token: &str) -> Result<(), String> {
+        if self.eat(token) {
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected {token:?}")))
+        }
+    }
+
+    fn term(&mut self) -> Result<Term, String> {
+        if self.depth == MAX_DEPTH {
+            return Err(self.error("terms are nested too deeply"));
+        }
+
+        self.depth += 1;
+        let term = self.nested_term();
+        self.depth -= 1;
+        term
+    }
+
+    fn nested_term(&mut self) -> Result<Term, String> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some('%') => {
+                self.expect("%{")?;
+                let mut entries = Vec::new();
+                while !self.eat("}") {
+                    let entry = match self.keyword_key()? {
+                        Some(key) => (Term::Atom(key), self.term()?),
+                        None => {
+                            let key = self.term()?;
+                            self.expect("=>")?;
+                            (key, self.term()?)
+                        }
+                    };
+                    entries.push(entry);
+                    if !self.eat(",") {
+                        self.expect("}")?;
+                        break;
+                    }
+                }
+                Ok(Term::Map(entries))
+            }
+            Some('{') => {
+                self.pos += 1;
+                Ok(Term::Tuple(self.sequence("}")?))
+            }
+            Some('[') => {
+                self.pos += 1;
+                Ok(Term::List(self.sequence("]")?))
+            }
+            Some('"') => Ok(Term::String(self.string()?)),
+            Some(':') => {
+                self.pos += 1;
+                if self.peek() == Some('"') {
+                    Ok(Term::Atom(self.string()?))
+                } else {
+                    Ok(Term::Atom(self.identifier()))
+                }
+            }
+            Some(c) if c.is_ascii_digit() || c == '-' => {
+                let start = self.pos;
+                self.pos += 1;
+                while matches!(self.peek(), Some(c) if c.is_ascii_alphanumeric() || c == '.' || c == '_')
+                {
+                    self.pos += 1;
+                }
+                Ok(Term::Number(self.input[start..self.pos].to_owned()))
+            }
+            Some(c) if c.is_alphabetic() => {
+                // Bare `true`, `false` and `nil` are atoms in Elixir.
+                let identifier = self.identifier();
+                match identifier.as_str() {
+                    "true" | "false" | "nil" => Ok(Term::Atom(identifier)),
+                    _ => Err(self.error(&format!("unexpected identifier {identifier:?}"))),
+                }
+            }
+            _ => Err(self.error("unexpected character")),
+        }
+    }
+
+    /// Read a keyword key like `optional:` or `"name":`, if there is one.
+    fn keyword_key(&mut self) -> Result<Option<String>, String> {
+        self.skip_whitespace();
+        let start = self.pos;
+
+        let key = match self.peek() {
+            Some('"') => self.string()?,
+            Some(c) if c.is_alphabetic() || c == '_' => self.identifier(),
+            _ => return Ok(None),
+        };
+
+        if self.peek() == Some(':') {
+            self.pos += 1;
+            Ok(Some(key))
+        } else {
+            self.pos = start;
+            Ok(None)
+        }
+    }
+
+    fn sequence(&mut self, close: &str) -> Result<Vec<Term>, String> {
+        let mut items = Vec::new();
+        while !self.eat(close) {
+            let item = match self.keyword_key()? {
+                Some(key) => Term::Tuple(vec![Term::Atom(key), self.term()?]),
+                None => self.term()?,
+            };
+            items.push(item);
+            if !self.eat(",") {
+                self.expect(close)?;
+                break;
+            }
+        }
+        Ok(items)
+    }
+
+    fn identifier(&mut self) -> String {
+        let start = self.pos;
+        while let Some(c) = self.peek() {
+            if !(c.is_alphanumeric() || c == '_' || c == '?' || c == '!') {
+                break;
+            }
+            self.pos += c.len_utf8();
+        }
+        self.input[start..self.pos].to_owned()
+    }
+
+    fn string(&mut self) -> Result<String, String> {
+        self.expect("\"")?;
+        let mut string = String::new();
+        let mut chars = self.input[self.pos..].char_indices();
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '"' => {
+                    self.pos += i + 1;
+                    return Ok(string);
+                }
+                '\\' => match chars.next() {
+                    Some((_, 'n')) => string.push('\n'),
+                    Some((_, 't')) => string.push('\t'),
+                    Some((_, escaped)) => string.push(escaped),
+                    None => break,
+                },
+                c => string.push(c),
+            }
+        }
+        Err(self.error("unterminated string"))
+    }
+}
@@ -1 +1,8 @@
+pub mod cocoapods;
+pub mod composer;
+pub mod conda;
+pub mod gradle;
+pub mod hex;
 pub mod maven;
+pub mod pubspec;
+pub mod swift;
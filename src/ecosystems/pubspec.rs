@@ -0,0 +1,90 @@
+// This is a reference for the lockfile of the Dart and Flutter package manager.
+// https://dart.dev/tools/pub/glossary#lockfile
+
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::Error;
+use crate::types::package::{PackageDescriptor, PackageType};
+
+/// A `pubspec.lock` file.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PubspecLock {
+    #[serde(default)]
+    pub packages: BTreeMap<String, PubPackage>,
+    #[serde(default)]
+    pub sdks: BTreeMap<String, String>,
+}
+
+impl PubspecLock {
+    /// Descriptors of all packages hosted on a pub repository.
+    ///
+    /// SDK, path and git packages are skipped.
+    pub fn packages(&self) -> Vec<PackageDescriptor> {
+        self.packages
+            .iter()
+            .filter(|(_, package)| package.source == "hosted")
+            .map(|(name, package)| PackageDescriptor {
+                name: package.description.name().unwrap_or(name).to_owned(),
+                version: package.version.clone(),
+                package_type: PackageType::Pub,
+            })
+            .collect()
+    }
+}
+
+impl FromStr for PubspecLock {
+    type Err = Error;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        serde_yaml::from_str(input).map_err(|err| Error::InvalidLockfile {
+            lockfile: "pubspec.lock",
+            reason: err.to_string(),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PubPackage {
+    /// How the package is depended upon, like `direct main` or `transitive`.
+    pub dependency: String,
+    pub description: PubDescription,
+    /// Where the package comes from, like `hosted`, `sdk`, `git` or `path`.
+    pub source: String,
+    pub version: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum PubDescription {
+    /// SDK packages only name their SDK.
+    Sdk(String),
+    Hosted {
+        name: String,
+        url: String,
+        sha256: Option<String>,
+    },
+    Git {
+        url: String,
+        #[serde(rename = "ref")]
+        reference: Option<String>,
+        #[serde(rename = "resolved-ref")]
+        resolved_ref: Option<String>,
+        path: Option<String>,
+    },
+    Path {
+        path: String,
+        relative: bool,
+    },
+}
+
+impl PubDescription {
+    fn name(&self) -> Option<&str> {
+        match self {
+            PubDescription::Hosted { name, .. } => Some(name),
+            _ => None,
+        }
+    }
+}
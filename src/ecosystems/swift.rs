@@ -0,0 +1,112 @@
+// This is a reference for the Swift Package Manager lockfile.
+// https://github.com/apple/swift-package-manager/blob/main/Sources/PackageGraph/PinsStore.swift
+
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::Error;
+use crate::types::package::{PackageDescriptor, PackageType};
+
+/// A `Package.resolved` file.
+///
+/// Version 1 nests its pins in an `object` field and names the repository
+/// `repositoryURL`, later versions use a flat list of pins with a `location`.
+/// Both are read into the same representation.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(from = "RawPackageResolved")]
+pub struct PackageResolved {
+    pub version: u32,
+    pub pins: Vec<SwiftPin>,
+}
+
+impl PackageResolved {
+    /// Descriptors of all pinned packages.
+    ///
+    /// Packages are named by their repository location without scheme and
+    /// `.git` suffix, like `github.com/apple/swift-nio`. Pins on a branch
+    /// use the pinned revision as version.
+    pub fn packages(&self) -> Vec<PackageDescriptor> {
+        self.pins
+            .iter()
+            .map(|pin| PackageDescriptor {
+                name: pin.name(),
+                version: pin
+                    .state
+                    .version
+                    .clone()
+                    .unwrap_or_else(|| pin.state.revision.clone()),
+                package_type: PackageType::Swift,
+            })
+            .collect()
+    }
+}
+
+impl FromStr for PackageResolved {
+    type Err = Error;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        serde_json::from_str(input).map_err(|err| Error::InvalidLockfile {
+            lockfile: "Package.resolved",
+            reason: err.to_string(),
+        })
+    }
+}
+
+#[derive(Deserialize)]
+struct RawPackageResolved {
+    version: u32,
+    #[serde(default)]
+    pins: Vec<SwiftPin>,
+    object: Option<RawPins>,
+}
+
+#[derive(Deserialize)]
+struct RawPins {
+    pins: Vec<SwiftPin>,
+}
+
+impl From<RawPackageResolved> for PackageResolved {
+    fn from(raw: RawPackageResolved) -> Self {
+        let mut pins = raw.pins;
+        if let Some(object) = raw.object {
+            pins.extend(object.pins);
+        }
+        Self {
+            version: raw.version,
+            pins,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SwiftPin {
+    #[serde(alias = "package")]
+    pub identity: String,
+    pub kind: Option<String>,
+    #[serde(alias = "repositoryURL")]
+    pub location: String,
+    pub state: SwiftPinState,
+}
+
+impl SwiftPin {
+    fn name(&self) -> String {
+        let location = self.location.trim_end_matches('/');
+        let location = location.strip_suffix(".git").unwrap_or(location);
+        let location = location
+            .split_once("://")
+            .map_or(location, |(_, rest)| rest);
+        // SCP-like git locations, e.g. `git@github.com:apple/swift-nio`.
+        match location.split_once('@') {
+            Some((_, host_path)) => host_path.replacen(':', "/", 1),
+            None => location.to_owned(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SwiftPinState {
+    pub branch: Option<String>,
+    pub revision: String,
+    pub version: Option<String>,
+}
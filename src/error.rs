@@ -0,0 +1,137 @@
+//! Error type shared by conversions and validation throughout this crate.
+
+use std::{error, fmt};
+
+use crate::types::job::Action;
+use crate::types::package::PackageType;
+
+/// Errors produced while converting or validating package data.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// The ecosystem name is not known, with the closest known ecosystem if
+    /// there is a likely typo.
+    UnknownEcosystem {
+        name: String,
+        suggestion: Option<PackageType>,
+    },
+    /// The ecosystem is known, but has no mapping to the requested format.
+    UnsupportedEcosystem(PackageType),
+    /// The package name does not follow the registry's naming rules.
+    InvalidName {
+        package_type: PackageType,
+        name: String,
+        reason: String,
+    },
+    /// The version is not a valid version for the ecosystem.
+    InvalidVersion {
+        package_type: PackageType,
+        version: String,
+        reason: String,
+    },
+    /// The package URL could not be parsed.
+    Purl(purl::ParseError),
+    /// A lockfile or dependency manifest could not be read.
+    InvalidLockfile {
+        lockfile: &'static str,
+        reason: String,
+    },
+    /// The action is not one of `none`, `warn` or `break`, with the closest
+    /// action if there is a likely typo.
+    UnknownAction {
+        name: String,
+        suggestion: Option<Action>,
+    },
+    /// The settings could not be read.
+    InvalidSettings(String),
+    /// The settings were written by a newer version, or have a version that
+    /// never existed.
+    UnsupportedSettingsVersion { version: u32, latest: u32 },
+    /// The thresholds can't be converted to the job format without losing
+    /// information.
+    IncompatibleThresholds(String),
+    /// The suppression can never apply.
+    InvalidSuppression(String),
+    /// The license is not a valid SPDX license expression.
+    InvalidLicense { expression: String, reason: String },
+    /// The CVSS vector is malformed or of an unsupported version.
+    InvalidCvss { vector: String, reason: String },
+    /// A document in an exchange format like CycloneDX could not be read.
+    InvalidDocument {
+        format: &'static str,
+        reason: String,
+    },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::UnknownEcosystem {
+                name,
+                suggestion: Some(suggestion),
+            } => write!(
+                f,
+                "Unknown ecosystem {name:?}, did you mean \"{suggestion}\"?"
+            ),
+            Error::UnknownEcosystem { name, .. } => write!(f, "Unknown ecosystem {name:?}"),
+            Error::UnsupportedEcosystem(package_type) => {
+                write!(f, "Ecosystem {package_type} is not supported here")
+            }
+            Error::InvalidName {
+                package_type,
+                name,
+                reason,
+            } => write!(f, "Invalid {package_type} package name {name:?}: {reason}"),
+            Error::InvalidVersion {
+                package_type,
+                version,
+                reason,
+            } => write!(f, "Invalid {package_type} version {version:?}: {reason}"),
+            Error::Purl(err) => write!(f, "Invalid package URL: {err}"),
+            Error::InvalidLockfile { lockfile, reason } => {
+                write!(f, "Invalid {lockfile}: {reason}")
+            }
+            Error::UnknownAction {
+                name,
+                suggestion: Some(suggestion),
+            } => write!(f, "Unknown action {name:?}, did you mean \"{suggestion}\"?"),
+            Error::UnknownAction { name, .. } => write!(f, "Unknown action {name:?}"),
+            Error::InvalidSettings(reason) => write!(f, "Invalid settings: {reason}"),
+            Error::UnsupportedSettingsVersion { version, latest } => write!(
+                f,
+                "Unsupported settings version {version}, latest known version is {latest}"
+            ),
+            Error::IncompatibleThresholds(reason) => {
+                write!(
+                    f,
+                    "Thresholds can't be converted to project thresholds: {reason}"
+                )
+            }
+            Error::InvalidSuppression(reason) => write!(f, "Invalid suppression: {reason}"),
+            Error::InvalidLicense { expression, reason } => {
+                write!(f, "Invalid license expression {expression:?}: {reason}")
+            }
+            Error::InvalidCvss { vector, reason } => {
+                write!(f, "Invalid CVSS vector {vector:?}: {reason}")
+            }
+            Error::InvalidDocument { format, reason } => {
+                write!(f, "Invalid {format} document: {reason}")
+            }
+        }
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Error::Purl(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<purl::ParseError> for Error {
+    fn from(err: purl::ParseError) -> Self {
+        Error::Purl(err)
+    }
+}
@@ -0,0 +1,748 @@
+//! CycloneDX 1.5 software bills of materials.
+//!
+//! A [`Bom`] can be built from a job or from analyzed packages and written as
+//! JSON or XML. SBOMs received from elsewhere can be read from either
+//! encoding and turned into packages for submission.
+
+use std::convert::TryFrom;
+use std::fmt::Write;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::error::Error;
+use crate::formats::{escape_xml, rfc3339};
+use crate::types::job::JobStatusResponse;
+use crate::types::package::{
+    IgnoredReason, Issue, Package, PackageDescriptor, PackageDescriptorAndLockfile,
+    PackageStatusExtended, RiskDomain, RiskLevel, Vulnerability,
+};
+
+const FORMAT: &str = "CycloneDX";
+const SPEC_VERSION: &str = "1.5";
+const XML_NAMESPACE: &str = "http://cyclonedx.org/schema/bom/1.5";
+/// Namespace of every CycloneDX version, followed by the spec version.
+const XML_NAMESPACE_PREFIX: &str = "http://cyclonedx.org/schema/bom/";
+
+/// A CycloneDX bill of materials.
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Bom {
+    pub bom_format: String,
+    pub spec_version: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub serial_number: Option<String>,
+    #[serde(default = "default_version")]
+    pub version: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<Metadata>,
+    #[serde(default)]
+    pub components: Vec<Component>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub vulnerabilities: Vec<BomVulnerability>,
+}
+
+fn default_version() -> u32 {
+    1
+}
+
+#[derive(PartialEq, Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Metadata {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timestamp: Option<DateTime<Utc>>,
+    /// The component the BOM describes, like the analyzed project.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub component: Option<Component>,
+}
+
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
+pub struct Component {
+    /// Kind of component, like `library` or `application`.
+    #[serde(rename = "type")]
+    pub component_type: String,
+    #[serde(rename = "bom-ref")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bom_ref: Option<String>,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub licenses: Vec<LicenseChoice>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub purl: Option<String>,
+    /// Components bundled within this one.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub components: Vec<Component>,
+}
+
+/// Either a single license or an SPDX license expression.
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum LicenseChoice {
+    License { license: License },
+    Expression { expression: String },
+}
+
+/// A license by SPDX identifier or by name.
+#[derive(PartialEq, Clone, Debug, Default, Serialize, Deserialize)]
+pub struct License {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+}
+
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
+pub struct BomVulnerability {
+    pub id: String,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub ratings: Vec<Rating>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub recommendation: Option<String>,
+    /// Assessment of the vulnerability, for ignored issues.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub analysis: Option<Analysis>,
+    /// References to the affected components.
+    #[serde(default)]
+    pub affects: Vec<Affects>,
+}
+
+#[derive(PartialEq, Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Rating {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub score: Option<f32>,
+    /// One of `critical`, `high`, `medium`, `low`, `info`, `none` or
+    /// `unknown`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub severity: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub method: Option<String>,
+}
+
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
+pub struct Analysis {
+    /// Impact of the vulnerability, like `not_affected` or `false_positive`.
+    pub state: String,
+}
+
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
+pub struct Affects {
+    #[serde(rename = "ref")]
+    pub bom_ref: String,
+}
+
+impl Bom {
+    /// An empty BOM.
+    pub fn new() -> Self {
+        Self {
+            bom_format: FORMAT.into(),
+            spec_version: SPEC_VERSION.into(),
+            serial_number: None,
+            version: 1,
+            metadata: None,
+            components: Vec::new(),
+            vulnerabilities: Vec::new(),
+        }
+    }
+
+    /// The packages of a job, with their licenses and vulnerabilities.
+    ///
+    /// Issues in the vulnerability and malicious code domains are listed as
+    /// vulnerabilities, ignored ones with an analysis of why they don't
+    /// apply.
+    pub fn from_job(job: &JobStatusResponse<PackageStatusExtended>) -> Self {
+        let mut bom = Self::new();
+        bom.serial_number = Some(format!("urn:uuid:{}", job.job_id));
+        bom.metadata = Some(Metadata {
+            timestamp: DateTime::from_timestamp(job.created_at, 0),
+            component: Some(Component {
+                component_type: "application".into(),
+                bom_ref: None,
+                name: job.project_name.clone(),
+                version: job.label.clone(),
+                licenses: Vec::new(),
+                purl: None,
+                components: Vec::new(),
+            }),
+        });
+
+        for package in &job.packages {
+            let descriptor = PackageDescriptor {
+                name: package.basic_status.name.clone(),
+                version: package.basic_status.version.clone(),
+                package_type: package.package_type.clone(),
+            };
+            let bom_ref = bom.add_component(&descriptor, package.basic_status.license.as_deref());
+
+            for issue in &package.issues {
+                if let Some(vulnerability) = issue_vulnerability(&issue.issue, issue.ignored) {
+                    bom.add_affected(&bom_ref, vulnerability);
+                }
+            }
+        }
+
+        bom
+    }
+
+    /// Analyzed packages, with their licenses and vulnerabilities.
+    ///
+    /// Vulnerabilities are taken from the issues of the packages, advisories
+    /// known from elsewhere can be added with [`Self::add_vulnerability`].
+    pub fn from_packages(packages: &[Package]) -> Self {
+        let mut bom = Self::new();
+        for package in packages {
+            let descriptor = PackageDescriptor {
+                name: package.name.clone(),
+                version: package.version.clone(),
+                package_type: package.package_type(),
+            };
+            let bom_ref = bom.add_component(&descriptor, package.license.as_deref());
+
+            for issue in &package.issues_details {
+                if let Some(vulnerability) = issue_vulnerability(issue, IgnoredReason::False) {
+                    bom.add_affected(&bom_ref, vulnerability);
+                }
+            }
+        }
+        bom
+    }
+
+    /// Add a package as a library component, returning its reference.
+    ///
+    /// The reference is the package URL, or the name and version if the
+    /// package has no package URL.
+    pub fn add_component(&mut self, package: &PackageDescriptor, license: Option<&str>) -> String {
+        let purl = package.to_purl().ok();
+        let bom_ref = purl
+            .clone()
+            .unwrap_or_else(|| format!("{}@{}", package.name, package.version));
+
+        if !self
+            .components
+            .iter()
+            .any(|component| component.bom_ref.as_ref() == Some(&bom_ref))
+        {
+            self.components.push(Component {
+                component_type: "library".into(),
+                bom_ref: Some(bom_ref.clone()),
+                name: package.name.clone(),
+                version: Some(package.version.clone()),
+                licenses: license.map(license_choice).into_iter().collect(),
+                purl,
+                components: Vec::new(),
+            });
+        }
+
+        bom_ref
+    }
+
+    /// Add a known vulnerability of a component.
+    ///
+    /// Each CVE of the vulnerability is listed separately, with the base
+    /// severity as a CVSS v3 rating.
+    pub fn add_vulnerability(&mut self, bom_ref: &str, vulnerability: &Vulnerability) {
+        let mut ids = vulnerability.cve.clone();
+        if ids.is_empty() {
+            ids.push(vulnerability.title.clone());
+        }
+
+        for id in ids {
+            self.add_affected(
+                bom_ref,
+                BomVulnerability {
+                    id,
+                    ratings: vec![Rating {
+                        score: Some(vulnerability.base_severity),
+                        severity: Some(severity(vulnerability.risk_level).into()),
+                        method: Some("CVSSv3".into()),
+                    }],
+                    description: Some(vulnerability.description.clone()),
+                    recommendation: Some(vulnerability.remediation.clone())
+                        .filter(|remediation| !remediation.is_empty()),
+                    analysis: None,
+                    affects: Vec::new(),
+                },
+            );
+        }
+    }
+
+    /// Mark a component as affected by a vulnerability, adding the
+    /// vulnerability if it isn't listed yet.
+    ///
+    /// The analysis applies to every affected component, so a vulnerability
+    /// which is ignored for some components but not for others is listed
+    /// once per analysis.
+    fn add_affected(&mut self, bom_ref: &str, new: BomVulnerability) {
+        let index = match self
+            .vulnerabilities
+            .iter()
+            .position(|existing| existing.id == new.id && existing.analysis == new.analysis)
+        {
+            Some(index) => index,
+            None => {
+                self.vulnerabilities.push(new);
+                self.vulnerabilities.len() - 1
+            }
+        };
+
+        let affects = &mut self.vulnerabilities[index].affects;
+        if !affects.iter().any(|affected| affected.bom_ref == bom_ref) {
+            affects.push(Affects {
+                bom_ref: bom_ref.into(),
+            });
+        }
+    }
+
+    /// Read a BOM in the JSON encoding.
+    pub fn from_json(json: &str) -> Result<Self, Error> {
+        let bom: Bom = serde_json::from_str(json).map_err(|err| Error::InvalidDocument {
+            format: FORMAT,
+            reason: err.to_string(),
+        })?;
+
+        if bom.bom_format != FORMAT {
+            return Err(Error::InvalidDocument {
+                format: FORMAT,
+                reason: format!("unexpected BOM format {:?}", bom.bom_format),
+            });
+        }
+
+        Ok(bom)
+    }
+
+    /// Read a BOM in the XML encoding.
+    ///
+    /// The spec version is taken from the namespace. Only the elements
+    /// [`Self::to_xml`] writes are read, others like services and
+    /// dependencies are skipped.
+    pub fn from_xml(xml: &str) -> Result<Self, Error> {
+        let document = roxmltree::Document::parse(xml).map_err(|err| invalid(err.to_string()))?;
+        let root = document.root_element();
+        let spec_version = root
+            .tag_name()
+            .namespace()
+            .and_then(|namespace| namespace.strip_prefix(XML_NAMESPACE_PREFIX))
+            .filter(|_| root.has_tag_name("bom"))
+            .ok_or_else(|| invalid(format!("unexpected root element {:?}", root.tag_name())))?;
+
+        let version = match root.attribute("version") {
+            Some(version) => version
+                .parse()
+                .map_err(|_| invalid(format!("invalid BOM version {version:?}")))?,
+            None => default_version(),
+        };
+
+        let metadata = match child(root, "metadata") {
+            Some(metadata) => Some(Metadata {
+                timestamp: child_text(metadata, "timestamp")
+                    .map(|timestamp| {
+                        DateTime::parse_from_rfc3339(&timestamp)
+                            .map(|timestamp| timestamp.with_timezone(&Utc))
+                            .map_err(|_| invalid(format!("invalid timestamp {timestamp:?}")))
+                    })
+                    .transpose()?,
+                component: child(metadata, "component")
+                    .map(component_from_xml)
+                    .transpose()?,
+            }),
+            None => None,
+        };
+
+        Ok(Self {
+            bom_format: FORMAT.into(),
+            spec_version: spec_version.into(),
+            serial_number: root.attribute("serialNumber").map(String::from),
+            version,
+            metadata,
+            components: grandchildren(root, "components", "component")
+                .map(component_from_xml)
+                .collect::<Result<_, _>>()?,
+            vulnerabilities: grandchildren(root, "vulnerabilities", "vulnerability")
+                .map(vulnerability_from_xml)
+                .collect::<Result<_, _>>()?,
+        })
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("BOM serialization cannot fail")
+    }
+
+    pub fn to_xml(&self) -> String {
+        let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        let serial_number = match &self.serial_number {
+            Some(serial_number) => format!(" serialNumber=\"{}\"", escape_xml(serial_number)),
+            None => String::new(),
+        };
+        let _ = writeln!(
+            xml,
+            "<bom xmlns=\"{XML_NAMESPACE}\"{serial_number} version=\"{}\">",
+            self.version
+        );
+
+        if let Some(metadata) = &self.metadata {
+            xml.push_str("  <metadata>\n");
+            if let Some(timestamp) = &metadata.timestamp {
+                text_element(&mut xml, 2, "timestamp", &rfc3339(timestamp));
+            }
+            if let Some(component) = &metadata.component {
+                component_xml(&mut xml, 2, component);
+            }
+            xml.push_str("  </metadata>\n");
+        }
+
+        xml.push_str("  <components>\n");
+        for component in &self.components {
+            component_xml(&mut xml, 2, component);
+        }
+        xml.push_str("  </components>\n");
+
+        if !self.vulnerabilities.is_empty() {
+            xml.push_str("  <vulnerabilities>\n");
+            for vulnerability in &self.vulnerabilities {
+                vulnerability_xml(&mut xml, 2, vulnerability);
+            }
+            xml.push_str("  </vulnerabilities>\n");
+        }
+
+        xml.push_str("</bom>\n");
+        xml
+    }
+
+    /// The packages listed in the BOM, for submission.
+    ///
+    /// Packages are identified by their package URL, so components without
+    /// one are skipped, as are components of ecosystems that can't be
+    /// analyzed and components with an invalid package URL, see
+    /// [`Self::invalid_purls`]. Nested components are included.
+    pub fn packages(&self) -> Vec<PackageDescriptorAndLockfile> {
+        let mut packages = Vec::new();
+        for component in self.all_components() {
+            let descriptor = match component.purl.as_deref().map(PackageDescriptor::try_from) {
+                Some(Ok(descriptor)) => descriptor,
+                _ => continue,
+            };
+
+            let package = PackageDescriptorAndLockfile::from(descriptor);
+            if !packages.contains(&package) {
+                packages.push(package);
+            }
+        }
+        packages
+    }
+
+    /// Package URLs of components which can't be submitted, like package
+    /// URLs without a version, with the reason.
+    ///
+    /// Components of ecosystems that can't be analyzed are not listed.
+    pub fn invalid_purls(&self) -> Vec<(&str, Error)> {
+        self.all_components()
+            .filter_map(|component| {
+                let purl = component.purl.as_deref()?;
+                match PackageDescriptor::try_from(purl) {
+                    Ok(_) | Err(Error::UnknownEcosystem { .. }) => None,
+                    Err(err) => Some((purl, err)),
+                }
+            })
+            .collect()
+    }
+
+    /// All components in document order, nested components after their
+    /// parent.
+    fn all_components(&self) -> impl Iterator<Item = &Component> {
+        let mut pending: Vec<&Component> = self.components.iter().rev().collect();
+        std::iter::from_fn(move || {
+            let component = pending.pop()?;
+            pending.extend(component.components.iter().rev());
+            Some(component)
+        })
+    }
+}
+
+impl Default for Bom {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Issues in the vulnerability and malicious code domains are listed as
+/// vulnerabilities, by tag or id.
+fn issue_vulnerability(issue: &Issue, ignored: IgnoredReason) -> Option<BomVulnerability> {
+    if !matches!(
+        issue.domain,
+        RiskDomain::Vulnerabilities | RiskDomain::Malicious
+    ) {
+        return None;
+    }
+
+    Some(BomVulnerability {
+        id: issue.tag.clone().or_else(|| issue.id.clone())?,
+        ratings: vec![Rating {
+            score: None,
+            severity: Some(severity(issue.severity).into()),
+            method: None,
+        }],
+        description: Some(issue.description.clone()),
+        recommendation: None,
+        analysis: analysis(ignored),
+        affects: Vec::new(),
+    })
+}
+
+fn severity(risk_level: RiskLevel) -> &'static str {
+    match risk_level {
+        RiskLevel::Info => "info",
+        RiskLevel::Low => "low",
+        RiskLevel::Medium => "medium",
+        RiskLevel::High => "high",
+        RiskLevel::Critical => "critical",
+    }
+}
+
+fn analysis(ignored: IgnoredReason) -> Option<Analysis> {
+    let state = match ignored {
+        IgnoredReason::False => return None,
+        IgnoredReason::FalsePositive => "false_positive",
+        IgnoredReason::NotRelevant | IgnoredReason::Other => "not_affected",
+    };
+    Some(Analysis {
+        state: state.into(),
+    })
+}
+
+/// License expressions are kept as expressions, single licenses are assumed
+/// to be SPDX identifiers unless they contain spaces.
+fn license_choice(license: &str) -> LicenseChoice {
+    let is_expression = license
+        .split_whitespace()
+        .any(|word| matches!(word, "AND" | "OR" | "WITH"));
+    if is_expression {
+        return LicenseChoice::Expression {
+            expression: license.into(),
+        };
+    }
+
+    let license = match license.contains(char::is_whitespace) {
+        true => License {
+            id: None,
+            name: Some(license.into()),
+        },
+        false => License {
+            id: Some(license.into()),
+            name: None,
+        },
+    };
+    LicenseChoice::License { license }
+}
+
+fn invalid(reason: String) -> Error {
+    Error::InvalidDocument {
+        format: FORMAT,
+        reason,
+    }
+}
+
+/// First child element named `name`.
+fn child<'a, 'input>(
+    node: roxmltree::Node<'a, 'input>,
+    name: &str,
+) -> Option<roxmltree::Node<'a, 'input>> {
+    node.children().find(|child| child.has_tag_name(name))
+}
+
+fn child_text(node: roxmltree::Node, name: &str) -> Option<String> {
+    child(node, name).map(|child| child.text().unwrap_or_default().into())
+}
+
+/// Elements named `name` in the list element `list`, like the components in
+/// `<components>`.
+fn grandchildren<'a, 'input: 'a>(
+    node: roxmltree::Node<'a, 'input>,
+    list: &str,
+    name: &'a str,
+) -> impl Iterator<Item = roxmltree::Node<'a, 'input>> + 'a {
+    child(node, list)
+        .into_iter()
+        .flat_map(|list| list.children())
+        .filter(move |child| child.has_tag_name(name))
+}
+
+fn component_from_xml(node: roxmltree::Node) -> Result<Component, Error> {
+    let licenses = child(node, "licenses")
+        .into_iter()
+        .flat_map(|licenses| licenses.children())
+        .filter_map(|license| match license.tag_name().name() {
+            "license" => Some(LicenseChoice::License {
+                license: License {
+                    id: child_text(license, "id"),
+                    name: child_text(license, "name"),
+                },
+            }),
+            "expression" => Some(LicenseChoice::Expression {
+                expression: license.text().unwrap_or_default().into(),
+            }),
+            _ => None,
+        })
+        .collect();
+
+    Ok(Component {
+        component_type: node
+            .attribute("type")
+            .ok_or_else(|| invalid("component without type".into()))?
+            .into(),
+        bom_ref: node.attribute("bom-ref").map(String::from),
+        name: child_text(node, "name").ok_or_else(|| invalid("component without name".into()))?,
+        version: child_text(node, "version"),
+        licenses,
+        purl: child_text(node, "purl"),
+        components: grandchildren(node, "components", "component")
+            .map(component_from_xml)
+            .collect::<Result<_, _>>()?,
+    })
+}
+
+fn vulnerability_from_xml(node: roxmltree::Node) -> Result<BomVulnerability, Error> {
+    let ratings = grandchildren(node, "ratings", "rating")
+        .map(|rating| {
+            let score = match child_text(rating, "score") {
+                Some(score) => Some(
+                    score
+                        .parse()
+                        .map_err(|_| invalid(format!("invalid rating score {score:?}")))?,
+                ),
+                None => None,
+            };
+            Ok(Rating {
+                score,
+                severity: child_text(rating, "severity"),
+                method: child_text(rating, "method"),
+            })
+        })
+        .collect::<Result<_, Error>>()?;
+
+    Ok(BomVulnerability {
+        id: child_text(node, "id").ok_or_else(|| invalid("vulnerability without id".into()))?,
+        ratings,
+        description: child_text(node, "description"),
+        recommendation: child_text(node, "recommendation"),
+        analysis: child(node, "analysis")
+            .and_then(|analysis| child_text(analysis, "state"))
+            .map(|state| Analysis { state }),
+        affects: grandchildren(node, "affects", "target")
+            .filter_map(|target| child_text(target, "ref"))
+            .map(|bom_ref| Affects { bom_ref })
+            .collect(),
+    })
+}
+
+fn text_element(xml: &mut String, depth: usize, name: &str, text: &str) {
+    let _ = writeln!(
+        xml,
+        "{:indent$}<{name}>{}</{name}>",
+        "",
+        escape_xml(text),
+        indent = depth * 2
+    );
+}
+
+fn component_xml(xml: &mut String, depth: usize, component: &Component) {
+    let indent = depth * 2;
+    let bom_ref = match &component.bom_ref {
+        Some(bom_ref) => format!(" bom-ref=\"{}\"", escape_xml(bom_ref)),
+        None => String::new(),
+    };
+    let _ = writeln!(
+        xml,
+        "{:indent$}<component type=\"{}\"{bom_ref}>",
+        "",
+        escape_xml(&component.component_type)
+    );
+
+    text_element(xml, depth + 1, "name", &component.name);
+    if let Some(version) = &component.version {
+        text_element(xml, depth + 1, "version", version);
+    }
+    if !component.licenses.is_empty() {
+        let _ = writeln!(xml, "{:indent$}  <licenses>", "");
+        for license in &component.licenses {
+            match license {
+                LicenseChoice::License { license } => {
+                    let _ = writeln!(xml, "{:indent$}    <license>", "");
+                    if let Some(id) = &license.id {
+                        text_element(xml, depth + 3, "id", id);
+                    }
+                    if let Some(name) = &license.name {
+                        text_element(xml, depth + 3, "name", name);
+                    }
+                    let _ = writeln!(xml, "{:indent$}    </license>", "");
+                }
+                LicenseChoice::Expression { expression } => {
+                    text_element(xml, depth + 2, "expression", expression)
+                }
+            }
+        }
+        let _ = writeln!(xml, "{:indent$}  </licenses>", "");
+    }
+    if let Some(purl) = &component.purl {
+        text_element(xml, depth + 1, "purl", purl);
+    }
+    if !component.components.is_empty() {
+        let _ = writeln!(xml, "{:indent$}  <components>", "");
+        for nested in &component.components {
+            component_xml(xml, depth + 2, nested);
+        }
+        let _ = writeln!(xml, "{:indent$}  </components>", "");
+    }
+
+    let _ = writeln!(xml, "{:indent$}</component>", "");
+}
+
+fn vulnerability_xml(xml: &mut String, depth: usize, vulnerability: &BomVulnerability) {
+    let indent = depth * 2;
+    let _ = writeln!(xml, "{:indent$}<vulnerability>", "");
+    text_element(xml, depth + 1, "id", &vulnerability.id);
+
+    if !vulnerability.ratings.is_empty() {
+        let _ = writeln!(xml, "{:indent$}  <ratings>", "");
+        for rating in &vulnerability.ratings {
+            let _ = writeln!(xml, "{:indent$}    <rating>", "");
+            if let Some(score) = rating.score {
+                text_element(xml, depth + 3, "score", &score.to_string());
+            }
+            if let Some(severity) = &rating.severity {
+                text_element(xml, depth + 3, "severity", severity);
+            }
+            if let Some(method) = &rating.method {
+                text_element(xml, depth + 3, "method", method);
+            }
+            let _ = writeln!(xml, "{:indent$}    </rating>", "");
+        }
+        let _ = writeln!(xml, "{:indent$}  </ratings>", "");
+    }
+    if let Some(description) = &vulnerability.description {
+        text_element(xml, depth + 1, "description", description);
+    }
+    if let Some(recommendation) = &vulnerability.recommendation {
+        text_element(xml, depth + 1, "recommendation", recommendation);
+    }
+    if let Some(analysis) = &vulnerability.analysis {
+        let _ = writeln!(xml, "{:indent$}  <analysis>", "");
+        text_element(xml, depth + 2, "state", &analysis.state);
+        let _ = writeln!(xml, "{:indent$}  </analysis>", "");
+    }
+
+    let _ = writeln!(xml, "{:indent$}  <affects>", "");
+    for affected in &vulnerability.affects {
+        let _ = writeln!(xml, "{:indent$}    <target>", "");
+        text_element(xml, depth + 3, "ref", &affected.bom_ref);
+        let _ = writeln!(xml, "{:indent$}    </target>", "");
+    }
+    let _ = writeln!(xml, "{:indent$}  </affects>", "");
+
+    let _ = writeln!(xml, "{:indent$}</vulnerability>", "");
+}
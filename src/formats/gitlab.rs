@@ -0,0 +1,388 @@
+//! GitLab Dependency Scanning and Code Quality reports of job results.
+//!
+//! Vulnerability and malicious code issues are reported as vulnerabilities
+//! of a `gl-dependency-scanning-report.json`, shown in GitLab's security
+//! dashboard. Issues of the other risk domains are reported as Code Quality
+//! findings, shown in merge requests.
+//!
+//! Both formats locate findings in a file, so issues are reported once for
+//! every lockfile their package was submitted from. Issues of packages
+//! without a lockfile are located in the ecosystem's manifest, as in SARIF
+//! reports. Ignored issues are not reported.
+
+use chrono::DateTime;
+use serde::{Deserialize, Serialize};
+
+use crate::formats::{lockfiles, package_files, rfc3339};
+use crate::types::job::JobStatusResponse;
+use crate::types::package::{
+    IgnoredReason, IssueStatus, PackageDescriptor, PackageDescriptorAndLockfile,
+    PackageStatusExtended, PackageType, RiskDomain, RiskLevel,
+};
+
+const SCHEMA_VERSION: &str = "15.0.7";
+
+/// Convert the results of a job to a Dependency Scanning report.
+///
+/// `submitted` are the packages the job was submitted with, which know the
+/// lockfiles of each package. All of them are listed as dependency files.
+pub fn to_dependency_scanning(
+    job: &JobStatusResponse<PackageStatusExtended>,
+    submitted: &[PackageDescriptorAndLockfile],
+) -> DependencyScanningReport {
+    let lockfiles = lockfiles(submitted);
+
+    let mut vulnerabilities = Vec::new();
+    for (descriptor, issue) in issues(job) {
+        if !is_vulnerability(issue.issue.domain) {
+            continue;
+        }
+
+        for file in package_files(&lockfiles, &descriptor) {
+            vulnerabilities.push(GitLabVulnerability {
+                id: fingerprint(&descriptor, issue, &file),
+                name: issue.issue.title.clone(),
+                description: issue.issue.description.clone(),
+                severity: severity(issue.issue.severity).into(),
+                identifiers: identifiers(issue),
+                location: DependencyLocation {
+                    file,
+                    dependency: dependency(&descriptor.name, &descriptor.version),
+                },
+            });
+        }
+    }
+
+    let mut dependency_files: Vec<DependencyFile> = Vec::new();
+    for package in submitted {
+        let lockfile = match &package.lockfile {
+            Some(lockfile) => lockfile,
+            None => continue,
+        };
+        let descriptor = &package.package_descriptor;
+
+        let index = match dependency_files
+            .iter()
+            .position(|file| &file.path == lockfile)
+        {
+            Some(index) => index,
+            None => {
+                dependency_files.push(DependencyFile {
+                    path: lockfile.clone(),
+                    package_manager: package_manager(&descriptor.package_type, lockfile).into(),
+                    dependencies: Vec::new(),
+                });
+                dependency_files.len() - 1
+            }
+        };
+
+        let dependency = dependency(&descriptor.name, &descriptor.version);
+        let dependencies = &mut dependency_files[index].dependencies;
+        if !dependencies.contains(&dependency) {
+            dependencies.push(dependency);
+        }
+    }
+
+    let time = |timestamp: i64| {
+        let timestamp = DateTime::from_timestamp(timestamp, 0).unwrap_or_default();
+        rfc3339(&timestamp).trim_end_matches('Z').to_owned()
+    };
+    let tool = Tool {
+        id: "phylum".into(),
+        name: "Phylum".into(),
+        version: env!("CARGO_PKG_VERSION").into(),
+        vendor: Vendor {
+            name: "Phylum".into(),
+        },
+    };
+
+    DependencyScanningReport {
+        version: SCHEMA_VERSION.into(),
+        scan: Scan {
+            analyzer: tool.clone(),
+            scanner: tool,
+            scan_type: "dependency_scanning".into(),
+            start_time: time(job.created_at),
+            end_time: time(job.last_updated as i64),
+            status: "success".into(),
+        },
+        vulnerabilities,
+        dependency_files,
+    }
+}
+
+/// Convert the issues of a job outside the vulnerability and malicious code
+/// domains to Code Quality findings.
+pub fn to_code_quality(
+    job: &JobStatusResponse<PackageStatusExtended>,
+    submitted: &[PackageDescriptorAndLockfile],
+) -> Vec<CodeQualityIssue> {
+    let lockfiles = lockfiles(submitted);
+
+    let mut findings = Vec::new();
+    for (descriptor, issue) in issues(job) {
+        if is_vulnerability(issue.issue.domain) {
+            continue;
+        }
+
+        for file in package_files(&lockfiles, &descriptor) {
+            findings.push(CodeQualityIssue {
+                description: format!(
+                    "{}@{}: {}",
+                    descriptor.name, descriptor.version, issue.issue.title
+                ),
+                check_name: issue
+                    .issue
+                    .tag
+                    .clone()
+                    .unwrap_or_else(|| category(issue.issue.domain).into()),
+                fingerprint: fingerprint(&descriptor, issue, &file),
+                severity: code_quality_severity(issue.issue.severity).into(),
+                categories: vec![category(issue.issue.domain).into()],
+                location: CodeQualityLocation {
+                    path: file,
+                    lines: Lines { begin: 1 },
+                },
+            });
+        }
+    }
+    findings
+}
+
+/// Issues of the job which are not ignored, with their package.
+fn issues(
+    job: &JobStatusResponse<PackageStatusExtended>,
+) -> impl Iterator<Item = (PackageDescriptor, &IssueStatus)> {
+    job.packages.iter().flat_map(|package| {
+        let descriptor = PackageDescriptor {
+            name: package.basic_status.name.clone(),
+            version: package.basic_status.version.clone(),
+            package_type: package.package_type.clone(),
+        };
+        package
+            .issues
+            .iter()
+            .filter(|issue| issue.ignored == IgnoredReason::False)
+            .map(move |issue| (descriptor.clone(), issue))
+    })
+}
+
+fn is_vulnerability(domain: RiskDomain) -> bool {
+    matches!(domain, RiskDomain::Vulnerabilities | RiskDomain::Malicious)
+}
+
+/// Identify the finding across pipelines, so it isn't reported as new.
+fn fingerprint(package: &PackageDescriptor, issue: &IssueStatus, file: &str) -> String {
+    let issue_id = issue
+        .issue
+        .id
+        .as_deref()
+        .or(issue.issue.tag.as_deref())
+        .unwrap_or(&issue.issue.title);
+    format!(
+        "{}/{}@{}/{issue_id}/{file}",
+        package.package_type.purl_type(),
+        package.name,
+        package.version
+    )
+}
+
+/// Identifiers of a vulnerability, by tag and by Phylum issue id.
+///
+/// Tags of CVEs and GitHub advisories link to their advisory. GitLab requires
+/// at least one identifier, so issues without either are identified by their
+/// title, like their fingerprint.
+fn identifiers(issue: &IssueStatus) -> Vec<Identifier> {
+    let mut identifiers = Vec::new();
+    if let Some(tag) = &issue.issue.tag {
+        let (identifier_type, url) = if tag.starts_with("CVE-") {
+            (
+                "cve",
+                Some(format!("https://nvd.nist.gov/vuln/detail/{tag}")),
+            )
+        } else if tag.starts_with("GHSA-") {
+            ("ghsa", Some(format!("https://github.com/advisories/{tag}")))
+        } else {
+            ("phylum", None)
+        };
+        identifiers.push(Identifier {
+            identifier_type: identifier_type.into(),
+            name: tag.clone(),
+            value: tag.clone(),
+            url,
+        });
+    }
+    if let Some(id) = &issue.issue.id {
+        identifiers.push(Identifier {
+            identifier_type: "phylum_issue".into(),
+            name: format!("Phylum issue {id}"),
+            value: id.clone(),
+            url: None,
+        });
+    }
+    if identifiers.is_empty() {
+        identifiers.push(Identifier {
+            identifier_type: "phylum".into(),
+            name: issue.issue.title.clone(),
+            value: issue.issue.title.clone(),
+            url: None,
+        });
+    }
+    identifiers
+}
+
+fn dependency(name: &str, version: &str) -> Dependency {
+    Dependency {
+        package: DependencyPackage { name: name.into() },
+        version: version.into(),
+    }
+}
+
+/// The package manager of a lockfile, as GitLab names it.
+fn package_manager<'a>(package_type: &'a PackageType, lockfile: &str) -> &'a str {
+    let file_name = lockfile.rsplit(['/', '\\']).next().unwrap_or_default();
+    match (package_type, file_name) {
+        (PackageType::Npm, "yarn.lock") => "yarn",
+        (PackageType::Npm, "pnpm-lock.yaml") => "pnpm",
+        (PackageType::PyPi, "poetry.lock") => "poetry",
+        (PackageType::PyPi, "Pipfile.lock") => "pipenv",
+        (PackageType::PyPi, _) => "pip",
+        (PackageType::Maven, name) if name.starts_with("gradle") => "gradle",
+        (PackageType::RubyGems, _) => "bundler",
+        (PackageType::Golang, _) => "go",
+        (package_type, _) => package_type.as_str(),
+    }
+}
+
+fn severity(risk_level: RiskLevel) -> &'static str {
+    match risk_level {
+        RiskLevel::Info => "Info",
+        RiskLevel::Low => "Low",
+        RiskLevel::Medium => "Medium",
+        RiskLevel::High => "High",
+        RiskLevel::Critical => "Critical",
+    }
+}
+
+fn code_quality_severity(risk_level: RiskLevel) -> &'static str {
+    match risk_level {
+        RiskLevel::Info => "info",
+        RiskLevel::Low => "minor",
+        RiskLevel::Medium => "major",
+        RiskLevel::High => "critical",
+        RiskLevel::Critical => "blocker",
+    }
+}
+
+/// Code Climate category of a risk domain.
+fn category(domain: RiskDomain) -> &'static str {
+    match domain {
+        RiskDomain::AuthorRisk | RiskDomain::Malicious | RiskDomain::Vulnerabilities => "Security",
+        RiskDomain::EngineeringRisk => "Bug Risk",
+        RiskDomain::LicenseRisk => "Compatibility",
+    }
+}
+
+/// A `gl-dependency-scanning-report.json`.
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
+pub struct DependencyScanningReport {
+    pub version: String,
+    pub scan: Scan,
+    pub vulnerabilities: Vec<GitLabVulnerability>,
+    pub dependency_files: Vec<DependencyFile>,
+}
+
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
+pub struct Scan {
+    pub analyzer: Tool,
+    pub scanner: Tool,
+    #[serde(rename = "type")]
+    pub scan_type: String,
+    /// Time in the format `YYYY-MM-DDThh:mm:ss`, in UTC.
+    pub start_time: String,
+    pub end_time: String,
+    pub status: String,
+}
+
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
+pub struct Tool {
+    pub id: String,
+    pub name: String,
+    pub version: String,
+    pub vendor: Vendor,
+}
+
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
+pub struct Vendor {
+    pub name: String,
+}
+
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
+pub struct GitLabVulnerability {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    /// One of `Info`, `Unknown`, `Low`, `Medium`, `High` or `Critical`.
+    pub severity: String,
+    pub identifiers: Vec<Identifier>,
+    pub location: DependencyLocation,
+}
+
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
+pub struct Identifier {
+    /// Kind of identifier, like `cve`.
+    #[serde(rename = "type")]
+    pub identifier_type: String,
+    pub name: String,
+    pub value: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+}
+
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
+pub struct DependencyLocation {
+    /// The lockfile of the dependency.
+    pub file: String,
+    pub dependency: Dependency,
+}
+
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
+pub struct Dependency {
+    pub package: DependencyPackage,
+    pub version: String,
+}
+
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
+pub struct DependencyPackage {
+    pub name: String,
+}
+
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
+pub struct DependencyFile {
+    pub path: String,
+    pub package_manager: String,
+    pub dependencies: Vec<Dependency>,
+}
+
+/// A Code Quality finding, a subset of the Code Climate issue format.
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
+pub struct CodeQualityIssue {
+    pub description: String,
+    pub check_name: String,
+    pub fingerprint: String,
+    /// One of `info`, `minor`, `major`, `critical` or `blocker`.
+    pub severity: String,
+    pub categories: Vec<String>,
+    pub location: CodeQualityLocation,
+}
+
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
+pub struct CodeQualityLocation {
+    pub path: String,
+    pub lines: Lines,
+}
+
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
+pub struct Lines {
+    pub begin: u32,
+}
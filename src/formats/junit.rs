@@ -0,0 +1,127 @@
+//! JUnit XML reports of job results, as rendered by CI systems like Jenkins
+//! and GitLab.
+//!
+//! Every package is a test case of the project's test suite. Each threshold
+//! a package fails is reported as a failure, listing the issues of its risk
+//! domain. Packages which have not completed processing are skipped.
+
+use std::fmt::Write;
+
+use chrono::DateTime;
+
+use crate::formats::{escape_xml, has_risk_type, rfc3339, risk_type_name};
+use crate::policy::{ThresholdFailure, Verdict};
+use crate::types::common::Status;
+use crate::types::job::{Action, JobStatusResponse};
+use crate::types::package::{IgnoredReason, PackageDescriptor, PackageStatusExtended};
+
+/// Render the results of a job and their evaluation as JUnit XML.
+///
+/// Threshold failures without an action are not reported.
+pub fn to_junit(job: &JobStatusResponse<PackageStatusExtended>, verdict: &Verdict) -> String {
+    let mut testcases = String::new();
+    let mut failures = 0;
+    let mut skipped = 0;
+    for package in &job.packages {
+        let descriptor = PackageDescriptor {
+            name: package.basic_status.name.clone(),
+            version: package.basic_status.version.clone(),
+            package_type: package.package_type.clone(),
+        };
+        let package_failures: Vec<&ThresholdFailure> = verdict
+            .failures
+            .iter()
+            .filter(|failure| failure.package == descriptor && failure.action != Action::None)
+            .collect();
+
+        let _ = write!(
+            testcases,
+            "    <testcase classname=\"{}\" name=\"{}\"",
+            escape_xml(descriptor.package_type.as_str()),
+            escape_xml(&format!("{}@{}", descriptor.name, descriptor.version)),
+        );
+
+        if package.basic_status.status != Status::Complete {
+            skipped += 1;
+            testcases
+                .push_str(">\n      <skipped message=\"Package processing is incomplete\"/>\n");
+        } else if package_failures.is_empty() {
+            testcases.push_str("/>\n");
+            continue;
+        } else {
+            failures += 1;
+            testcases.push_str(">\n");
+            for failure in package_failures {
+                failure_xml(&mut testcases, package, failure);
+            }
+        }
+        testcases.push_str("    </testcase>\n");
+    }
+
+    let tests = job.packages.len();
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    let _ = writeln!(
+        xml,
+        "<testsuites name=\"Phylum\" tests=\"{tests}\" failures=\"{failures}\" \
+         skipped=\"{skipped}\">"
+    );
+    let _ = writeln!(
+        xml,
+        "  <testsuite name=\"{}\" tests=\"{tests}\" failures=\"{failures}\" errors=\"0\" \
+         skipped=\"{skipped}\"{}>",
+        escape_xml(&job.project_name),
+        match DateTime::from_timestamp(job.created_at, 0) {
+            Some(timestamp) => format!(" timestamp=\"{}\"", rfc3339(&timestamp)),
+            None => String::new(),
+        },
+    );
+
+    xml.push_str("    <properties>\n");
+    let mut properties = vec![("job", job.job_id.to_string())];
+    if let Some(label) = &job.label {
+        properties.push(("label", label.clone()));
+    }
+    properties.push(("action", verdict.action.as_str().into()));
+    for (name, value) in properties {
+        let _ = writeln!(
+            xml,
+            "      <property name=\"{name}\" value=\"{}\"/>",
+            escape_xml(&value)
+        );
+    }
+    xml.push_str("    </properties>\n");
+
+    xml.push_str(&testcases);
+    xml.push_str("  </testsuite>\n</testsuites>\n");
+    xml
+}
+
+/// A failed threshold, with the issues of its risk domain which are not
+/// ignored.
+fn failure_xml(xml: &mut String, package: &PackageStatusExtended, failure: &ThresholdFailure) {
+    let name = risk_type_name(failure.risk_type);
+    let _ = writeln!(
+        xml,
+        "      <failure type=\"{}\" message=\"{}\">",
+        failure.risk_type,
+        escape_xml(&format!(
+            "{name} score {:.2} is below the threshold of {:.2}",
+            failure.score, failure.threshold
+        )),
+    );
+
+    let _ = writeln!(xml, "Action: {}", failure.action.as_str());
+    let issues = package.issues.iter().filter(|issue| {
+        issue.ignored == IgnoredReason::False && has_risk_type(&issue.issue, failure.risk_type)
+    });
+    for issue in issues {
+        let issue = &issue.issue;
+        let line = match issue.tag.as_deref().or(issue.id.as_deref()) {
+            Some(id) => format!("{id} ({}): {}", issue.severity, issue.title),
+            None => format!("{} ({})", issue.title, issue.severity),
+        };
+        let _ = writeln!(xml, "{}", escape_xml(&line));
+    }
+
+    xml.push_str("      </failure>\n");
+}
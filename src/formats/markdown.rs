@@ -0,0 +1,225 @@
+//! Markdown reports of job results, for pull request comments.
+//!
+//! A report starts with the outcome of the job and a summary table by risk
+//! type, comparing the lowest score of each risk type to its threshold.
+//! Failed thresholds follow, then the issues of every package in a
+//! collapsible section.
+
+use std::cmp::Reverse;
+use std::collections::HashSet;
+use std::fmt::Write;
+
+use crate::formats::{has_risk_type, risk_type_name, threshold, RISK_TYPES};
+use crate::policy::{package_scores, ThresholdFailure, Verdict};
+use crate::types::common::Status;
+use crate::types::job::{Action, JobStatusResponse};
+use crate::types::package::{IgnoredReason, IssueStatus, PackageStatusExtended, RiskType};
+
+/// Render the results of a job and their evaluation as Markdown.
+pub fn to_markdown(job: &JobStatusResponse<PackageStatusExtended>, verdict: &Verdict) -> String {
+    let mut md = String::new();
+    let _ = write!(md, "## Phylum analysis of {}", escape(&job.project_name));
+    if let Some(label) = &job.label {
+        let _ = write!(md, " ({})", escape(label));
+    }
+    md.push_str("\n\n");
+
+    let count = reported(verdict).count();
+    let _ = match verdict.action {
+        Action::None => writeln!(
+            md,
+            "✅ **Passed**: all packages meet the project's thresholds."
+        ),
+        Action::Warn => writeln!(
+            md,
+            "⚠️ **Passed with warnings**: {}.",
+            thresholds_failed(count)
+        ),
+        Action::Break => writeln!(md, "❌ **Failed**: {}.", thresholds_failed(count)),
+    };
+    if job.num_incomplete > 0 {
+        let _ = writeln!(
+            md,
+            "\n{} of {} packages are still being processed and were not evaluated.",
+            job.num_incomplete,
+            job.packages.len()
+        );
+    }
+
+    summary(&mut md, job, verdict);
+    failures(&mut md, verdict);
+    packages(&mut md, job);
+    md
+}
+
+/// Threshold failures which have an action, others are not reported.
+fn reported(verdict: &Verdict) -> impl Iterator<Item = &ThresholdFailure> {
+    verdict
+        .failures
+        .iter()
+        .filter(|failure| failure.action != Action::None)
+}
+
+fn thresholds_failed(count: usize) -> String {
+    match count {
+        1 => "1 threshold failed".into(),
+        count => format!("{count} thresholds failed"),
+    }
+}
+
+/// Table of the lowest score, failures and issues of each risk type.
+fn summary(md: &mut String, job: &JobStatusResponse<PackageStatusExtended>, verdict: &Verdict) {
+    let scores: Vec<_> = job
+        .packages
+        .iter()
+        .filter(|package| package.basic_status.status == Status::Complete)
+        .map(|package| package_scores(package, &[]))
+        .collect();
+
+    md.push_str("\n### Summary\n\n");
+    md.push_str("| Risk domain | Threshold | Lowest score | Delta | Failed packages | Issues |\n");
+    md.push_str("| --- | ---: | ---: | ---: | ---: | ---: |\n");
+    for risk_type in RISK_TYPES {
+        let threshold = threshold(&job.thresholds, risk_type);
+        let lowest = scores
+            .iter()
+            .map(|scores| scores.get(risk_type))
+            .reduce(f32::min);
+
+        let failed = reported(verdict)
+            .filter(|failure| failure.risk_type == risk_type)
+            .map(|failure| &failure.package)
+            .collect::<HashSet<_>>()
+            .len();
+
+        let issues = job
+            .packages
+            .iter()
+            .flat_map(|package| &package.issues)
+            .filter(|issue| {
+                issue.ignored == IgnoredReason::False && has_risk_type(&issue.issue, risk_type)
+            })
+            .count();
+
+        let (threshold, delta) = match lowest {
+            Some(lowest) if threshold > 0. => (
+                format!("{threshold:.2}"),
+                format!("{:+.2}", lowest - threshold),
+            ),
+            _ if threshold > 0. => (format!("{threshold:.2}"), "–".into()),
+            _ => ("–".into(), "–".into()),
+        };
+        let lowest = lowest.map_or_else(|| "–".into(), |lowest| format!("{lowest:.2}"));
+        let _ = writeln!(
+            md,
+            "| {} | {threshold} | {lowest} | {delta} | {} | {issues} |",
+            risk_type_name(risk_type),
+            failed,
+        );
+    }
+}
+
+/// Table of failed thresholds, with how far each package is below them.
+fn failures(md: &mut String, verdict: &Verdict) {
+    if reported(verdict).next().is_none() {
+        return;
+    }
+
+    md.push_str("\n### Failed thresholds\n\n");
+    md.push_str("| Package | Risk domain | Score | Threshold | Delta | Action |\n");
+    md.push_str("| --- | --- | ---: | ---: | ---: | --- |\n");
+    for failure in reported(verdict) {
+        let _ = writeln!(
+            md,
+            "| {}@{} | {} | {:.2} | {:.2} | {:+.2} | {} |",
+            escape(&failure.package.name),
+            escape(&failure.package.version),
+            risk_type_name(failure.risk_type),
+            failure.score,
+            failure.threshold,
+            failure.score - failure.threshold,
+            failure.action.as_str(),
+        );
+    }
+}
+
+/// Collapsible lists of the issues of each package, most severe first.
+fn packages(md: &mut String, job: &JobStatusResponse<PackageStatusExtended>) {
+    md.push_str("\n### Packages\n");
+
+    let mut any_issues = false;
+    for package in &job.packages {
+        if package.issues.is_empty() {
+            continue;
+        }
+        any_issues = true;
+
+        let mut issues: Vec<&IssueStatus> = package.issues.iter().collect();
+        issues.sort_by_key(|issue| Reverse(issue.issue.severity));
+
+        let count = match issues.len() {
+            1 => "1 issue".into(),
+            count => format!("{count} issues"),
+        };
+        let score = match package.basic_status.package_score {
+            Some(score) => format!(", score {score:.2}"),
+            None => String::new(),
+        };
+        let _ = writeln!(
+            md,
+            "\n<details>\n<summary><b>{}@{}</b> ({}): {count}{score}</summary>\n",
+            escape(&package.basic_status.name),
+            escape(&package.basic_status.version),
+            package.package_type,
+        );
+
+        md.push_str("| Severity | Risk domain | Issue | Title |\n");
+        md.push_str("| --- | --- | --- | --- |\n");
+        for issue in issues {
+            let id = issue.issue.tag.as_deref().or(issue.issue.id.as_deref());
+            let title = escape(&issue.issue.title);
+            let title = match ignored(issue.ignored) {
+                Some(reason) => format!("~~{title}~~ (ignored: {reason})"),
+                None => title,
+            };
+            let _ = writeln!(
+                md,
+                "| {} | {} | {} | {title} |",
+                issue.issue.severity,
+                risk_type_name(RiskType::from(issue.issue.domain)),
+                escape(id.unwrap_or("–")),
+            );
+        }
+
+        md.push_str("\n</details>\n");
+    }
+
+    if !any_issues {
+        md.push_str("\nNo issues were found.\n");
+    }
+}
+
+fn ignored(reason: IgnoredReason) -> Option<&'static str> {
+    match reason {
+        IgnoredReason::False => None,
+        IgnoredReason::FalsePositive => Some("false positive"),
+        IgnoredReason::NotRelevant => Some("not relevant"),
+        IgnoredReason::Other => Some("other"),
+    }
+}
+
+/// Escape text for table cells and HTML elements.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '|' => escaped.push_str("\\|"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '&' => escaped.push_str("&amp;"),
+            '\n' | '\r' => escaped.push(' '),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
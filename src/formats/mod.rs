@@ -0,0 +1,146 @@
+//! Conversion of job results to and from formats understood by other tools.
+
+pub mod cyclonedx;
+pub mod gitlab;
+pub mod junit;
+pub mod markdown;
+pub mod osv;
+pub mod sarif;
+pub mod spdx;
+pub mod vex;
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Datelike, Timelike, Utc};
+
+use crate::types::package::{
+    Issue, PackageDescriptor, PackageDescriptorAndLockfile, PackageType, RiskType,
+};
+use crate::types::project::ProjectThresholds;
+
+/// Risk types in the order reports list them.
+pub(crate) const RISK_TYPES: [RiskType; 6] = [
+    RiskType::TotalRisk,
+    RiskType::Vulnerabilities,
+    RiskType::MaliciousRisk,
+    RiskType::AuthorsRisk,
+    RiskType::EngineeringRisk,
+    RiskType::LicenseRisk,
+];
+
+/// Name of a risk type for people, like `Malicious code`.
+pub(crate) fn risk_type_name(risk_type: RiskType) -> &'static str {
+    match risk_type {
+        RiskType::TotalRisk => "Total",
+        RiskType::Vulnerabilities => "Vulnerability",
+        RiskType::MaliciousRisk => "Malicious code",
+        RiskType::AuthorsRisk => "Author",
+        RiskType::EngineeringRisk => "Engineering",
+        RiskType::LicenseRisk => "License",
+    }
+}
+
+/// The cutoff of a risk type, `0` if the threshold is not active.
+pub(crate) fn threshold(thresholds: &ProjectThresholds, risk_type: RiskType) -> f32 {
+    match risk_type {
+        RiskType::TotalRisk => thresholds.total,
+        RiskType::Vulnerabilities => thresholds.vulnerability,
+        RiskType::MaliciousRisk => thresholds.malicious,
+        RiskType::AuthorsRisk => thresholds.author,
+        RiskType::EngineeringRisk => thresholds.engineering,
+        RiskType::LicenseRisk => thresholds.license,
+    }
+}
+
+/// Check if an issue counts towards a risk type, every issue counts towards
+/// the total.
+pub(crate) fn has_risk_type(issue: &Issue, risk_type: RiskType) -> bool {
+    risk_type == RiskType::TotalRisk || RiskType::from(issue.domain) == risk_type
+}
+
+/// Escape text for use in XML content and attribute values.
+pub(crate) fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Format a time as `YYYY-MM-DDThh:mm:ssZ`, without fractional seconds.
+pub(crate) fn rfc3339(timestamp: &DateTime<Utc>) -> String {
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        timestamp.year(),
+        timestamp.month(),
+        timestamp.day(),
+        timestamp.hour(),
+        timestamp.minute(),
+        timestamp.second()
+    )
+}
+
+/// The lockfiles each package was submitted from, in submission order.
+pub(crate) fn lockfiles(
+    submitted: &[PackageDescriptorAndLockfile],
+) -> HashMap<&PackageDescriptor, Vec<&str>> {
+    let mut lockfiles: HashMap<&PackageDescriptor, Vec<&str>> = HashMap::new();
+    for package in submitted {
+        if let Some(lockfile) = &package.lockfile {
+            let entry = lockfiles.entry(&package.package_descriptor).or_default();
+            if !entry.contains(&lockfile.as_str()) {
+                entry.push(lockfile);
+            }
+        }
+    }
+    lockfiles
+}
+
+/// Files to locate the findings of a package in: the lockfiles it was
+/// submitted from, or a fallback location if it has none, so findings are
+/// never dropped.
+pub(crate) fn package_files(
+    lockfiles: &HashMap<&PackageDescriptor, Vec<&str>>,
+    package: &PackageDescriptor,
+) -> Vec<String> {
+    match lockfiles.get(package) {
+        Some(lockfiles) => lockfiles
+            .iter()
+            .map(|lockfile| (*lockfile).into())
+            .collect(),
+        None => vec![fallback_uri(package)],
+    }
+}
+
+/// Location of a package which wasn't submitted from a lockfile.
+///
+/// This is the ecosystem's manifest in the repository root, or a path made up
+/// of the ecosystem and package name for ecosystems without a conventional
+/// manifest.
+fn fallback_uri(package: &PackageDescriptor) -> String {
+    let manifest = match package.package_type {
+        PackageType::Npm => "package.json",
+        PackageType::PyPi => "requirements.txt",
+        PackageType::Maven => "pom.xml",
+        PackageType::RubyGems => "Gemfile",
+        PackageType::Cargo => "Cargo.toml",
+        PackageType::Golang => "go.mod",
+        PackageType::Composer => "composer.json",
+        PackageType::Hex => "mix.exs",
+        PackageType::Pub => "pubspec.yaml",
+        PackageType::CocoaPods => "Podfile",
+        PackageType::Swift => "Package.swift",
+        PackageType::Conda => "environment.yml",
+        PackageType::Nuget | PackageType::Other(_) => {
+            return format!("{}/{}", package.package_type.purl_type(), package.name)
+        }
+    };
+    manifest.into()
+}
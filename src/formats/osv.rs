@@ -0,0 +1,610 @@
+//! Advisories in the Open Source Vulnerability format.
+//!
+//! OSV advisories list the affected packages of a vulnerability with
+//! ecosystem-specific version ranges, which makes it possible to match
+//! packages against a local copy of an advisory database without the API. See
+//! <https://ossf.github.io/osv-schema/>.
+
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::iter::FromIterator;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::cvss::CvssVector;
+use crate::error::Error;
+use crate::types::package::{
+    Issue, PackageDescriptor, PackageType, RiskDomain, RiskLevel, Vulnerability,
+};
+use crate::types::version::compare_versions;
+
+const FORMAT: &str = "OSV";
+const SCHEMA_VERSION: &str = "1.6.0";
+
+/// A vulnerability advisory.
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
+pub struct Advisory {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub schema_version: Option<String>,
+    pub id: String,
+    pub modified: DateTime<Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub published: Option<DateTime<Utc>>,
+    /// Set if the advisory was retracted.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub withdrawn: Option<DateTime<Utc>>,
+    /// Ids of the same vulnerability in other databases, like CVEs.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub aliases: Vec<String>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub related: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub details: Option<String>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub severity: Vec<Severity>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub affected: Vec<Affected>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub references: Vec<Reference>,
+    /// Fields specific to the database the advisory came from.
+    ///
+    /// Severities are read from `severity` and `base_severity`, where
+    /// GitHub's `MODERATE` is accepted for medium severity. They take
+    /// precedence over scores computed from CVSS vectors.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub database_specific: Option<Value>,
+}
+
+/// A severity score, like a CVSS vector.
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
+pub struct Severity {
+    #[serde(rename = "type")]
+    pub severity_type: SeverityType,
+    pub score: String,
+}
+
+#[derive(PartialEq, Eq, Copy, Clone, Debug, Serialize, Deserialize)]
+pub enum SeverityType {
+    #[serde(rename = "CVSS_V2")]
+    CvssV2,
+    #[serde(rename = "CVSS_V3")]
+    CvssV3,
+    #[serde(rename = "CVSS_V4")]
+    CvssV4,
+    Ubuntu,
+}
+
+/// A package affected by the vulnerability.
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
+pub struct Affected {
+    pub package: AffectedPackage,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub ranges: Vec<Range>,
+    /// Affected versions, in addition to the ranges.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub versions: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ecosystem_specific: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub database_specific: Option<Value>,
+}
+
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
+pub struct AffectedPackage {
+    /// The OSV ecosystem, like `npm`, `PyPI` or `crates.io`.
+    pub ecosystem: String,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub purl: Option<String>,
+}
+
+/// Affected versions as a sequence of events.
+///
+/// Versions are affected from an `introduced` event up to the next `fixed`
+/// event, or including the next `last_affected` event.
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
+pub struct Range {
+    #[serde(rename = "type")]
+    pub range_type: RangeType,
+    /// The repository of `GIT` ranges.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub repo: Option<String>,
+    pub events: Vec<Event>,
+}
+
+#[derive(PartialEq, Eq, Copy, Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum RangeType {
+    /// Semantic versions, regardless of the ecosystem.
+    Semver,
+    /// Versions compared with the rules of the package's ecosystem.
+    Ecosystem,
+    /// Commit hashes, which can't be matched against package versions.
+    Git,
+}
+
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Event {
+    /// The first affected version, `0` for all versions.
+    Introduced(String),
+    /// The first version which is no longer affected.
+    Fixed(String),
+    /// The last affected version.
+    LastAffected(String),
+    /// Versions from here on are outside of the range.
+    Limit(String),
+}
+
+impl Event {
+    pub fn version(&self) -> &str {
+        match self {
+            Event::Introduced(version)
+            | Event::Fixed(version)
+            | Event::LastAffected(version)
+            | Event::Limit(version) => version,
+        }
+    }
+}
+
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
+pub struct Reference {
+    /// Kind of reference, like `ADVISORY`, `FIX` or `WEB`.
+    #[serde(rename = "type")]
+    pub reference_type: String,
+    pub url: String,
+}
+
+impl Advisory {
+    /// Create an advisory for a vulnerability, without affected packages.
+    ///
+    /// CVEs of the vulnerability other than `id` become aliases.
+    pub fn from_vulnerability(
+        id: &str,
+        vulnerability: &Vulnerability,
+        modified: DateTime<Utc>,
+    ) -> Self {
+        let aliases = vulnerability
+            .cve
+            .iter()
+            .filter(|cve| *cve != id)
+            .cloned()
+            .collect();
+
+        let mut database_specific = json!({
+            "severity": vulnerability.risk_level,
+            "base_severity": vulnerability.base_severity,
+        });
+        if !vulnerability.remediation.is_empty() {
+            database_specific["remediation"] = vulnerability.remediation.clone().into();
+        }
+
+        Self {
+            schema_version: Some(SCHEMA_VERSION.into()),
+            id: id.into(),
+            modified,
+            published: None,
+            withdrawn: None,
+            aliases,
+            related: Vec::new(),
+            summary: Some(vulnerability.title.clone()),
+            details: Some(vulnerability.description.clone()),
+            severity: Vec::new(),
+            affected: Vec::new(),
+            references: Vec::new(),
+            database_specific: Some(database_specific),
+        }
+    }
+
+    /// Create an advisory for an issue of a package.
+    ///
+    /// The issue's tag, or its id if it has no tag, identifies the advisory.
+    /// Only issues in the vulnerability and malicious code domains are
+    /// advisories.
+    pub fn from_issue(
+        issue: &Issue,
+        package: &PackageDescriptor,
+        modified: DateTime<Utc>,
+    ) -> Option<Self> {
+        if !matches!(
+            issue.domain,
+            RiskDomain::Vulnerabilities | RiskDomain::Malicious
+        ) {
+            return None;
+        }
+
+        Some(Self {
+            schema_version: Some(SCHEMA_VERSION.into()),
+            id: issue.tag.clone().or_else(|| issue.id.clone())?,
+            modified,
+            published: None,
+            withdrawn: None,
+            aliases: Vec::new(),
+            related: Vec::new(),
+            summary: Some(issue.title.clone()),
+            details: Some(issue.description.clone()),
+            severity: Vec::new(),
+            affected: vec![Affected {
+                package: AffectedPackage {
+                    ecosystem: ecosystem(&package.package_type).into(),
+                    name: package.name.clone(),
+                    purl: package.to_purl().ok(),
+                },
+                ranges: Vec::new(),
+                versions: vec![package.version.clone()],
+                ecosystem_specific: None,
+                database_specific: None,
+            }],
+            references: Vec::new(),
+            database_specific: Some(json!({ "severity": issue.severity })),
+        })
+    }
+
+    /// Read an advisory in the JSON encoding.
+    pub fn from_json(json: &str) -> Result<Self, Error> {
+        serde_json::from_str(json).map_err(|err| Error::InvalidDocument {
+            format: FORMAT,
+            reason: err.to_string(),
+        })
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("OSV serialization cannot fail")
+    }
+
+    /// The vulnerability described by the advisory.
+    ///
+    /// CVEs are taken from the id and aliases. The base severity is the base
+    /// score of the advisory's CVSS v3 or v4 vector, unless the database
+    /// specific fields specify one, and the risk level is derived from the
+    /// same score. Without a remediation in the database specific fields, the
+    /// fixed versions are suggested.
+    pub fn vulnerability(&self) -> Vulnerability {
+        let mut cve = Vec::new();
+        for id in Some(&self.id).into_iter().chain(&self.aliases) {
+            if id.starts_with("CVE-") && !cve.contains(id) {
+                cve.push(id.clone());
+            }
+        }
+
+        let remediation = match self
+            .database_specific("remediation")
+            .and_then(Value::as_str)
+        {
+            Some(remediation) => remediation.into(),
+            None => remediation(self.affected.iter()),
+        };
+
+        Vulnerability {
+            cve,
+            base_severity: self.base_score().unwrap_or_default() as f32,
+            risk_level: self.risk_level(),
+            title: self.title(),
+            description: self.details.clone().unwrap_or_default(),
+            remediation,
+        }
+    }
+
+    /// The advisory as an issue.
+    ///
+    /// The advisory id becomes the issue's tag. Advisories of the OSV
+    /// malicious packages database, with `MAL-` ids, are malicious code
+    /// issues.
+    pub fn issue(&self) -> Issue {
+        let domain = match self.id.starts_with("MAL-") {
+            true => RiskDomain::Malicious,
+            false => RiskDomain::Vulnerabilities,
+        };
+
+        Issue {
+            tag: Some(self.id.clone()),
+            id: None,
+            title: self.title(),
+            description: self.details.clone().unwrap_or_default(),
+            severity: self.risk_level(),
+            domain,
+        }
+    }
+
+    /// Check if a package version is affected.
+    ///
+    /// Names are compared with the ecosystem's normalization rules. `GIT`
+    /// ranges are ignored.
+    pub fn affects(&self, package: &PackageDescriptor) -> bool {
+        self.withdrawn.is_none()
+            && self
+                .affected
+                .iter()
+                .any(|affected| affected.affects(package))
+    }
+
+    /// The severity from the database specific fields, or the severity of
+    /// [`Advisory::base_score`], medium if unknown.
+    fn risk_level(&self) -> RiskLevel {
+        self.database_specific("severity")
+            .and_then(Value::as_str)
+            .and_then(risk_level)
+            .or_else(|| self.base_score().map(RiskLevel::from_cvss_score))
+            .unwrap_or(RiskLevel::Medium)
+    }
+
+    /// The base score from the database specific fields, or of the CVSS
+    /// vector.
+    fn base_score(&self) -> Option<f64> {
+        self.database_specific("base_severity")
+            .and_then(Value::as_f64)
+            .or_else(|| self.cvss().map(|cvss| cvss.base_score()))
+    }
+
+    /// The first valid CVSS vector, preferring CVSS v3.
+    ///
+    /// CVSS v2 vectors are ignored, since [`CvssVector`] doesn't score them.
+    /// Advisories with only a v2 vector are rare, and databases which
+    /// publish them usually include a severity in the database specific
+    /// fields.
+    fn cvss(&self) -> Option<CvssVector> {
+        let vectors = |severity_type: SeverityType| {
+            self.severity
+                .iter()
+                .filter(move |severity| severity.severity_type == severity_type)
+                .filter_map(|severity| severity.score.parse().ok())
+        };
+        vectors(SeverityType::CvssV3)
+            .chain(vectors(SeverityType::CvssV4))
+            .next()
+    }
+
+    fn title(&self) -> String {
+        self.summary.clone().unwrap_or_else(|| self.id.clone())
+    }
+
+    fn database_specific(&self, key: &str) -> Option<&Value> {
+        self.database_specific.as_ref()?.get(key)
+    }
+}
+
+impl Affected {
+    /// Check if a package version is affected.
+    pub fn affects(&self, package: &PackageDescriptor) -> bool {
+        if package_type(&self.package.ecosystem) != package.package_type
+            || canonical_name(&package.package_type, &self.package.name)
+                != canonical_name(&package.package_type, &package.name)
+        {
+            return false;
+        }
+
+        let compare = |a: &str, b: &str| compare_versions(&package.package_type, a, b);
+        self.versions
+            .iter()
+            .any(|version| compare(version, &package.version) == Ordering::Equal)
+            || self
+                .ranges
+                .iter()
+                .any(|range| range.includes(&package.package_type, &package.version))
+    }
+}
+
+impl Range {
+    /// Check if a version of an ecosystem is within the range.
+    pub fn includes(&self, package_type: &PackageType, version: &str) -> bool {
+        let compare = |a: &str, b: &str| match self.range_type {
+            RangeType::Semver => compare_versions(
+                &PackageType::Npm,
+                a.trim_start_matches('v'),
+                b.trim_start_matches('v'),
+            ),
+            RangeType::Ecosystem => compare_versions(package_type, a, b),
+            RangeType::Git => Ordering::Equal,
+        };
+        if self.range_type == RangeType::Git {
+            return false;
+        }
+
+        // Events apply in version order, with `0` before all versions.
+        let mut events: Vec<&Event> = self.events.iter().collect();
+        events.sort_by(|a, b| match (a.version(), b.version()) {
+            ("0", "0") => Ordering::Equal,
+            ("0", _) => Ordering::Less,
+            (_, "0") => Ordering::Greater,
+            (a, b) => compare(a, b),
+        });
+
+        let mut affected = false;
+        for event in events {
+            match event {
+                Event::Introduced(introduced) => {
+                    if introduced == "0" || compare(version, introduced) != Ordering::Less {
+                        affected = true;
+                    }
+                }
+                Event::Fixed(fixed) => {
+                    if compare(version, fixed) != Ordering::Less {
+                        affected = false;
+                    }
+                }
+                Event::LastAffected(last) => {
+                    if compare(version, last) == Ordering::Greater {
+                        affected = false;
+                    }
+                }
+                Event::Limit(limit) => {
+                    if compare(version, limit) != Ordering::Less {
+                        return false;
+                    }
+                }
+            }
+        }
+        affected
+    }
+}
+
+/// Advisories indexed by affected package, for matching packages offline.
+///
+/// Withdrawn advisories are skipped.
+#[derive(Clone, Debug, Default)]
+pub struct AdvisoryDatabase {
+    advisories: Vec<Advisory>,
+    by_package: HashMap<(PackageType, String), Vec<usize>>,
+}
+
+impl AdvisoryDatabase {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&mut self, advisory: Advisory) {
+        if advisory.withdrawn.is_some() {
+            return;
+        }
+
+        let index = self.advisories.len();
+        for affected in &advisory.affected {
+            let package_type = package_type(&affected.package.ecosystem);
+            let name = canonical_name(&package_type, &affected.package.name);
+            let indices = self.by_package.entry((package_type, name)).or_default();
+            if !indices.contains(&index) {
+                indices.push(index);
+            }
+        }
+        self.advisories.push(advisory);
+    }
+
+    pub fn len(&self) -> usize {
+        self.advisories.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.advisories.is_empty()
+    }
+
+    /// Advisories affecting a package version.
+    pub fn advisories(&self, package: &PackageDescriptor) -> Vec<&Advisory> {
+        let key = (
+            package.package_type.clone(),
+            canonical_name(&package.package_type, &package.name),
+        );
+        self.by_package
+            .get(&key)
+            .into_iter()
+            .flatten()
+            .map(|index| &self.advisories[*index])
+            .filter(|advisory| advisory.affects(package))
+            .collect()
+    }
+
+    /// Issues of a package version, one for each affecting advisory.
+    pub fn issues(&self, package: &PackageDescriptor) -> Vec<Issue> {
+        self.advisories(package)
+            .into_iter()
+            .map(Advisory::issue)
+            .collect()
+    }
+}
+
+impl FromIterator<Advisory> for AdvisoryDatabase {
+    fn from_iter<I: IntoIterator<Item = Advisory>>(advisories: I) -> Self {
+        let mut database = Self::new();
+        for advisory in advisories {
+            database.insert(advisory);
+        }
+        database
+    }
+}
+
+/// The OSV ecosystem name of a package type.
+pub fn ecosystem(package_type: &PackageType) -> &str {
+    match package_type {
+        PackageType::Npm => "npm",
+        PackageType::PyPi => "PyPI",
+        PackageType::Maven => "Maven",
+        PackageType::RubyGems => "RubyGems",
+        PackageType::Nuget => "NuGet",
+        PackageType::Cargo => "crates.io",
+        PackageType::Golang => "Go",
+        PackageType::Composer => "Packagist",
+        PackageType::Hex => "Hex",
+        PackageType::Pub => "Pub",
+        PackageType::CocoaPods => "CocoaPods",
+        PackageType::Swift => "SwiftURL",
+        PackageType::Conda => "conda",
+        PackageType::Other(name) => name,
+    }
+}
+
+/// The package type of an OSV ecosystem.
+///
+/// Suffixes like the release in `Debian:11` are ignored, unknown ecosystems
+/// become [`PackageType::Other`].
+pub fn package_type(ecosystem: &str) -> PackageType {
+    let name = ecosystem.split(':').next().unwrap_or_default();
+    match name {
+        "npm" => PackageType::Npm,
+        "PyPI" => PackageType::PyPi,
+        "Maven" => PackageType::Maven,
+        "RubyGems" => PackageType::RubyGems,
+        "NuGet" => PackageType::Nuget,
+        "crates.io" => PackageType::Cargo,
+        "Go" => PackageType::Golang,
+        "Packagist" => PackageType::Composer,
+        "Hex" => PackageType::Hex,
+        "Pub" => PackageType::Pub,
+        "CocoaPods" => PackageType::CocoaPods,
+        "SwiftURL" => PackageType::Swift,
+        "conda" => PackageType::Conda,
+        _ => PackageType::Other(name.into()),
+    }
+}
+
+fn canonical_name(package_type: &PackageType, name: &str) -> String {
+    let descriptor = PackageDescriptor {
+        name: name.into(),
+        version: String::new(),
+        package_type: package_type.clone(),
+    };
+    match descriptor.package_name() {
+        Ok(name) => name.canonical().into(),
+        Err(_) => name.into(),
+    }
+}
+
+/// Map severities like `HIGH` or GitHub's `MODERATE` to a risk level.
+fn risk_level(severity: &str) -> Option<RiskLevel> {
+    Some(match severity.to_lowercase().as_str() {
+        "critical" => RiskLevel::Critical,
+        "high" => RiskLevel::High,
+        "medium" | "moderate" => RiskLevel::Medium,
+        "low" => RiskLevel::Low,
+        "info" | "none" => RiskLevel::Info,
+        _ => return None,
+    })
+}
+
+fn remediation<'a>(affected: impl Iterator<Item = &'a Affected>) -> String {
+    let mut fixed: Vec<&str> = Vec::new();
+    let events = affected
+        .flat_map(|affected| &affected.ranges)
+        .flat_map(|range| &range.events);
+    for event in events {
+        if let Event::Fixed(version) = event {
+            if !fixed.contains(&version.as_str()) {
+                fixed.push(version);
+            }
+        }
+    }
+
+    match fixed.is_empty() {
+        true => String::new(),
+        false => format!("Upgrade to a fixed version: {}.", fixed.join(", ")),
+    }
+}
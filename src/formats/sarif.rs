@@ -0,0 +1,334 @@
+//! Export of job results as SARIF 2.1.0, as consumed by GitHub code scanning.
+//!
+//! Every issue of a package becomes a result of the rule for its issue tag.
+//! Results are located in the lockfiles the package was submitted from, or
+//! the ecosystem's manifest if none is known, and ignored issues are reported
+//! as suppressed.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::formats::{lockfiles, package_files};
+use crate::types::job::JobStatusResponse;
+use crate::types::package::{
+    IgnoredReason, IssueStatus, PackageDescriptor, PackageDescriptorAndLockfile,
+    PackageStatusExtended, RiskDomain, RiskLevel,
+};
+
+const SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+const VERSION: &str = "2.1.0";
+
+/// Convert the results of a job to a SARIF log.
+///
+/// `submitted` are the packages the job was submitted with, which know the
+/// lockfiles of each package.
+pub fn to_sarif(
+    job: &JobStatusResponse<PackageStatusExtended>,
+    submitted: &[PackageDescriptorAndLockfile],
+) -> SarifLog {
+    let lockfiles = lockfiles(submitted);
+
+    let mut rules: Vec<ReportingDescriptor> = Vec::new();
+    let mut results = Vec::new();
+    for package in &job.packages {
+        let descriptor = PackageDescriptor {
+            name: package.basic_status.name.clone(),
+            version: package.basic_status.version.clone(),
+            package_type: package.package_type.clone(),
+        };
+        // GitHub rejects results without a location.
+        let locations: Vec<Location> = package_files(&lockfiles, &descriptor)
+            .into_iter()
+            .map(|uri| Location {
+                physical_location: PhysicalLocation {
+                    artifact_location: ArtifactLocation { uri },
+                },
+            })
+            .collect();
+
+        for issue in &package.issues {
+            let rule_id = rule_id(issue);
+            let rule_index = match rules.iter().position(|rule| rule.id == rule_id) {
+                Some(index) => index,
+                None => {
+                    rules.push(rule(rule_id.clone(), issue));
+                    rules.len() - 1
+                }
+            };
+
+            results.push(SarifResult {
+                rule_id,
+                rule_index,
+                level: level(issue.issue.severity).into(),
+                message: Message {
+                    text: format!(
+                        "{}@{}: {}",
+                        descriptor.name, descriptor.version, issue.issue.title
+                    ),
+                },
+                locations: locations.clone(),
+                partial_fingerprints: fingerprints(&descriptor, issue),
+                suppressions: suppressions(issue.ignored),
+            });
+        }
+    }
+
+    SarifLog {
+        schema: SCHEMA.into(),
+        version: VERSION.into(),
+        runs: vec![Run {
+            tool: Tool {
+                driver: ToolComponent {
+                    name: "Phylum".into(),
+                    information_uri: Some("https://phylum.io".into()),
+                    rules,
+                },
+            },
+            results,
+        }],
+    }
+}
+
+/// Rules are identified by the issue tag, then the issue id, then the risk
+/// domain for issues which have neither.
+fn rule_id(issue: &IssueStatus) -> String {
+    issue
+        .issue
+        .tag
+        .clone()
+        .or_else(|| issue.issue.id.clone())
+        .unwrap_or_else(|| category(issue.issue.domain).into())
+}
+
+/// Rule for the issues with the id `id`.
+///
+/// Rules of tagged issues are described by the issue, while the per-domain
+/// rules of untagged issues are described generically, since they are shared
+/// by unrelated issues.
+fn rule(id: String, issue: &IssueStatus) -> ReportingDescriptor {
+    let severity = issue.issue.severity;
+    let domain = issue.issue.domain;
+    let (name, description) = if issue.issue.tag.is_none() && issue.issue.id.is_none() {
+        let (name, description) = domain_rule(domain);
+        (name.to_owned(), description.to_owned())
+    } else {
+        (issue.issue.title.clone(), issue.issue.description.clone())
+    };
+
+    let mut tags = vec![category(domain).to_owned()];
+    if matches!(domain, RiskDomain::Vulnerabilities | RiskDomain::Malicious) {
+        tags.insert(0, "security".into());
+    }
+
+    ReportingDescriptor {
+        id,
+        name: Some(name.clone()),
+        short_description: Some(Message { text: name }),
+        full_description: Some(Message { text: description }),
+        default_configuration: Some(ReportingConfiguration {
+            level: level(severity).into(),
+        }),
+        properties: Some(RuleProperties {
+            tags,
+            security_severity: Some(security_severity(severity).into()),
+        }),
+    }
+}
+
+/// Name and description of the rule for issues without tag or id.
+fn domain_rule(domain: RiskDomain) -> (&'static str, &'static str) {
+    match domain {
+        RiskDomain::AuthorRisk => (
+            "Author risk",
+            "The package's authors or maintainers pose a risk.",
+        ),
+        RiskDomain::EngineeringRisk => (
+            "Engineering risk",
+            "The package's engineering practices pose a risk.",
+        ),
+        RiskDomain::Malicious => ("Malicious code", "The package contains malicious code."),
+        RiskDomain::Vulnerabilities => ("Vulnerability", "The package has a known vulnerability."),
+        RiskDomain::LicenseRisk => ("License risk", "The package's license poses a risk."),
+    }
+}
+
+/// Category of a risk domain, used as a rule tag.
+fn category(domain: RiskDomain) -> &'static str {
+    match domain {
+        RiskDomain::AuthorRisk => "author",
+        RiskDomain::EngineeringRisk => "engineering",
+        RiskDomain::Malicious => "malicious-code",
+        RiskDomain::Vulnerabilities => "vulnerability",
+        RiskDomain::LicenseRisk => "license",
+    }
+}
+
+fn level(severity: RiskLevel) -> &'static str {
+    match severity {
+        RiskLevel::Info | RiskLevel::Low => "note",
+        RiskLevel::Medium => "warning",
+        RiskLevel::High | RiskLevel::Critical => "error",
+    }
+}
+
+/// CVSS-like score GitHub uses to rank alerts.
+fn security_severity(severity: RiskLevel) -> &'static str {
+    match severity {
+        RiskLevel::Info => "0.0",
+        RiskLevel::Low => "2.0",
+        RiskLevel::Medium => "5.0",
+        RiskLevel::High => "7.5",
+        RiskLevel::Critical => "9.5",
+    }
+}
+
+/// Identify the issue across runs, so alerts aren't duplicated on upload.
+fn fingerprints(package: &PackageDescriptor, issue: &IssueStatus) -> HashMap<String, String> {
+    let issue_id = issue
+        .issue
+        .id
+        .as_deref()
+        .or(issue.issue.tag.as_deref())
+        .unwrap_or(&issue.issue.title);
+    let fingerprint = format!(
+        "{}/{}@{}/{issue_id}",
+        package.package_type.purl_type(),
+        package.name,
+        package.version
+    );
+
+    let mut fingerprints = HashMap::new();
+    fingerprints.insert("packageIssue/v1".into(), fingerprint);
+    fingerprints
+}
+
+fn suppressions(ignored: IgnoredReason) -> Vec<Suppression> {
+    let justification = match ignored {
+        IgnoredReason::False => return Vec::new(),
+        IgnoredReason::FalsePositive => "false positive",
+        IgnoredReason::NotRelevant => "not relevant",
+        IgnoredReason::Other => "other",
+    };
+
+    vec![Suppression {
+        kind: "external".into(),
+        status: Some("accepted".into()),
+        justification: Some(justification.into()),
+    }]
+}
+
+/// A SARIF log file.
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
+pub struct SarifLog {
+    #[serde(rename = "$schema")]
+    pub schema: String,
+    pub version: String,
+    pub runs: Vec<Run>,
+}
+
+/// A single run of an analysis tool.
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
+pub struct Run {
+    pub tool: Tool,
+    pub results: Vec<SarifResult>,
+}
+
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
+pub struct Tool {
+    pub driver: ToolComponent,
+}
+
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolComponent {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub information_uri: Option<String>,
+    #[serde(default)]
+    pub rules: Vec<ReportingDescriptor>,
+}
+
+/// A rule results refer to.
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReportingDescriptor {
+    pub id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub short_description: Option<Message>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub full_description: Option<Message>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_configuration: Option<ReportingConfiguration>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub properties: Option<RuleProperties>,
+}
+
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
+pub struct ReportingConfiguration {
+    /// One of `none`, `note`, `warning` or `error`.
+    pub level: String,
+}
+
+/// Rule properties understood by GitHub code scanning.
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
+pub struct RuleProperties {
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(rename = "security-severity")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub security_severity: Option<String>,
+}
+
+/// A finding of a rule.
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SarifResult {
+    pub rule_id: String,
+    pub rule_index: usize,
+    /// One of `none`, `note`, `warning` or `error`.
+    pub level: String,
+    pub message: Message,
+    #[serde(default)]
+    pub locations: Vec<Location>,
+    #[serde(default)]
+    pub partial_fingerprints: HashMap<String, String>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub suppressions: Vec<Suppression>,
+}
+
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
+pub struct Message {
+    pub text: String,
+}
+
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Location {
+    pub physical_location: PhysicalLocation,
+}
+
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PhysicalLocation {
+    pub artifact_location: ArtifactLocation,
+}
+
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
+pub struct ArtifactLocation {
+    pub uri: String,
+}
+
+/// A reason a result is not reported as an alert.
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
+pub struct Suppression {
+    /// `inSource` or `external`.
+    pub kind: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub justification: Option<String>,
+}
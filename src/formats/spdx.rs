@@ -0,0 +1,563 @@
+//! SPDX 2.3 documents in the JSON and tag-value encodings.
+//!
+//! A document lists the packages of a project with their package URLs and
+//! licenses. Dependencies between packages are `DEPENDS_ON` relationships,
+//! and the project's direct dependencies are described by the document.
+//!
+//! Registries only report the license a package declares, so the concluded
+//! license is always `NOASSERTION`. Declared licenses are normalized to SPDX
+//! expressions, and licenses which are not on the SPDX list are defined as
+//! `LicenseRef-` licenses with the registry's text.
+
+use std::convert::TryFrom;
+use std::fmt::Write;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::error::Error;
+use crate::formats::rfc3339;
+use crate::license::LicenseExpression;
+use crate::types::package::{Package, PackageDescriptor};
+
+const FORMAT: &str = "SPDX";
+const SPDX_VERSION: &str = "SPDX-2.3";
+const DATA_LICENSE: &str = "CC0-1.0";
+const DOCUMENT_ID: &str = "SPDXRef-DOCUMENT";
+const NOASSERTION: &str = "NOASSERTION";
+
+/// An SPDX document.
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpdxDocument {
+    pub spdx_version: String,
+    pub data_license: String,
+    #[serde(rename = "SPDXID")]
+    pub spdx_id: String,
+    pub name: String,
+    /// Unique URI of the document.
+    pub document_namespace: String,
+    pub creation_info: CreationInfo,
+    #[serde(default)]
+    pub packages: Vec<SpdxPackage>,
+    #[serde(default)]
+    pub relationships: Vec<Relationship>,
+    /// Licenses referenced as `LicenseRef-` by packages.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub has_extracted_licensing_infos: Vec<ExtractedLicensingInfo>,
+}
+
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
+pub struct CreationInfo {
+    /// Creation time, formatted as `YYYY-MM-DDThh:mm:ssZ`.
+    pub created: String,
+    /// Creators like `Tool: phylum`.
+    pub creators: Vec<String>,
+}
+
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpdxPackage {
+    #[serde(rename = "SPDXID")]
+    pub spdx_id: String,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version_info: Option<String>,
+    pub download_location: String,
+    #[serde(default = "default_files_analyzed")]
+    pub files_analyzed: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub license_concluded: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub license_declared: Option<String>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub external_refs: Vec<ExternalRef>,
+}
+
+impl SpdxPackage {
+    /// The package URL of this package, if it has one.
+    pub fn purl(&self) -> Option<&str> {
+        self.external_refs
+            .iter()
+            .find(|external_ref| external_ref.reference_type == "purl")
+            .map(|external_ref| external_ref.reference_locator.as_str())
+    }
+}
+
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExternalRef {
+    /// Like `PACKAGE-MANAGER` or `SECURITY`.
+    pub reference_category: String,
+    /// Like `purl` or `cpe23Type`.
+    pub reference_type: String,
+    pub reference_locator: String,
+}
+
+/// A license which is not on the SPDX license list.
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtractedLicensingInfo {
+    /// Reference like `LicenseRef-Commercial`.
+    pub license_id: String,
+    pub extracted_text: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+}
+
+/// A relationship like `SPDXRef-a DEPENDS_ON SPDXRef-b`.
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Relationship {
+    pub spdx_element_id: String,
+    pub relationship_type: String,
+    pub related_spdx_element: String,
+}
+
+impl SpdxDocument {
+    /// An empty document.
+    ///
+    /// The namespace must be a unique URI for this document, like
+    /// `https://example.com/spdx/my-project-<uuid>`.
+    pub fn new(name: &str, namespace: &str, created: DateTime<Utc>) -> Self {
+        Self {
+            spdx_version: SPDX_VERSION.into(),
+            data_license: DATA_LICENSE.into(),
+            spdx_id: DOCUMENT_ID.into(),
+            name: name.into(),
+            document_namespace: namespace.into(),
+            creation_info: CreationInfo {
+                created: rfc3339(&created),
+                creators: vec!["Tool: phylum".into()],
+            },
+            packages: Vec::new(),
+            relationships: Vec::new(),
+            has_extracted_licensing_infos: Vec::new(),
+        }
+    }
+
+    /// Add the direct dependencies of the project and, recursively, their
+    /// dependencies.
+    pub fn add_packages(&mut self, packages: &[Package]) {
+        for package in packages {
+            let id = self.add_package(package);
+            self.add_relationship(DOCUMENT_ID, "DESCRIBES", &id);
+        }
+    }
+
+    /// Add a package and its dependencies, returning its SPDX identifier.
+    fn add_package(&mut self, package: &Package) -> String {
+        let descriptor = PackageDescriptor {
+            name: package.name.clone(),
+            version: package.version.clone(),
+            package_type: package.package_type(),
+        };
+        let purl = package.purl.clone().or_else(|| descriptor.to_purl().ok());
+
+        let existing = self.packages.iter().find(|existing| {
+            existing.name == package.name
+                && existing.version_info.as_ref() == Some(&package.version)
+                && existing.purl() == purl.as_deref()
+        });
+        if let Some(existing) = existing {
+            return existing.spdx_id.clone();
+        }
+
+        let id = self.unique_id(&descriptor);
+        let license_declared = self.declared_license(package.license.as_deref());
+        self.packages.push(SpdxPackage {
+            spdx_id: id.clone(),
+            name: package.name.clone(),
+            version_info: Some(package.version.clone()),
+            download_location: NOASSERTION.into(),
+            files_analyzed: false,
+            license_concluded: Some(NOASSERTION.into()),
+            license_declared: Some(license_declared),
+            external_refs: purl
+                .map(|purl| ExternalRef {
+                    reference_category: "PACKAGE-MANAGER".into(),
+                    reference_type: "purl".into(),
+                    reference_locator: purl,
+                })
+                .into_iter()
+                .collect(),
+        });
+
+        for dependency in package.dependencies.iter().flatten() {
+            let dependency_id = self.add_package(dependency);
+            self.add_relationship(&id, "DEPENDS_ON", &dependency_id);
+        }
+
+        id
+    }
+
+    /// The declared license as an SPDX expression, defining licenses which
+    /// are not on the SPDX list. Missing licenses and placeholders like
+    /// `UNKNOWN` are `NOASSERTION`.
+    fn declared_license(&mut self, license: Option<&str>) -> String {
+        let (license, expression) =
+            match license.map(|license| (license, LicenseExpression::normalize(license))) {
+                Some((license, Ok(expression))) => (license, expression),
+                _ => return NOASSERTION.into(),
+            };
+
+        let licenses = expression.licenses();
+        for simple in &licenses {
+            let is_defined = self
+                .has_extracted_licensing_infos
+                .iter()
+                .any(|info| info.license_id == simple.id);
+            if !simple.id.starts_with("LicenseRef-") || is_defined {
+                continue;
+            }
+
+            // The registry's text is only known for a license on its own.
+            let name = simple.id.trim_start_matches("LicenseRef-");
+            let text = if licenses.len() == 1 { license } else { name };
+            self.has_extracted_licensing_infos
+                .push(ExtractedLicensingInfo {
+                    license_id: simple.id.clone(),
+                    extracted_text: text.into(),
+                    name: Some(name.into()),
+                });
+        }
+
+        expression.to_string()
+    }
+
+    fn add_relationship(&mut self, element: &str, relationship_type: &str, related: &str) {
+        let relationship = Relationship {
+            spdx_element_id: element.into(),
+            relationship_type: relationship_type.into(),
+            related_spdx_element: related.into(),
+        };
+        if !self.relationships.contains(&relationship) {
+            self.relationships.push(relationship);
+        }
+    }
+
+    /// An identifier like `SPDXRef-Package-npm-left-pad-1.3.0`, which may
+    /// only contain letters, numbers, `.` and `-`.
+    fn unique_id(&self, package: &PackageDescriptor) -> String {
+        let id: String = format!(
+            "SPDXRef-Package-{}-{}-{}",
+            package.package_type.purl_type(),
+            package.name,
+            package.version
+        )
+        .chars()
+        .map(|c| match c {
+            'a'..='z' | 'A'..='Z' | '0'..='9' | '.' | '-' => c,
+            _ => '-',
+        })
+        .collect();
+
+        let is_taken = |id: &str| self.packages.iter().any(|package| package.spdx_id == id);
+        let mut unique = id.clone();
+        let mut suffix = 1;
+        while is_taken(&unique) {
+            suffix += 1;
+            unique = format!("{id}-{suffix}");
+        }
+        unique
+    }
+
+    /// Packages of the document which have a package URL of a supported
+    /// ecosystem.
+    ///
+    /// Packages with an invalid package URL are skipped, see
+    /// [`Self::invalid_purls`].
+    pub fn package_descriptors(&self) -> Vec<PackageDescriptor> {
+        let mut descriptors = Vec::new();
+        for purl in self.packages.iter().filter_map(SpdxPackage::purl) {
+            let descriptor = match PackageDescriptor::try_from(purl) {
+                Ok(descriptor) => descriptor,
+                Err(_) => continue,
+            };
+            if !descriptors.contains(&descriptor) {
+                descriptors.push(descriptor);
+            }
+        }
+        descriptors
+    }
+
+    /// Package URLs of packages which can't be submitted, like package URLs
+    /// without a version, with the reason.
+    ///
+    /// Packages of ecosystems that can't be analyzed are not listed.
+    pub fn invalid_purls(&self) -> Vec<(&str, Error)> {
+        self.packages
+            .iter()
+            .filter_map(|package| {
+                let purl = package.purl()?;
+                match PackageDescriptor::try_from(purl) {
+                    Ok(_) | Err(Error::UnknownEcosystem { .. }) => None,
+                    Err(err) => Some((purl, err)),
+                }
+            })
+            .collect()
+    }
+
+    /// Read a document in the JSON encoding.
+    pub fn from_json(json: &str) -> Result<Self, Error> {
+        let document: SpdxDocument = serde_json::from_str(json).map_err(invalid)?;
+        document.check_version()?;
+        Ok(document)
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("SPDX serialization cannot fail")
+    }
+
+    /// Read a document in the tag-value encoding.
+    ///
+    /// Tags this crate doesn't model are skipped, as are the tags of file and
+    /// snippet sections.
+    pub fn from_tag_value(text: &str) -> Result<Self, Error> {
+        let mut document = SpdxDocument {
+            spdx_version: String::new(),
+            data_license: String::new(),
+            spdx_id: String::new(),
+            name: String::new(),
+            document_namespace: String::new(),
+            creation_info: CreationInfo {
+                created: String::new(),
+                creators: Vec::new(),
+            },
+            packages: Vec::new(),
+            relationships: Vec::new(),
+            has_extracted_licensing_infos: Vec::new(),
+        };
+
+        let mut section = Section::Document;
+        let mut lines = text.lines().enumerate();
+        while let Some((index, line)) = lines.next() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let (tag, value) = line
+                .split_once(':')
+                .ok_or_else(|| invalid(format!("line {}: expected `Tag: value`", index + 1)))?;
+            let mut value = value.trim().to_owned();
+
+            // Multi-line values are wrapped in `<text>` tags.
+            if let Some(text) = value.strip_prefix("<text>") {
+                let mut text = text.to_owned();
+                while !text.ends_with("</text>") {
+                    let (_, line) = lines
+                        .next()
+                        .ok_or_else(|| invalid(format!("line {}: unclosed <text>", index + 1)))?;
+                    text.push('\n');
+                    text.push_str(line);
+                }
+                value = text.trim_end_matches("</text>").to_owned();
+            }
+
+            section = match tag {
+                "PackageName" => Section::Package,
+                "FileName" => Section::File,
+                "SnippetSPDXID" => Section::Snippet,
+                "LicenseID" => Section::License,
+                _ => section,
+            };
+            let package = match section {
+                Section::Package => document.packages.last_mut(),
+                _ => None,
+            };
+            match (tag, package) {
+                ("PackageName", _) => document.packages.push(SpdxPackage {
+                    spdx_id: String::new(),
+                    name: value,
+                    version_info: None,
+                    download_location: NOASSERTION.into(),
+                    files_analyzed: true,
+                    license_concluded: None,
+                    license_declared: None,
+                    external_refs: Vec::new(),
+                }),
+                ("SPDXID", Some(package)) => package.spdx_id = value,
+                ("SPDXID", None) if section == Section::Document => document.spdx_id = value,
+                ("PackageVersion", Some(package)) => package.version_info = Some(value),
+                ("PackageDownloadLocation", Some(package)) => package.download_location = value,
+                ("FilesAnalyzed", Some(package)) => package.files_analyzed = value == "true",
+                ("PackageLicenseConcluded", Some(package)) => {
+                    package.license_concluded = Some(value)
+                }
+                ("PackageLicenseDeclared", Some(package)) => package.license_declared = Some(value),
+                ("ExternalRef", Some(package)) => {
+                    let mut parts = value.splitn(3, ' ');
+                    match (parts.next(), parts.next(), parts.next()) {
+                        (Some(category), Some(reference_type), Some(locator)) => {
+                            package.external_refs.push(ExternalRef {
+                                reference_category: category.into(),
+                                reference_type: reference_type.into(),
+                                reference_locator: locator.trim().into(),
+                            })
+                        }
+                        _ => {
+                            return Err(invalid(format!(
+                                "line {}: expected `ExternalRef: CATEGORY type locator`",
+                                index + 1
+                            )))
+                        }
+                    }
+                }
+                ("Relationship", _) => {
+                    let parts: Vec<&str> = value.split_whitespace().collect();
+                    match parts[..] {
+                        [element, relationship_type, related] => {
+                            document.add_relationship(element, relationship_type, related)
+                        }
+                        _ => {
+                            return Err(invalid(format!(
+                                "line {}: expected `Relationship: element TYPE related`",
+                                index + 1
+                            )))
+                        }
+                    }
+                }
+                ("LicenseID", _) => {
+                    document
+                        .has_extracted_licensing_infos
+                        .push(ExtractedLicensingInfo {
+                            license_id: value,
+                            extracted_text: String::new(),
+                            name: None,
+                        })
+                }
+                ("ExtractedText", _) if section == Section::License => {
+                    if let Some(info) = document.has_extracted_licensing_infos.last_mut() {
+                        info.extracted_text = value;
+                    }
+                }
+                ("LicenseName", _) if section == Section::License => {
+                    if let Some(info) = document.has_extracted_licensing_infos.last_mut() {
+                        info.name = Some(value);
+                    }
+                }
+                ("SPDXVersion", _) => document.spdx_version = value,
+                ("DataLicense", _) => document.data_license = value,
+                ("DocumentName", _) => document.name = value,
+                ("DocumentNamespace", _) => document.document_namespace = value,
+                ("Creator", _) => document.creation_info.creators.push(value),
+                ("Created", _) => document.creation_info.created = value,
+                _ => (),
+            }
+        }
+
+        document.check_version()?;
+        Ok(document)
+    }
+
+    pub fn to_tag_value(&self) -> String {
+        let mut text = String::new();
+        write_tag(&mut text, "SPDXVersion", &self.spdx_version);
+        write_tag(&mut text, "DataLicense", &self.data_license);
+        write_tag(&mut text, "SPDXID", &self.spdx_id);
+        write_tag(&mut text, "DocumentName", &self.name);
+        write_tag(&mut text, "DocumentNamespace", &self.document_namespace);
+        for creator in &self.creation_info.creators {
+            write_tag(&mut text, "Creator", creator);
+        }
+        write_tag(&mut text, "Created", &self.creation_info.created);
+
+        for package in &self.packages {
+            text.push('\n');
+            write_tag(&mut text, "PackageName", &package.name);
+            write_tag(&mut text, "SPDXID", &package.spdx_id);
+            if let Some(version) = &package.version_info {
+                write_tag(&mut text, "PackageVersion", version);
+            }
+            write_tag(
+                &mut text,
+                "PackageDownloadLocation",
+                &package.download_location,
+            );
+            write_tag(
+                &mut text,
+                "FilesAnalyzed",
+                &package.files_analyzed.to_string(),
+            );
+            if let Some(license) = &package.license_concluded {
+                write_tag(&mut text, "PackageLicenseConcluded", license);
+            }
+            if let Some(license) = &package.license_declared {
+                write_tag(&mut text, "PackageLicenseDeclared", license);
+            }
+            for external_ref in &package.external_refs {
+                let value = format!(
+                    "{} {} {}",
+                    external_ref.reference_category,
+                    external_ref.reference_type,
+                    external_ref.reference_locator
+                );
+                write_tag(&mut text, "ExternalRef", &value);
+            }
+        }
+
+        for info in &self.has_extracted_licensing_infos {
+            text.push('\n');
+            write_tag(&mut text, "LicenseID", &info.license_id);
+            let _ = writeln!(text, "ExtractedText: <text>{}</text>", info.extracted_text);
+            if let Some(name) = &info.name {
+                write_tag(&mut text, "LicenseName", name);
+            }
+        }
+
+        if !self.relationships.is_empty() {
+            text.push('\n');
+        }
+        for relationship in &self.relationships {
+            let value = format!(
+                "{} {} {}",
+                relationship.spdx_element_id,
+                relationship.relationship_type,
+                relationship.related_spdx_element
+            );
+            write_tag(&mut text, "Relationship", &value);
+        }
+
+        text
+    }
+
+    fn check_version(&self) -> Result<(), Error> {
+        match self.spdx_version.as_str() {
+            version if version.starts_with("SPDX-2.") => Ok(()),
+            version => Err(invalid(format!("unsupported version {version:?}"))),
+        }
+    }
+}
+
+/// Section of a tag-value document, started by the first tag of a section
+/// like `PackageName`.
+#[derive(PartialEq, Clone, Copy)]
+enum Section {
+    Document,
+    Package,
+    File,
+    Snippet,
+    License,
+}
+
+/// Write a tag, wrapping values which span multiple lines in `<text>`.
+fn write_tag(text: &mut String, tag: &str, value: &str) {
+    let _ = match value.contains('\n') {
+        true => writeln!(text, "{tag}: <text>{value}</text>"),
+        false => writeln!(text, "{tag}: {value}"),
+    };
+}
+
+fn default_files_analyzed() -> bool {
+    true
+}
+
+fn invalid(reason: impl ToString) -> Error {
+    Error::InvalidDocument {
+        format: FORMAT,
+        reason: reason.to_string(),
+    }
+}
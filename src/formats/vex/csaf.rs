@@ -0,0 +1,484 @@
+//! CSAF 2.0 documents of the VEX profile. See
+//! <https://docs.oasis-open.org/csaf/csaf/v2.0/csaf-v2.0.html>.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::error::Error;
+use crate::formats::vex::{
+    findings, ignored_reason, suppressions, vex_status, Justification, VexStatus, AFFECTED_ACTION,
+    IGNORED_IMPACT,
+};
+use crate::policy::ignore::Suppression;
+use crate::types::job::JobStatusResponse;
+use crate::types::package::PackageStatusExtended;
+use crate::types::preferences::IgnoredIssue;
+
+const FORMAT: &str = "CSAF";
+const CATEGORY: &str = "csaf_vex";
+const CSAF_VERSION: &str = "2.0";
+
+/// A CSAF document of the VEX profile.
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
+pub struct CsafVex {
+    pub document: DocumentMetadata,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub product_tree: Option<ProductTree>,
+    #[serde(default)]
+    pub vulnerabilities: Vec<CsafVulnerability>,
+}
+
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
+pub struct DocumentMetadata {
+    /// The document's profile, `csaf_vex` for VEX documents.
+    pub category: String,
+    pub csaf_version: String,
+    pub publisher: Publisher,
+    pub title: String,
+    pub tracking: Tracking,
+}
+
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
+pub struct Publisher {
+    /// Kind of publisher, like `vendor` or `user`.
+    pub category: String,
+    pub name: String,
+    /// URL of the publisher's organization.
+    pub namespace: String,
+}
+
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
+pub struct Tracking {
+    pub id: String,
+    /// One of `draft`, `interim` or `final`.
+    pub status: String,
+    pub version: String,
+    pub initial_release_date: DateTime<Utc>,
+    pub current_release_date: DateTime<Utc>,
+    #[serde(default)]
+    pub revision_history: Vec<Revision>,
+}
+
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
+pub struct Revision {
+    pub date: DateTime<Utc>,
+    pub number: String,
+    pub summary: String,
+}
+
+#[derive(PartialEq, Clone, Debug, Default, Serialize, Deserialize)]
+pub struct ProductTree {
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub branches: Vec<Branch>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub full_product_names: Vec<FullProductName>,
+}
+
+/// A level of the product hierarchy, like a vendor or a product version.
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
+pub struct Branch {
+    pub category: String,
+    pub name: String,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub branches: Vec<Branch>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub product: Option<FullProductName>,
+}
+
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
+pub struct FullProductName {
+    pub name: String,
+    pub product_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub product_identification_helper: Option<ProductIdentificationHelper>,
+}
+
+#[derive(PartialEq, Clone, Debug, Default, Serialize, Deserialize)]
+pub struct ProductIdentificationHelper {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub purl: Option<String>,
+}
+
+#[derive(PartialEq, Clone, Debug, Default, Serialize, Deserialize)]
+pub struct CsafVulnerability {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cve: Option<String>,
+    /// Identifiers of vulnerabilities without a CVE.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub ids: Vec<VulnerabilityId>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub notes: Vec<Note>,
+    #[serde(default)]
+    pub product_status: ProductStatus,
+    /// Justifications of products which are not affected.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub flags: Vec<Flag>,
+    /// Impact statements of products which are not affected, for products
+    /// without a justification.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub threats: Vec<Threat>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub remediations: Vec<Remediation>,
+}
+
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
+pub struct VulnerabilityId {
+    pub system_name: String,
+    pub text: String,
+}
+
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
+pub struct Note {
+    /// Kind of note, like `description` or `summary`.
+    pub category: String,
+    pub text: String,
+}
+
+/// Products by status. Statuses without a VEX equivalent are not listed.
+#[derive(PartialEq, Clone, Debug, Default, Serialize, Deserialize)]
+pub struct ProductStatus {
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub known_affected: Vec<String>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub known_not_affected: Vec<String>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub fixed: Vec<String>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub first_fixed: Vec<String>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub under_investigation: Vec<String>,
+}
+
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
+pub struct Flag {
+    pub label: Justification,
+    #[serde(default)]
+    pub product_ids: Vec<String>,
+}
+
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
+pub struct Threat {
+    /// Kind of threat, `impact` for impact statements.
+    pub category: String,
+    pub details: String,
+    #[serde(default)]
+    pub product_ids: Vec<String>,
+}
+
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
+pub struct Remediation {
+    /// Kind of remediation, like `vendor_fix` or `none_available`.
+    pub category: String,
+    pub details: String,
+    #[serde(default)]
+    pub product_ids: Vec<String>,
+}
+
+impl CsafVex {
+    /// An empty document, published by `publisher` from `namespace`.
+    pub fn new(
+        id: &str,
+        title: &str,
+        publisher: &str,
+        namespace: &str,
+        timestamp: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            document: DocumentMetadata {
+                category: CATEGORY.into(),
+                csaf_version: CSAF_VERSION.into(),
+                publisher: Publisher {
+                    category: "user".into(),
+                    name: publisher.into(),
+                    namespace: namespace.into(),
+                },
+                title: title.into(),
+                tracking: Tracking {
+                    id: id.into(),
+                    status: "final".into(),
+                    version: "1".into(),
+                    initial_release_date: timestamp,
+                    current_release_date: timestamp,
+                    revision_history: vec![Revision {
+                        date: timestamp,
+                        number: "1".into(),
+                        summary: "Initial version.".into(),
+                    }],
+                },
+            },
+            product_tree: None,
+            vulnerabilities: Vec::new(),
+        }
+    }
+
+    /// Vulnerabilities for the vulnerability and malicious code issues of a
+    /// job.
+    ///
+    /// Ignored issues, either in the job or through `ignored_issues`, are
+    /// `known_not_affected`, all others are `known_affected`. Every package
+    /// is a product identified by its package URL.
+    pub fn from_job(
+        job: &JobStatusResponse<PackageStatusExtended>,
+        ignored_issues: &[IgnoredIssue],
+        publisher: &str,
+        namespace: &str,
+        timestamp: DateTime<Utc>,
+    ) -> Self {
+        let title = format!("VEX for {}", job.project_name);
+        let mut vex = Self::new(
+            &job.job_id.to_string(),
+            &title,
+            publisher,
+            namespace,
+            timestamp,
+        );
+        let mut product_tree = ProductTree::default();
+
+        for finding in findings(job, ignored_issues) {
+            let name = finding.vulnerability;
+            let product_id = finding.product_id;
+            if !product_tree
+                .full_product_names
+                .iter()
+                .any(|product| product.product_id == product_id)
+            {
+                product_tree.full_product_names.push(FullProductName {
+                    name: format!("{} {}", finding.package.name, finding.package.version),
+                    product_id: product_id.clone(),
+                    product_identification_helper: finding
+                        .purl
+                        .map(|purl| ProductIdentificationHelper { purl: Some(purl) }),
+                });
+            }
+
+            let index = match vex
+                .vulnerabilities
+                .iter()
+                .position(|vulnerability| vulnerability.name() == Some(name))
+            {
+                Some(index) => index,
+                None => {
+                    vex.vulnerabilities.push(vulnerability(
+                        name,
+                        finding.title,
+                        finding.description,
+                    ));
+                    vex.vulnerabilities.len() - 1
+                }
+            };
+            let vulnerability = &mut vex.vulnerabilities[index];
+
+            let status = &mut vulnerability.product_status;
+            if status.known_affected.contains(&product_id)
+                || status.known_not_affected.contains(&product_id)
+            {
+                continue;
+            }
+
+            match vex_status(finding.reason) {
+                (VexStatus::NotAffected, Some(justification)) => {
+                    status.known_not_affected.push(product_id.clone());
+                    match vulnerability
+                        .flags
+                        .iter_mut()
+                        .find(|flag| flag.label == justification)
+                    {
+                        Some(flag) => flag.product_ids.push(product_id),
+                        None => vulnerability.flags.push(Flag {
+                            label: justification,
+                            product_ids: vec![product_id],
+                        }),
+                    }
+                }
+                (VexStatus::NotAffected, None) => {
+                    status.known_not_affected.push(product_id.clone());
+                    match vulnerability.threats.first_mut() {
+                        Some(threat) => threat.product_ids.push(product_id),
+                        None => vulnerability.threats.push(Threat {
+                            category: "impact".into(),
+                            details: IGNORED_IMPACT.into(),
+                            product_ids: vec![product_id],
+                        }),
+                    }
+                }
+                _ => {
+                    status.known_affected.push(product_id.clone());
+                    match vulnerability.remediations.first_mut() {
+                        Some(remediation) => remediation.product_ids.push(product_id),
+                        None => vulnerability.remediations.push(Remediation {
+                            category: "vendor_fix".into(),
+                            details: AFFECTED_ACTION.into(),
+                            product_ids: vec![product_id],
+                        }),
+                    }
+                }
+            }
+        }
+
+        vex.product_tree = Some(product_tree);
+        vex
+    }
+
+    /// Read a document in the JSON encoding.
+    pub fn from_json(json: &str) -> Result<Self, Error> {
+        let vex: CsafVex = serde_json::from_str(json).map_err(|err| Error::InvalidDocument {
+            format: FORMAT,
+            reason: err.to_string(),
+        })?;
+
+        if vex.document.category != CATEGORY {
+            return Err(Error::InvalidDocument {
+                format: FORMAT,
+                reason: format!("unexpected document category {:?}", vex.document.category),
+            });
+        }
+
+        Ok(vex)
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("CSAF serialization cannot fail")
+    }
+
+    /// Package URLs of the products in the product tree, by product id.
+    pub fn purls(&self) -> HashMap<&str, &str> {
+        let mut purls = HashMap::new();
+        let tree = match &self.product_tree {
+            Some(tree) => tree,
+            None => return purls,
+        };
+
+        let mut products: Vec<&FullProductName> = tree.full_product_names.iter().collect();
+        let mut pending: Vec<&Branch> = tree.branches.iter().collect();
+        while let Some(branch) = pending.pop() {
+            pending.extend(&branch.branches);
+            products.extend(&branch.product);
+        }
+
+        for product in products {
+            let purl = product
+                .product_identification_helper
+                .as_ref()
+                .and_then(|helper| helper.purl.as_deref());
+            if let Some(purl) = purl {
+                purls.insert(product.product_id.as_str(), purl);
+            }
+        }
+        purls
+    }
+
+    /// Suppressions for the issues of products which are not affected or
+    /// fixed, approved by the document's publisher.
+    ///
+    /// Products are matched by the package URL of the product tree, products
+    /// without one are skipped.
+    pub fn suppressions(&self) -> Vec<Suppression> {
+        let purls = self.purls();
+        let publisher = &self.document.publisher.name;
+
+        let mut all = Vec::new();
+        for vulnerability in &self.vulnerabilities {
+            let names: Vec<&str> = vulnerability
+                .cve
+                .iter()
+                .map(String::as_str)
+                .chain(vulnerability.ids.iter().map(|id| id.text.as_str()))
+                .collect();
+            let status = &vulnerability.product_status;
+
+            let fixed = status.fixed.iter().chain(&status.first_fixed);
+            for product_id in fixed {
+                let purl: Vec<&str> = purls
+                    .get(product_id.as_str())
+                    .copied()
+                    .into_iter()
+                    .collect();
+                all.extend(suppressions(
+                    &names,
+                    &purl,
+                    ignored_reason(VexStatus::Fixed, None),
+                    None,
+                    Some(publisher),
+                ));
+            }
+
+            for product_id in &status.known_not_affected {
+                let justification = vulnerability
+                    .flags
+                    .iter()
+                    .find(|flag| flag.product_ids.contains(product_id))
+                    .map(|flag| flag.label);
+                let impact = vulnerability
+                    .threats
+                    .iter()
+                    .find(|threat| {
+                        threat.category == "impact" && threat.product_ids.contains(product_id)
+                    })
+                    .map(|threat| threat.details.clone());
+
+                let purl: Vec<&str> = purls
+                    .get(product_id.as_str())
+                    .copied()
+                    .into_iter()
+                    .collect();
+                all.extend(suppressions(
+                    &names,
+                    &purl,
+                    ignored_reason(VexStatus::NotAffected, justification),
+                    impact.or_else(|| {
+                        justification.map(|justification| justification.as_str().into())
+                    }),
+                    Some(publisher),
+                ));
+            }
+        }
+        all
+    }
+}
+
+impl CsafVulnerability {
+    /// The CVE of the vulnerability, or its first other identifier.
+    pub fn name(&self) -> Option<&str> {
+        self.cve
+            .as_deref()
+            .or_else(|| self.ids.first().map(|id| id.text.as_str()))
+    }
+}
+
+/// A vulnerability without products, by CVE if it has one.
+fn vulnerability(name: &str, title: &str, description: &str) -> CsafVulnerability {
+    let is_cve = name.starts_with("CVE-");
+    CsafVulnerability {
+        cve: Some(name.into()).filter(|_| is_cve),
+        ids: match is_cve {
+            true => Vec::new(),
+            false => vec![VulnerabilityId {
+                system_name: "Phylum".into(),
+                text: name.into(),
+            }],
+        },
+        title: Some(title.into()),
+        notes: vec![Note {
+            category: "description".into(),
+            text: description.into(),
+        }],
+        ..CsafVulnerability::default()
+    }
+}
@@ -0,0 +1,237 @@
+//! Vulnerability Exploitability eXchange statements as OpenVEX and CSAF VEX
+//! documents.
+//!
+//! VEX statements say whether a product is affected by a vulnerability. They
+//! are generated from the issues of a job, where ignored issues become
+//! `not_affected` statements justified by their [`IgnoredReason`]. VEX
+//! documents of third parties are read back as [`Suppression`]s, which
+//! ignore the issues their statements rule out:
+//!
+//! ```
+//! use chrono::{TimeZone, Utc};
+//! use phylum_types::formats::vex::openvex::OpenVex;
+//! use phylum_types::policy::ignore::SuppressionApplicator;
+//! # use phylum_types::types::package::{PackageDescriptor, PackageType};
+//! # let package = PackageDescriptor {
+//! #     name: "minimist".into(),
+//! #     version: "1.2.5".into(),
+//! #     package_type: PackageType::Npm,
+//! # };
+//! # let mut issues: Vec<phylum_types::types::package::IssueStatus> = Vec::new();
+//! # let json = r#"{"@context": "https://openvex.dev/ns/v0.2.0", "@id": "urn:uuid:1", "author": "Vendor", "timestamp": "2023-03-10T00:00:00Z", "version": 1, "statements": []}"#;
+//!
+//! let vex = OpenVex::from_json(json).unwrap();
+//! let now = Utc.with_ymd_and_hms(2023, 3, 10, 0, 0, 0).unwrap();
+//! let mut applicator = SuppressionApplicator::new(vex.suppressions(), now);
+//! applicator.apply(&package, &mut issues);
+//! ```
+//!
+//! [`Suppression`]: crate::policy::ignore::Suppression
+
+pub mod csaf;
+pub mod openvex;
+
+use serde::{Deserialize, Serialize};
+
+use crate::policy::ignore::Suppression;
+use crate::types::job::JobStatusResponse;
+use crate::types::package::{IgnoredReason, PackageDescriptor, PackageStatusExtended, RiskDomain};
+use crate::types::package_url::name_and_version;
+use crate::types::preferences::IgnoredIssue;
+
+/// Impact statement of issues ignored for a reason without a matching
+/// justification.
+const IGNORED_IMPACT: &str = "The issue was reviewed and does not apply to this package.";
+/// Action statement of issues which are not ignored.
+const AFFECTED_ACTION: &str = "Update the package to a version which is not affected.";
+
+/// Whether a product is affected by a vulnerability.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Copy, Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VexStatus {
+    NotAffected,
+    Affected,
+    Fixed,
+    UnderInvestigation,
+}
+
+/// Why a product is not affected by a vulnerability.
+///
+/// OpenVEX justifications and CSAF flag labels share these values.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Copy, Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Justification {
+    ComponentNotPresent,
+    VulnerableCodeNotPresent,
+    VulnerableCodeNotInExecutePath,
+    VulnerableCodeCannotBeControlledByAdversary,
+    InlineMitigationsAlreadyExist,
+}
+
+impl Justification {
+    /// The value in VEX documents, like `vulnerable_code_not_present`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Justification::ComponentNotPresent => "component_not_present",
+            Justification::VulnerableCodeNotPresent => "vulnerable_code_not_present",
+            Justification::VulnerableCodeNotInExecutePath => "vulnerable_code_not_in_execute_path",
+            Justification::VulnerableCodeCannotBeControlledByAdversary => {
+                "vulnerable_code_cannot_be_controlled_by_adversary"
+            }
+            Justification::InlineMitigationsAlreadyExist => "inline_mitigations_already_exist",
+        }
+    }
+}
+
+/// The VEX status of an issue ignored for `reason`.
+///
+/// Issues which are not ignored affect the package. False positives are not
+/// present in the package, and issues which are not relevant can't be
+/// reached. Issues ignored for other reasons are not affected without a
+/// justification.
+pub fn vex_status(reason: IgnoredReason) -> (VexStatus, Option<Justification>) {
+    match reason {
+        IgnoredReason::False => (VexStatus::Affected, None),
+        IgnoredReason::FalsePositive => (
+            VexStatus::NotAffected,
+            Some(Justification::VulnerableCodeNotPresent),
+        ),
+        IgnoredReason::NotRelevant => (
+            VexStatus::NotAffected,
+            Some(Justification::VulnerableCodeNotInExecutePath),
+        ),
+        IgnoredReason::Other => (VexStatus::NotAffected, None),
+    }
+}
+
+/// The reason to ignore issues of a VEX statement, the inverse of
+/// [`vex_status`].
+///
+/// Packages which are not affected because the vulnerability isn't present,
+/// or which contain a fix, are false positives. Any other justification
+/// makes the issue not relevant. Statements which don't rule out the
+/// vulnerability don't ignore anything.
+pub fn ignored_reason(status: VexStatus, justification: Option<Justification>) -> IgnoredReason {
+    match (status, justification) {
+        (VexStatus::Affected | VexStatus::UnderInvestigation, _) => IgnoredReason::False,
+        (VexStatus::Fixed, _)
+        | (
+            VexStatus::NotAffected,
+            Some(Justification::ComponentNotPresent | Justification::VulnerableCodeNotPresent),
+        ) => IgnoredReason::FalsePositive,
+        (VexStatus::NotAffected, Some(_)) => IgnoredReason::NotRelevant,
+        (VexStatus::NotAffected, None) => IgnoredReason::Other,
+    }
+}
+
+/// An issue of a package in a job, with the reason it is ignored.
+struct Finding<'a> {
+    /// The vulnerability, by tag or id.
+    vulnerability: &'a str,
+    title: &'a str,
+    description: &'a str,
+    package: PackageDescriptor,
+    /// The package URL, or the name and version if the package has none.
+    product_id: String,
+    purl: Option<String>,
+    reason: IgnoredReason,
+}
+
+/// Issues in the vulnerability and malicious code domains of a job.
+///
+/// Issues which are not ignored in the job take the reason of the matching
+/// entry of `ignored_issues`, if any.
+fn findings<'a>(
+    job: &'a JobStatusResponse<PackageStatusExtended>,
+    ignored_issues: &[IgnoredIssue],
+) -> Vec<Finding<'a>> {
+    let mut findings = Vec::new();
+    for package in &job.packages {
+        let descriptor = PackageDescriptor {
+            name: package.basic_status.name.clone(),
+            version: package.basic_status.version.clone(),
+            package_type: package.package_type.clone(),
+        };
+        let purl = descriptor.to_purl().ok();
+        let product_id = purl
+            .clone()
+            .unwrap_or_else(|| format!("{}@{}", descriptor.name, descriptor.version));
+
+        for status in &package.issues {
+            let issue = &status.issue;
+            if !matches!(
+                issue.domain,
+                RiskDomain::Vulnerabilities | RiskDomain::Malicious
+            ) {
+                continue;
+            }
+            let vulnerability = match issue.tag.as_deref().or(issue.id.as_deref()) {
+                Some(vulnerability) => vulnerability,
+                None => continue,
+            };
+
+            let reason = match status.ignored {
+                IgnoredReason::False => ignored_issues
+                    .iter()
+                    .find(|ignored| {
+                        issue.id.as_deref() == Some(ignored.id.as_str())
+                            && issue.tag.as_deref() == Some(ignored.tag.as_str())
+                    })
+                    .map_or(IgnoredReason::False, |ignored| ignored.reason),
+                reason => reason,
+            };
+
+            findings.push(Finding {
+                vulnerability,
+                title: &issue.title,
+                description: &issue.description,
+                package: descriptor.clone(),
+                product_id: product_id.clone(),
+                purl: purl.clone(),
+                reason,
+            });
+        }
+    }
+    findings
+}
+
+/// Suppressions of a statement about packages by package URL.
+///
+/// Issues are matched by tag against the vulnerability and its aliases.
+/// Packages are matched by canonical name, qualifiers of package URLs are
+/// dropped, and package URLs without a version match every version. Invalid
+/// package URLs are skipped.
+fn suppressions(
+    names: &[&str],
+    purls: &[&str],
+    reason: IgnoredReason,
+    justification: Option<String>,
+    approved_by: Option<&str>,
+) -> Vec<Suppression> {
+    if reason == IgnoredReason::False {
+        return Vec::new();
+    }
+
+    let mut suppressions = Vec::new();
+    for purl in purls {
+        let purl = purl.split(['?', '#']).next().unwrap_or_default();
+        if purl.contains('*') || name_and_version(purl).is_err() {
+            continue;
+        }
+
+        for name in names {
+            suppressions.push(Suppression {
+                id: None,
+                tag: Some((*name).into()),
+                reason,
+                package: None,
+                versions: None,
+                purl: Some(purl.into()),
+                expires_at: None,
+                justification: justification.clone(),
+                approved_by: approved_by.map(String::from),
+            });
+        }
+    }
+    suppressions
+}
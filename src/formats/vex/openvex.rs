@@ -0,0 +1,215 @@
+//! OpenVEX documents. See <https://github.com/openvex/spec>.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::error::Error;
+use crate::formats::vex::{
+    findings, ignored_reason, suppressions, vex_status, Justification, VexStatus, AFFECTED_ACTION,
+    IGNORED_IMPACT,
+};
+use crate::policy::ignore::Suppression;
+use crate::types::job::JobStatusResponse;
+use crate::types::package::PackageStatusExtended;
+use crate::types::preferences::IgnoredIssue;
+
+const FORMAT: &str = "OpenVEX";
+const CONTEXT: &str = "https://openvex.dev/ns/v0.2.0";
+
+/// An OpenVEX document.
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
+pub struct OpenVex {
+    #[serde(rename = "@context")]
+    pub context: String,
+    #[serde(rename = "@id")]
+    pub id: String,
+    pub author: String,
+    pub timestamp: DateTime<Utc>,
+    #[serde(default = "default_version")]
+    pub version: u32,
+    #[serde(default)]
+    pub statements: Vec<Statement>,
+}
+
+fn default_version() -> u32 {
+    1
+}
+
+/// The status of products for a vulnerability.
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
+pub struct Statement {
+    pub vulnerability: VexVulnerability,
+    pub products: Vec<Product>,
+    pub status: VexStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub justification: Option<Justification>,
+    /// Why the products are not affected, for statements without a
+    /// justification.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub impact_statement: Option<String>,
+    /// What to do about affected products.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub action_statement: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status_notes: Option<String>,
+}
+
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
+pub struct VexVulnerability {
+    /// The vulnerability's identifier, like `CVE-2021-44906`.
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Other identifiers of the same vulnerability.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub aliases: Vec<String>,
+}
+
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
+pub struct Product {
+    /// The product's identifier, usually a package URL.
+    #[serde(rename = "@id")]
+    pub id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub identifiers: Option<Identifiers>,
+}
+
+impl Product {
+    /// The package URL of the product, if it has one.
+    pub fn purl(&self) -> Option<&str> {
+        self.identifiers
+            .as_ref()
+            .and_then(|identifiers| identifiers.purl.as_deref())
+            .or_else(|| Some(self.id.as_str()).filter(|id| id.starts_with("pkg:")))
+    }
+}
+
+#[derive(PartialEq, Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Identifiers {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub purl: Option<String>,
+}
+
+impl OpenVex {
+    /// An empty document.
+    pub fn new(id: &str, author: &str, timestamp: DateTime<Utc>) -> Self {
+        Self {
+            context: CONTEXT.into(),
+            id: id.into(),
+            author: author.into(),
+            timestamp,
+            version: 1,
+            statements: Vec::new(),
+        }
+    }
+
+    /// Statements for the vulnerability and malicious code issues of a job.
+    ///
+    /// Ignored issues, either in the job or through `ignored_issues`, are
+    /// `not_affected`, all others are `affected`. Packages with the same
+    /// status for a vulnerability share a statement.
+    pub fn from_job(
+        job: &JobStatusResponse<PackageStatusExtended>,
+        ignored_issues: &[IgnoredIssue],
+        author: &str,
+        timestamp: DateTime<Utc>,
+    ) -> Self {
+        let mut vex = Self::new(&format!("urn:uuid:{}", job.job_id), author, timestamp);
+
+        for finding in findings(job, ignored_issues) {
+            let name = finding.vulnerability;
+            let (status, justification) = vex_status(finding.reason);
+            let product = Product {
+                id: finding.product_id,
+                identifiers: finding.purl.map(|purl| Identifiers { purl: Some(purl) }),
+            };
+
+            let existing = vex.statements.iter_mut().find(|statement| {
+                statement.vulnerability.name == name
+                    && statement.status == status
+                    && statement.justification == justification
+            });
+            if let Some(statement) = existing {
+                if !statement.products.contains(&product) {
+                    statement.products.push(product);
+                }
+                continue;
+            }
+
+            let not_affected = status == VexStatus::NotAffected;
+            vex.statements.push(Statement {
+                vulnerability: VexVulnerability {
+                    name: name.into(),
+                    description: Some(finding.title.into()),
+                    aliases: Vec::new(),
+                },
+                products: vec![product],
+                status,
+                justification,
+                impact_statement: Some(IGNORED_IMPACT.into())
+                    .filter(|_| not_affected && justification.is_none()),
+                action_statement: Some(AFFECTED_ACTION.into()).filter(|_| !not_affected),
+                status_notes: None,
+            });
+        }
+
+        vex
+    }
+
+    /// Read a document in the JSON encoding.
+    pub fn from_json(json: &str) -> Result<Self, Error> {
+        let vex: OpenVex = serde_json::from_str(json).map_err(|err| Error::InvalidDocument {
+            format: FORMAT,
+            reason: err.to_string(),
+        })?;
+
+        if !vex.context.starts_with("https://openvex.dev/ns") {
+            return Err(Error::InvalidDocument {
+                format: FORMAT,
+                reason: format!("unexpected context {:?}", vex.context),
+            });
+        }
+
+        Ok(vex)
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("OpenVEX serialization cannot fail")
+    }
+
+    /// Suppressions for the issues the statements rule out, approved by the
+    /// document's author.
+    ///
+    /// Products are matched by package URL, products without one are
+    /// skipped.
+    pub fn suppressions(&self) -> Vec<Suppression> {
+        let mut all = Vec::new();
+        for statement in &self.statements {
+            let vulnerability = &statement.vulnerability;
+            let names: Vec<&str> = Some(vulnerability.name.as_str())
+                .into_iter()
+                .chain(vulnerability.aliases.iter().map(String::as_str))
+                .collect();
+            let purls: Vec<&str> = statement
+                .products
+                .iter()
+                .filter_map(Product::purl)
+                .collect();
+            let justification = statement.impact_statement.clone().or_else(|| {
+                statement
+                    .justification
+                    .map(|justification| justification.as_str().into())
+            });
+
+            all.extend(suppressions(
+                &names,
+                &purls,
+                ignored_reason(statement.status, statement.justification),
+                justification,
+                Some(&self.author),
+            ));
+        }
+        all
+    }
+}
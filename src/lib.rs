@@ -1,5 +1,10 @@
 //! This crate contains common types used to share data between the Phylum API
 //! and CLI tool.
 
+pub mod cvss;
 pub mod ecosystems;
+pub mod error;
+pub mod formats;
+pub mod license;
+pub mod policy;
 pub mod types;
@@ -0,0 +1,650 @@
+//! SPDX license expressions.
+//!
+//! Licenses reported by registries are often not valid SPDX expressions:
+//! Maven POMs use names like `The Apache Software License, Version 2.0`, and
+//! old crates use `MIT/Apache-2.0`. [`LicenseExpression::normalize`] maps
+//! common spellings to SPDX identifiers, while [`str::parse`] only accepts
+//! valid expressions.
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::error::Error;
+
+mod spdx;
+
+/// A parsed SPDX license expression, like `MIT OR Apache-2.0`.
+#[derive(PartialEq, Eq, Hash, Clone, Debug)]
+pub enum LicenseExpression {
+    License(SimpleLicense),
+    /// All licenses apply.
+    And(Vec<LicenseExpression>),
+    /// Any of the licenses can be chosen.
+    Or(Vec<LicenseExpression>),
+}
+
+/// A single license, with an optional exception.
+#[derive(PartialEq, Eq, Hash, Clone, Debug)]
+pub struct SimpleLicense {
+    /// SPDX identifier or `LicenseRef-` reference.
+    pub id: String,
+    /// Whether later versions of the license may be used, written as `+`.
+    pub or_later: bool,
+    /// Exception like `Classpath-exception-2.0`, written after `WITH`.
+    pub exception: Option<String>,
+}
+
+impl SimpleLicense {
+    pub fn new(id: &str) -> Self {
+        Self {
+            id: id.into(),
+            or_later: false,
+            exception: None,
+        }
+    }
+
+    /// Check if this is a reference to a license not on the SPDX list.
+    pub fn is_license_ref(&self) -> bool {
+        self.id.starts_with("LicenseRef-") || self.id.starts_with("DocumentRef-")
+    }
+}
+
+impl fmt::Display for SimpleLicense {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.id)?;
+        if self.or_later {
+            f.write_str("+")?;
+        }
+        if let Some(exception) = &self.exception {
+            write!(f, " WITH {exception}")?;
+        }
+        Ok(())
+    }
+}
+
+impl LicenseExpression {
+    /// Parse a license, accepting common non-SPDX spellings.
+    ///
+    /// Known license names are mapped to their SPDX identifier, identifiers
+    /// are corrected to their canonical case and deprecated identifiers like
+    /// `GPL-2.0` are replaced. Licenses separated by `/` are alternatives.
+    /// Other names and identifiers which are not on the SPDX license list
+    /// become a `LicenseRef-` reference. Placeholders like `UNKNOWN` or
+    /// `SEE LICENSE IN LICENSE.md` are not a license and are rejected.
+    pub fn normalize(license: &str) -> Result<Self, Error> {
+        let license = license.trim();
+        if is_placeholder(license) {
+            return Err(invalid(license, "no license"));
+        }
+
+        if let Some(id) = alias(license) {
+            return Ok(LicenseExpression::License(SimpleLicense::new(id)));
+        }
+
+        if let Ok(expression) = license.parse::<LicenseExpression>() {
+            return expression.canonicalize();
+        }
+
+        if license.contains('/') {
+            let alternatives = license
+                .split('/')
+                .map(LicenseExpression::normalize)
+                .collect::<Result<Vec<_>, _>>()?;
+            return Ok(LicenseExpression::or(alternatives));
+        }
+
+        Ok(LicenseExpression::License(SimpleLicense::new(
+            &license_ref(license),
+        )))
+    }
+
+    /// All licenses in the expression.
+    pub fn licenses(&self) -> Vec<&SimpleLicense> {
+        match self {
+            LicenseExpression::License(license) => vec![license],
+            LicenseExpression::And(expressions) | LicenseExpression::Or(expressions) => {
+                expressions.iter().flat_map(|e| e.licenses()).collect()
+            }
+        }
+    }
+
+    /// The sets of licenses that can be chosen to comply with the
+    /// expression, all licenses of a set apply.
+    ///
+    /// The number of sets grows exponentially with `AND`s of `OR`s, use
+    /// [`Self::is_satisfied_by`] to check if an expression can be complied
+    /// with.
+    pub fn choices(&self) -> Vec<Vec<&SimpleLicense>> {
+        match self {
+            LicenseExpression::License(license) => vec![vec![license]],
+            LicenseExpression::Or(expressions) => {
+                expressions.iter().flat_map(|e| e.choices()).collect()
+            }
+            LicenseExpression::And(expressions) => {
+                expressions.iter().fold(vec![Vec::new()], |choices, e| {
+                    let mut combined = Vec::new();
+                    for choice in &choices {
+                        for other in e.choices() {
+                            let mut choice = choice.clone();
+                            choice.extend(other);
+                            combined.push(choice);
+                        }
+                    }
+                    combined
+                })
+            }
+        }
+    }
+
+    /// Check if the expression can be complied with using only licenses the
+    /// predicate accepts.
+    pub fn is_satisfied_by<F>(&self, accept: F) -> bool
+    where
+        F: Fn(&SimpleLicense) -> bool,
+    {
+        self.satisfies(&accept)
+    }
+
+    fn satisfies(&self, accept: &dyn Fn(&SimpleLicense) -> bool) -> bool {
+        match self {
+            LicenseExpression::License(license) => accept(license),
+            LicenseExpression::And(expressions) => expressions.iter().all(|e| e.satisfies(accept)),
+            LicenseExpression::Or(expressions) => expressions.iter().any(|e| e.satisfies(accept)),
+        }
+    }
+
+    fn canonicalize(self) -> Result<Self, Error> {
+        let canonicalize = |expressions: Vec<LicenseExpression>| {
+            expressions
+                .into_iter()
+                .map(Self::canonicalize)
+                .collect::<Result<Vec<_>, _>>()
+        };
+
+        Ok(match self {
+            LicenseExpression::License(license) => {
+                LicenseExpression::License(canonical_license(license)?)
+            }
+            LicenseExpression::And(expressions) => {
+                LicenseExpression::and(canonicalize(expressions)?)
+            }
+            LicenseExpression::Or(expressions) => LicenseExpression::or(canonicalize(expressions)?),
+        })
+    }
+
+    fn and(expressions: Vec<LicenseExpression>) -> Self {
+        Self::combine(expressions, true)
+    }
+
+    fn or(expressions: Vec<LicenseExpression>) -> Self {
+        Self::combine(expressions, false)
+    }
+
+    /// Combine expressions, flattening nested expressions of the same kind.
+    fn combine(expressions: Vec<LicenseExpression>, and: bool) -> Self {
+        let mut flattened = Vec::new();
+        for expression in expressions {
+            match expression {
+                LicenseExpression::And(nested) if and => flattened.extend(nested),
+                LicenseExpression::Or(nested) if !and => flattened.extend(nested),
+                expression => flattened.push(expression),
+            }
+        }
+
+        match (flattened.len(), and) {
+            (1, _) => flattened.remove(0),
+            (_, true) => LicenseExpression::And(flattened),
+            (_, false) => LicenseExpression::Or(flattened),
+        }
+    }
+}
+
+impl fmt::Display for LicenseExpression {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LicenseExpression::License(license) => license.fmt(f),
+            LicenseExpression::And(expressions) => {
+                for (index, expression) in expressions.iter().enumerate() {
+                    if index > 0 {
+                        f.write_str(" AND ")?;
+                    }
+                    match expression {
+                        LicenseExpression::Or(_) => write!(f, "({expression})")?,
+                        expression => expression.fmt(f)?,
+                    }
+                }
+                Ok(())
+            }
+            LicenseExpression::Or(expressions) => {
+                for (index, expression) in expressions.iter().enumerate() {
+                    if index > 0 {
+                        f.write_str(" OR ")?;
+                    }
+                    expression.fmt(f)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Parse a valid SPDX license expression.
+///
+/// Operators may be upper or lower case. Identifiers are kept as written.
+impl FromStr for LicenseExpression {
+    type Err = Error;
+
+    fn from_str(expression: &str) -> Result<Self, Self::Err> {
+        let tokens = tokenize(expression);
+        let mut parser = Parser {
+            expression,
+            tokens: &tokens,
+            position: 0,
+            depth: 0,
+        };
+
+        let parsed = parser.or()?;
+        match parser.tokens.get(parser.position) {
+            None => Ok(parsed),
+            Some(token) => Err(invalid(expression, &format!("unexpected {token}"))),
+        }
+    }
+}
+
+#[derive(PartialEq, Clone, Debug)]
+enum Token<'a> {
+    Open,
+    Close,
+    And,
+    Or,
+    With,
+    Id(&'a str),
+}
+
+impl fmt::Display for Token<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Token::Open => f.write_str("`(`"),
+            Token::Close => f.write_str("`)`"),
+            Token::And => f.write_str("`AND`"),
+            Token::Or => f.write_str("`OR`"),
+            Token::With => f.write_str("`WITH`"),
+            Token::Id(id) => write!(f, "`{id}`"),
+        }
+    }
+}
+
+fn tokenize(expression: &str) -> Vec<Token<'_>> {
+    let mut tokens = Vec::new();
+    for word in expression.split_whitespace() {
+        let mut rest = word;
+        while !rest.is_empty() {
+            if let Some(after) = rest.strip_prefix('(') {
+                tokens.push(Token::Open);
+                rest = after;
+                continue;
+            }
+
+            let end = rest.find(['(', ')']).unwrap_or(rest.len());
+            let (word, after) = rest.split_at(end);
+            if !word.is_empty() {
+                tokens.push(match word {
+                    "AND" | "and" => Token::And,
+                    "OR" | "or" => Token::Or,
+                    "WITH" | "with" => Token::With,
+                    id => Token::Id(id),
+                });
+            }
+
+            rest = match after.strip_prefix(')') {
+                Some(after) => {
+                    tokens.push(Token::Close);
+                    after
+                }
+                None => after,
+            };
+        }
+    }
+    tokens
+}
+
+/// Deepest nesting of parentheses, far more than any real license needs.
+const MAX_DEPTH: usize = 32;
+
+/// Recursive descent parser, `WITH` binds tightest, then `AND`, then `OR`.
+struct Parser<'a> {
+    expression: &'a str,
+    tokens: &'a [Token<'a>],
+    position: usize,
+    /// Number of open parentheses, bounded so input can't overflow the
+    /// stack.
+    depth: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn or(&mut self) -> Result<LicenseExpression, Error> {
+        let mut expressions = vec![self.and()?];
+        while self.next_if(&Token::Or) {
+            expressions.push(self.and()?);
+        }
+        Ok(LicenseExpression::or(expressions))
+    }
+
+    fn and(&mut self) -> Result<LicenseExpression, Error> {
+        let mut expressions = vec![self.with()?];
+        while self.next_if(&Token::And) {
+            expressions.push(self.with()?);
+        }
+        Ok(LicenseExpression::and(expressions))
+    }
+
+    fn with(&mut self) -> Result<LicenseExpression, Error> {
+        let expression = match self.next() {
+            Some(Token::Open) => {
+                if self.depth == MAX_DEPTH {
+                    return Err(self.error("too deeply nested"));
+                }
+                self.depth += 1;
+                let expression = self.or()?;
+                self.depth -= 1;
+                if !self.next_if(&Token::Close) {
+                    return Err(self.error("missing `)`"));
+                }
+                return Ok(expression);
+            }
+            Some(Token::Id(id)) => *id,
+            Some(token) => return Err(self.error(&format!("unexpected {token}"))),
+            None => return Err(self.error("missing license")),
+        };
+
+        let (id, or_later) = match expression.strip_suffix('+') {
+            Some(id) => (id, true),
+            None => (expression, false),
+        };
+        if !is_id(id) {
+            return Err(self.error(&format!("invalid license identifier `{expression}`")));
+        }
+
+        let exception = match self.next_if(&Token::With) {
+            true => match self.next() {
+                Some(Token::Id(exception)) if is_id(exception) => Some(exception.to_string()),
+                _ => return Err(self.error("missing exception after `WITH`")),
+            },
+            false => None,
+        };
+
+        Ok(LicenseExpression::License(SimpleLicense {
+            id: id.into(),
+            or_later,
+            exception,
+        }))
+    }
+
+    fn next(&mut self) -> Option<&'a Token<'a>> {
+        let token = self.tokens.get(self.position);
+        self.position += 1;
+        token
+    }
+
+    fn next_if(&mut self, expected: &Token) -> bool {
+        let matches = self.tokens.get(self.position) == Some(expected);
+        if matches {
+            self.position += 1;
+        }
+        matches
+    }
+
+    fn error(&self, reason: &str) -> Error {
+        invalid(self.expression, reason)
+    }
+}
+
+/// Identifiers are letters, numbers, `.` and `-`, references may contain a
+/// `:` after the document.
+fn is_id(id: &str) -> bool {
+    let id = match id.strip_prefix("DocumentRef-") {
+        Some(reference) => match reference.split_once(':') {
+            Some((_, license_ref)) => license_ref,
+            None => return false,
+        },
+        None => id,
+    };
+    !id.is_empty()
+        && id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '-')
+}
+
+fn invalid(expression: &str, reason: &str) -> Error {
+    Error::InvalidLicense {
+        expression: expression.into(),
+        reason: reason.into(),
+    }
+}
+
+/// Identifiers deprecated in favor of `-only` and `-or-later` variants.
+const DEPRECATED: &[&str] = &[
+    "AGPL-1.0", "AGPL-3.0", "GFDL-1.1", "GFDL-1.2", "GFDL-1.3", "GPL-1.0", "GPL-2.0", "GPL-3.0",
+    "LGPL-2.0", "LGPL-2.1", "LGPL-3.0",
+];
+
+/// Names used by registries and package authors in place of a license,
+/// after [`alias_key`] simplification.
+const PLACEHOLDERS: &[&str] = &[
+    "",
+    "unknown",
+    "other",
+    "others",
+    "n a",
+    "na",
+    "none",
+    "noassertion",
+    "null",
+    "undefined",
+    "tbd",
+    "todo",
+    "custom",
+    "see file",
+];
+
+/// Names of licenses, after [`alias_key`] simplification.
+const ALIASES: &[(&str, &[&str])] = &[
+    ("0BSD", &["zero clause bsd", "bsd zero clause"]),
+    (
+        "AGPL-3.0-only",
+        &[
+            "agpl 3",
+            "agpl 3.0",
+            "agplv3",
+            "gnu affero general public 3",
+        ],
+    ),
+    (
+        "Apache-2.0",
+        &[
+            "apache 2",
+            "apache 2.0",
+            "apache software 2",
+            "apache software 2.0",
+            "asl 2.0",
+            "al 2.0",
+            "apache2",
+        ],
+    ),
+    ("Artistic-2.0", &["artistic 2", "artistic 2.0"]),
+    (
+        "BSD-2-Clause",
+        &["bsd 2 clause", "2 clause bsd", "simplified bsd", "freebsd"],
+    ),
+    (
+        "BSD-3-Clause",
+        &[
+            "bsd 3 clause",
+            "3 clause bsd",
+            "new bsd",
+            "revised bsd",
+            "modified bsd",
+        ],
+    ),
+    ("BSL-1.0", &["boost software 1.0", "boost"]),
+    ("CC0-1.0", &["cc0", "cc0 1.0", "cc0 1.0 universal"]),
+    (
+        "CDDL-1.0",
+        &["cddl 1.0", "common development and distribution 1.0"],
+    ),
+    ("EPL-1.0", &["epl 1.0", "eclipse public 1.0"]),
+    ("EPL-2.0", &["epl 2.0", "eclipse public 2.0"]),
+    (
+        "GPL-2.0-only",
+        &[
+            "gpl 2",
+            "gpl 2.0",
+            "gplv2",
+            "gnu general public 2",
+            "gnu gpl 2",
+        ],
+    ),
+    (
+        "GPL-2.0-or-later",
+        &["gpl 2+", "gplv2+", "gnu general public 2 or later"],
+    ),
+    (
+        "GPL-3.0-only",
+        &[
+            "gpl 3",
+            "gpl 3.0",
+            "gplv3",
+            "gnu general public 3",
+            "gnu gpl 3",
+        ],
+    ),
+    (
+        "GPL-3.0-or-later",
+        &["gpl 3+", "gplv3+", "gnu general public 3 or later"],
+    ),
+    ("ISC", &["isc"]),
+    (
+        "LGPL-2.1-only",
+        &["lgpl 2.1", "lgplv2.1", "gnu lesser general public 2.1"],
+    ),
+    (
+        "LGPL-3.0-only",
+        &[
+            "lgpl 3",
+            "lgpl 3.0",
+            "lgplv3",
+            "gnu lesser general public 3",
+        ],
+    ),
+    ("MIT", &["mit", "expat"]),
+    ("MPL-2.0", &["mpl 2", "mpl 2.0", "mozilla public 2.0"]),
+    ("PSF-2.0", &["psf", "psf 2", "python software foundation"]),
+    ("Unlicense", &["unlicense"]),
+    ("WTFPL", &["wtfpl"]),
+];
+
+/// SPDX identifier for a known license name.
+fn alias(license: &str) -> Option<&'static str> {
+    let key = alias_key(license);
+    ALIASES
+        .iter()
+        .find(|(_, names)| names.contains(&key.as_str()))
+        .map(|(id, _)| *id)
+}
+
+/// Simplify a license name, like `The Apache Software License, Version 2.0`
+/// to `apache software 2.0`. Repeated words like in `The MIT License (MIT)`
+/// are dropped.
+fn alias_key(license: &str) -> String {
+    let lowercase = license.to_lowercase();
+    let mut words: Vec<&str> = lowercase
+        .split(|c: char| !(c.is_alphanumeric() || c == '.' || c == '+'))
+        .filter(|word| {
+            !word.is_empty()
+                && !matches!(
+                    *word,
+                    "the" | "license" | "licence" | "licensed" | "version" | "v"
+                )
+        })
+        .map(|word| match word.strip_prefix('v') {
+            // Versions like `v2.0`.
+            Some(version) if version.starts_with(|c: char| c.is_ascii_digit()) => version,
+            _ => word,
+        })
+        .collect();
+    words.dedup();
+    words.join(" ")
+}
+
+/// Check if a license is a placeholder like `UNKNOWN` or `SEE LICENSE IN
+/// LICENSE.md` rather than an actual license.
+fn is_placeholder(license: &str) -> bool {
+    let key = alias_key(license);
+    PLACEHOLDERS.contains(&key.as_str()) || key.starts_with("see ")
+}
+
+/// Correct the case of SPDX identifiers and replace deprecated ones.
+/// Identifiers which are not on the SPDX list become `LicenseRef-`
+/// references.
+fn canonical_license(license: SimpleLicense) -> Result<SimpleLicense, Error> {
+    if is_placeholder(&license.id) {
+        return Err(invalid(&license.to_string(), "unknown license"));
+    }
+
+    let canonical = |ids: &[&str], id: &str| {
+        ids.iter()
+            .find(|known| known.eq_ignore_ascii_case(id))
+            .map(|known| known.to_string())
+    };
+
+    let mut license = license;
+    if let Some(id) = canonical(spdx::LICENSES, &license.id) {
+        license.id = id;
+    } else if let Some(id) = canonical(DEPRECATED, &license.id) {
+        license.id = match license.or_later {
+            true => format!("{id}-or-later"),
+            false => format!("{id}-only"),
+        };
+        license.or_later = false;
+    } else if let Some(id) = canonical(spdx::DEPRECATED_LICENSES, &license.id) {
+        license.id = id;
+    } else if !license.is_license_ref() {
+        // References can't be marked as `+`.
+        let id = match license.or_later {
+            true => format!("{}-or-later", license.id),
+            false => license.id.clone(),
+        };
+        license.id = license_ref(&id);
+        license.or_later = false;
+    }
+
+    if let Some(exception) = license.exception.take() {
+        match canonical(spdx::EXCEPTIONS, &exception) {
+            Some(exception) => license.exception = Some(exception),
+            // Exceptions can't be references, so the exception becomes part
+            // of the license reference.
+            None => {
+                let id = license.id.trim_start_matches("LicenseRef-");
+                let or_later = if license.or_later { "-or-later" } else { "" };
+                license.id = license_ref(&format!("{id}{or_later}-WITH-{exception}"));
+                license.or_later = false;
+            }
+        }
+    }
+
+    Ok(license)
+}
+
+/// Reference for a license not on the SPDX list, like
+/// `LicenseRef-Proprietary`.
+fn license_ref(license: &str) -> String {
+    let mut id = String::from("LicenseRef-");
+    for c in license.chars() {
+        match c {
+            c if c.is_ascii_alphanumeric() || c == '.' => id.push(c),
+            _ if id.ends_with('-') => (),
+            _ => id.push('-'),
+        }
+    }
+    id.trim_end_matches('-').to_owned()
+}
@@ -0,0 +1,341 @@
+//! Differences between the results of two jobs of a project.
+//!
+//! Comparing a job of a pull request to a job of the main branch shows which
+//! packages the pull request adds, removes or updates, and how that changes
+//! their risk. Pull request checks evaluate the difference instead of the
+//! whole job, so they only fail on risk the pull request introduces and not
+//! on risk which already existed.
+
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::policy::{is_ignored, package_scores, thresholds_by_type, ThresholdFailure, Verdict};
+use crate::types::common::Status;
+use crate::types::job::{Action, JobStatusResponse};
+use crate::types::package::{
+    Issue, PackageDescriptor, PackageStatusExtended, PackageType, RiskScores, RiskType,
+};
+use crate::types::package_name::PackageName;
+use crate::types::preferences::{IgnoredIssue, RiskThresholds};
+use crate::types::version::compare_versions;
+
+/// Changes between a base job and a head job.
+#[derive(PartialEq, PartialOrd, Clone, Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct JobDiff {
+    /// Packages which were added, removed or changed versions, ordered by
+    /// ecosystem and name.
+    pub packages: Vec<PackageDiff>,
+    pub base_pass: bool,
+    pub head_pass: bool,
+}
+
+impl JobDiff {
+    /// Compare the packages of two jobs.
+    ///
+    /// Packages are matched by ecosystem and canonical name, so `Django` and
+    /// `django` are the same PyPI package. Versions which are in both jobs
+    /// are unchanged, the remaining versions of a package are paired up from
+    /// lowest to highest as version changes. Packages whose version didn't
+    /// change are not listed, even if their issues changed in the meantime.
+    ///
+    /// Issues which are ignored, in the jobs or through `ignored_issues`,
+    /// are neither introduced nor resolved and don't count towards scores.
+    pub fn new(
+        base: &JobStatusResponse<PackageStatusExtended>,
+        head: &JobStatusResponse<PackageStatusExtended>,
+        ignored_issues: &[IgnoredIssue],
+    ) -> Self {
+        let mut base_packages = by_name(base);
+        let mut head_packages = by_name(head);
+
+        let mut keys: Vec<(PackageType, String)> = base_packages
+            .keys()
+            .chain(head_packages.keys())
+            .cloned()
+            .collect();
+        keys.sort();
+        keys.dedup();
+
+        let mut packages = Vec::new();
+        for key in keys {
+            let mut removed = base_packages.remove(&key).unwrap_or_default();
+            let mut added = head_packages.remove(&key).unwrap_or_default();
+            let compare = |a: &PackageStatusExtended, b: &PackageStatusExtended| {
+                compare_versions(&key.0, &a.basic_status.version, &b.basic_status.version)
+            };
+
+            // Versions in both jobs are unchanged.
+            removed.retain(|base| {
+                match added
+                    .iter()
+                    .position(|head| compare(base, head) == Ordering::Equal)
+                {
+                    Some(index) => {
+                        added.remove(index);
+                        false
+                    }
+                    None => true,
+                }
+            });
+            removed.sort_by(|a, b| compare(a, b));
+            added.sort_by(|a, b| compare(a, b));
+
+            let count = removed.len().max(added.len());
+            for index in 0..count {
+                packages.push(PackageDiff::new(
+                    removed.get(index).copied(),
+                    added.get(index).copied(),
+                    ignored_issues,
+                ));
+            }
+        }
+
+        Self {
+            packages,
+            base_pass: base.pass,
+            head_pass: head.pass,
+        }
+    }
+
+    /// Check if the job stopped or started passing its thresholds.
+    pub fn pass_flipped(&self) -> bool {
+        self.base_pass != self.head_pass
+    }
+
+    /// Evaluate the risk introduced by the head job against thresholds.
+    ///
+    /// Only added and changed packages can fail, in risk types where their
+    /// score is below the cutoff and either got worse than the package they
+    /// replace, or an introduced issue alone scores below the cutoff.
+    /// Packages which have not completed processing are reported as pending,
+    /// so the verdict doesn't pass until they were evaluated.
+    pub fn evaluate(&self, thresholds: &RiskThresholds) -> Verdict {
+        let mut failures = Vec::new();
+        let mut pending = Vec::new();
+        for package in &self.packages {
+            let head_version = match &package.head_version {
+                Some(version) => version,
+                None => continue,
+            };
+            let descriptor = PackageDescriptor {
+                name: package.name.clone(),
+                version: head_version.clone(),
+                package_type: package.package_type.clone(),
+            };
+            let head_scores = match &package.head_scores {
+                Some(scores) => scores,
+                None => {
+                    pending.push(descriptor);
+                    continue;
+                }
+            };
+
+            for (risk_type, threshold) in thresholds_by_type(thresholds) {
+                if !threshold.active {
+                    continue;
+                }
+
+                let head_score = head_scores.get(risk_type);
+                let base_score = package
+                    .base_scores
+                    .as_ref()
+                    .map_or(1., |scores| scores.get(risk_type));
+                let introduced = package.introduced.iter().any(|issue| {
+                    (risk_type == RiskType::TotalRisk || RiskType::from(issue.domain) == risk_type)
+                        && issue.severity.score() < threshold.threshold
+                });
+                if head_score < threshold.threshold && (head_score < base_score || introduced) {
+                    failures.push(ThresholdFailure {
+                        package: descriptor.clone(),
+                        risk_type,
+                        score: head_score,
+                        threshold: threshold.threshold,
+                        action: threshold.action,
+                    });
+                }
+            }
+        }
+
+        failures.sort_by(|a, b| {
+            a.package
+                .cmp(&b.package)
+                .then_with(|| a.risk_type.cmp(&b.risk_type))
+        });
+
+        let action = failures
+            .iter()
+            .map(|failure| failure.action)
+            .max()
+            .unwrap_or(Action::None);
+
+        pending.sort();
+
+        Verdict {
+            action,
+            failures,
+            pending,
+        }
+    }
+}
+
+/// How a package changed between two jobs.
+#[derive(
+    PartialEq, Eq, PartialOrd, Ord, Hash, Copy, Clone, Debug, Serialize, Deserialize, JsonSchema,
+)]
+#[serde(rename_all = "camelCase")]
+pub enum PackageChange {
+    Added,
+    Removed,
+    VersionChanged,
+}
+
+/// A package which was added, removed or changed versions.
+#[derive(PartialEq, PartialOrd, Clone, Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PackageDiff {
+    pub name: String,
+    pub package_type: PackageType,
+    pub change: PackageChange,
+    /// The version in the base job, `None` for added packages.
+    pub base_version: Option<String>,
+    /// The version in the head job, `None` for removed packages.
+    pub head_version: Option<String>,
+    /// Scores in the base job, `None` if the package is not in the job or
+    /// has not completed processing.
+    pub base_scores: Option<RiskScores>,
+    /// Scores in the head job, `None` if the package is not in the job or
+    /// has not completed processing.
+    pub head_scores: Option<RiskScores>,
+    /// Head scores minus base scores, where a package which is not in a job
+    /// scores `1.0`. `None` if either version has not completed processing.
+    pub score_deltas: Option<RiskScores>,
+    /// Issues of the head version which the base version doesn't have.
+    pub introduced: Vec<Issue>,
+    /// Issues of the base version which the head version doesn't have.
+    pub resolved: Vec<Issue>,
+}
+
+impl PackageDiff {
+    fn new(
+        base: Option<&PackageStatusExtended>,
+        head: Option<&PackageStatusExtended>,
+        ignored_issues: &[IgnoredIssue],
+    ) -> Self {
+        let package = head.or(base).expect("package in base or head job");
+        let change = match (base, head) {
+            (None, _) => PackageChange::Added,
+            (_, None) => PackageChange::Removed,
+            _ => PackageChange::VersionChanged,
+        };
+
+        let scores = |package: Option<&PackageStatusExtended>| {
+            package
+                .filter(|package| package.basic_status.status == Status::Complete)
+                .map(|package| package_scores(package, ignored_issues))
+        };
+        let base_scores = scores(base);
+        let head_scores = scores(head);
+
+        // Missing packages have no risk, incomplete packages have no scores.
+        let perfect = RiskScores::from_issues(None);
+        let delta_base = if base.is_some() {
+            base_scores
+        } else {
+            Some(perfect)
+        };
+        let delta_head = if head.is_some() {
+            head_scores
+        } else {
+            Some(perfect)
+        };
+        let score_deltas = delta_base
+            .zip(delta_head)
+            .map(|(base, head)| deltas(&base, &head));
+
+        let base_issues = issues(base, ignored_issues);
+        let head_issues = issues(head, ignored_issues);
+        let introduced = head_issues
+            .iter()
+            .filter(|issue| !base_issues.iter().any(|base| same_issue(base, issue)))
+            .map(|issue| (*issue).clone())
+            .collect();
+        let resolved = base_issues
+            .iter()
+            .filter(|issue| !head_issues.iter().any(|head| same_issue(head, issue)))
+            .map(|issue| (*issue).clone())
+            .collect();
+
+        Self {
+            name: package.basic_status.name.clone(),
+            package_type: package.package_type.clone(),
+            change,
+            base_version: base.map(|package| package.basic_status.version.clone()),
+            head_version: head.map(|package| package.basic_status.version.clone()),
+            base_scores,
+            head_scores,
+            score_deltas,
+            introduced,
+            resolved,
+        }
+    }
+}
+
+/// Packages of a job by ecosystem and canonical name.
+///
+/// Names which are not valid in their ecosystem are used as they are.
+fn by_name(
+    job: &JobStatusResponse<PackageStatusExtended>,
+) -> HashMap<(PackageType, String), Vec<&PackageStatusExtended>> {
+    let mut packages: HashMap<_, Vec<_>> = HashMap::new();
+    for package in &job.packages {
+        let name = &package.basic_status.name;
+        let name = match PackageName::new(package.package_type.clone(), name.as_str()) {
+            Ok(name) => name.canonical().to_owned(),
+            Err(_) => name.clone(),
+        };
+        let key = (package.package_type.clone(), name);
+        packages.entry(key).or_default().push(package);
+    }
+    packages
+}
+
+/// Issues of a package which are not ignored.
+fn issues<'a>(
+    package: Option<&'a PackageStatusExtended>,
+    ignored_issues: &[IgnoredIssue],
+) -> Vec<&'a Issue> {
+    package
+        .into_iter()
+        .flat_map(|package| &package.issues)
+        .filter(|issue| !is_ignored(issue, ignored_issues))
+        .map(|issue| &issue.issue)
+        .collect()
+}
+
+/// Issues are the same if they have the same id or the same tag, since ids
+/// can differ between versions of a package. Issues with neither are the
+/// same if they have the same title and domain.
+fn same_issue(a: &Issue, b: &Issue) -> bool {
+    let untagged = |issue: &Issue| issue.id.is_none() && issue.tag.is_none();
+    if untagged(a) && untagged(b) {
+        return a.title == b.title && a.domain == b.domain;
+    }
+
+    let same = |a: &Option<String>, b: &Option<String>| a.is_some() && a == b;
+    same(&a.id, &b.id) || same(&a.tag, &b.tag)
+}
+
+fn deltas(base: &RiskScores, head: &RiskScores) -> RiskScores {
+    RiskScores {
+        total: head.total - base.total,
+        vulnerability: head.vulnerability - base.vulnerability,
+        malicious: head.malicious - base.malicious,
+        author: head.author - base.author,
+        engineering: head.engineering - base.engineering,
+        license: head.license - base.license,
+    }
+}
@@ -0,0 +1,338 @@
+//! Suppression of issues with scopes and expiry.
+//!
+//! A [`Suppression`] ignores issues by id or tag, optionally only for some
+//! packages, and optionally only until a date. Suppressions are applied to a
+//! package's issues with a [`SuppressionApplicator`], which also keeps track
+//! of suppressions that are stale and should be cleaned up.
+
+use std::cmp::Ordering;
+use std::convert::TryFrom;
+
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::error::Error;
+use crate::policy::rules::glob_matches;
+use crate::types::package::{IgnoredReason, IssueStatus, IssuesListItem, PackageDescriptor};
+use crate::types::package_url::name_and_version;
+use crate::types::preferences::IgnoredIssue;
+use crate::types::version::{compare_versions, EcosystemVersion, VersionRange};
+
+/// A rule to ignore issues.
+///
+/// A suppression needs an `id` or a `tag`, if it has both an issue must match
+/// both. Without any scope it applies to all packages.
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+#[serde(try_from = "SuppressionFields")]
+pub struct Suppression {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tag: Option<String>,
+    pub reason: IgnoredReason,
+    /// Package name pattern, like `@types/*`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub package: Option<String>,
+    /// Version range in the syntax of the package's ecosystem.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub versions: Option<String>,
+    /// Package URL pattern, like `pkg:npm/%40types/*`.
+    ///
+    /// A package URL without `*` names a single package, in any spelling of
+    /// its name, and a version if it has one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub purl: Option<String>,
+    /// Time after which the suppression no longer applies.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<DateTime<Utc>>,
+    /// Why the issue is safe to ignore.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub justification: Option<String>,
+    /// Who approved ignoring the issue.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub approved_by: Option<String>,
+}
+
+/// The fields of a [`Suppression`] before it is validated.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct SuppressionFields {
+    id: Option<String>,
+    tag: Option<String>,
+    reason: IgnoredReason,
+    package: Option<String>,
+    versions: Option<String>,
+    purl: Option<String>,
+    expires_at: Option<DateTime<Utc>>,
+    justification: Option<String>,
+    approved_by: Option<String>,
+}
+
+impl TryFrom<SuppressionFields> for Suppression {
+    type Error = Error;
+
+    fn try_from(fields: SuppressionFields) -> Result<Self, Self::Error> {
+        let suppression = Self {
+            id: fields.id,
+            tag: fields.tag,
+            reason: fields.reason,
+            package: fields.package,
+            versions: fields.versions,
+            purl: fields.purl,
+            expires_at: fields.expires_at,
+            justification: fields.justification,
+            approved_by: fields.approved_by,
+        };
+        suppression.validate()?;
+        Ok(suppression)
+    }
+}
+
+impl Suppression {
+    /// Check that the suppression names the issues it applies to.
+    pub fn validate(&self) -> Result<(), Error> {
+        match (&self.id, &self.tag) {
+            (None, None) => Err(Error::InvalidSuppression("needs an `id` or a `tag`".into())),
+            _ => Ok(()),
+        }
+    }
+
+    /// Check if the suppression no longer applies at `now`.
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at.is_some_and(|expires_at| expires_at <= now)
+    }
+
+    /// Check if an issue with the given id and tag is suppressed.
+    fn matches_issue(&self, id: Option<&str>, tag: Option<&str>) -> bool {
+        (self.id.is_some() || self.tag.is_some())
+            && self
+                .id
+                .as_deref()
+                .is_none_or(|expected| id == Some(expected))
+            && self
+                .tag
+                .as_deref()
+                .is_none_or(|expected| tag == Some(expected))
+    }
+
+    /// Check if a package is within the scope of the suppression.
+    fn matches_package(&self, package: &PackageDescriptor) -> Result<bool, Error> {
+        if let Some(pattern) = &self.package {
+            if !glob_matches(pattern, &package.name, false) {
+                return Ok(false);
+            }
+        }
+
+        if let Some(pattern) = &self.purl {
+            let matches = match pattern.contains('*') {
+                true => package
+                    .to_purl()
+                    .is_ok_and(|purl| glob_matches(pattern, &purl, false)),
+                false => {
+                    let (name, version) = name_and_version(pattern)?;
+                    package.package_name().is_ok_and(|actual| actual == name)
+                        && version.is_none_or(|version| {
+                            compare_versions(&package.package_type, &version, &package.version)
+                                == Ordering::Equal
+                        })
+                }
+            };
+            if !matches {
+                return Ok(false);
+            }
+        }
+
+        match &self.versions {
+            Some(range) => {
+                let range = VersionRange::parse(&package.package_type, range)?;
+                Ok(
+                    EcosystemVersion::parse(&package.package_type, &package.version)
+                        .is_ok_and(|version| range.matches(&version)),
+                )
+            }
+            None => Ok(true),
+        }
+    }
+}
+
+impl From<IgnoredIssue> for Suppression {
+    fn from(issue: IgnoredIssue) -> Self {
+        Self {
+            id: Some(issue.id),
+            tag: Some(issue.tag),
+            reason: issue.reason,
+            package: None,
+            versions: None,
+            purl: None,
+            expires_at: None,
+            justification: None,
+            approved_by: None,
+        }
+    }
+}
+
+/// An issue which can be suppressed.
+pub trait SuppressibleIssue {
+    fn issue_id(&self) -> Option<&str>;
+    fn issue_tag(&self) -> Option<&str>;
+    fn ignored(&self) -> IgnoredReason;
+    fn set_ignored(&mut self, reason: IgnoredReason);
+}
+
+impl SuppressibleIssue for IssueStatus {
+    fn issue_id(&self) -> Option<&str> {
+        self.issue.id.as_deref()
+    }
+
+    fn issue_tag(&self) -> Option<&str> {
+        self.issue.tag.as_deref()
+    }
+
+    fn ignored(&self) -> IgnoredReason {
+        self.ignored
+    }
+
+    fn set_ignored(&mut self, reason: IgnoredReason) {
+        self.ignored = reason;
+    }
+}
+
+impl SuppressibleIssue for IssuesListItem {
+    fn issue_id(&self) -> Option<&str> {
+        self.id.as_deref()
+    }
+
+    fn issue_tag(&self) -> Option<&str> {
+        self.tag.as_deref()
+    }
+
+    fn ignored(&self) -> IgnoredReason {
+        self.ignored
+    }
+
+    fn set_ignored(&mut self, reason: IgnoredReason) {
+        self.ignored = reason;
+    }
+}
+
+/// Applies suppressions to the issues of packages.
+///
+/// The applicator remembers which suppressions were used, so that after all
+/// packages of a project have been processed, [`Self::report`] lists the
+/// suppressions which are stale.
+#[derive(Clone, Debug)]
+pub struct SuppressionApplicator {
+    suppressions: Vec<Suppression>,
+    now: DateTime<Utc>,
+    used: Vec<bool>,
+    invalid: Vec<Option<String>>,
+}
+
+impl SuppressionApplicator {
+    /// Create an applicator, with `now` as the reference time for expiry.
+    ///
+    /// Suppressions without an `id` or `tag` never apply and are reported
+    /// as invalid.
+    pub fn new(suppressions: Vec<Suppression>, now: DateTime<Utc>) -> Self {
+        let count = suppressions.len();
+        let invalid = suppressions
+            .iter()
+            .map(|suppression| suppression.validate().err().map(|err| err.to_string()))
+            .collect();
+        Self {
+            suppressions,
+            now,
+            used: vec![false; count],
+            invalid,
+        }
+    }
+
+    /// Mark the issues of a package which are suppressed as ignored.
+    ///
+    /// Issues which are already ignored keep their reason. Otherwise the
+    /// reason of the first matching suppression is used. Expired suppressions
+    /// don't apply. Returns the number of newly ignored issues.
+    pub fn apply<I: SuppressibleIssue>(
+        &mut self,
+        package: &PackageDescriptor,
+        issues: &mut [I],
+    ) -> usize {
+        let mut in_scope = Vec::new();
+        for (index, suppression) in self.suppressions.iter().enumerate() {
+            if suppression.is_expired(self.now) {
+                continue;
+            }
+            match suppression.matches_package(package) {
+                Ok(true) => in_scope.push(index),
+                Ok(false) => (),
+                Err(err) => {
+                    self.invalid[index].get_or_insert(err.to_string());
+                }
+            }
+        }
+
+        let mut count = 0;
+        for issue in issues.iter_mut() {
+            let id = issue.issue_id();
+            let tag = issue.issue_tag();
+            let matching = in_scope
+                .iter()
+                .copied()
+                .find(|index| self.suppressions[*index].matches_issue(id, tag));
+
+            if let Some(index) = matching {
+                self.used[index] = true;
+                if issue.ignored() == IgnoredReason::False {
+                    issue.set_ignored(self.suppressions[index].reason);
+                    count += 1;
+                }
+            }
+        }
+
+        count
+    }
+
+    /// Suppressions which should be cleaned up.
+    pub fn report(&self) -> SuppressionReport {
+        let mut report = SuppressionReport::default();
+        for (index, suppression) in self.suppressions.iter().enumerate() {
+            if suppression.is_expired(self.now) {
+                report.expired.push(suppression.clone());
+            } else if let Some(err) = &self.invalid[index] {
+                report.invalid.push(InvalidSuppression {
+                    suppression: suppression.clone(),
+                    reason: err.clone(),
+                });
+            } else if !self.used[index] {
+                report.unused.push(suppression.clone());
+            }
+        }
+        report
+    }
+}
+
+/// Stale suppressions, in the order they were declared.
+#[derive(PartialEq, Clone, Debug, Default, Serialize, Deserialize, JsonSchema)]
+pub struct SuppressionReport {
+    /// Suppressions past their expiry date.
+    pub expired: Vec<Suppression>,
+    /// Suppressions which didn't match any issue.
+    pub unused: Vec<Suppression>,
+    /// Suppressions without an `id` or `tag`, or with a version range that
+    /// can't be parsed.
+    pub invalid: Vec<InvalidSuppression>,
+}
+
+impl SuppressionReport {
+    pub fn is_empty(&self) -> bool {
+        self.expired.is_empty() && self.unused.is_empty() && self.invalid.is_empty()
+    }
+}
+
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize, JsonSchema)]
+pub struct InvalidSuppression {
+    pub suppression: Suppression,
+    pub reason: String,
+}
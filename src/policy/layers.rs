@@ -0,0 +1,306 @@
+//! Resolution of preferences from multiple layers.
+//!
+//! Preferences can be set by the user, the project's group, the project and
+//! local overrides like command line flags. Later layers take precedence:
+//! each setting comes from the last layer which sets it, and the resolved
+//! value remembers that layer.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::types::package::RiskType;
+use crate::types::preferences::{CorePreferences, IgnoredIssue, RiskThresholds};
+use crate::types::user_settings::{Setting, Threshold, UserSettings};
+
+/// Origin of a setting, from lowest to highest precedence.
+#[derive(
+    PartialEq, Eq, PartialOrd, Ord, Hash, Copy, Clone, Debug, Serialize, Deserialize, JsonSchema,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum Layer {
+    /// Built-in defaults, used if no layer sets a value.
+    Default,
+    User,
+    Group,
+    Project,
+    /// Local overrides, like command line flags.
+    Override,
+}
+
+impl fmt::Display for Layer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let layer = format!("{self:?}");
+        write!(f, "{}", layer.to_lowercase())
+    }
+}
+
+/// A setting with the layer which set it.
+#[derive(PartialEq, PartialOrd, Clone, Debug, Serialize, Deserialize, JsonSchema)]
+pub struct Resolved<T> {
+    pub value: T,
+    pub layer: Layer,
+}
+
+/// Thresholds which may leave some risk domains unset.
+#[derive(PartialEq, Clone, Debug, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct PartialThresholds {
+    pub total: Option<Threshold>,
+    pub author: Option<Threshold>,
+    pub engineering: Option<Threshold>,
+    pub license: Option<Threshold>,
+    #[serde(alias = "malicious")]
+    #[serde(rename = "maliciousCode")]
+    pub malicious: Option<Threshold>,
+    pub vulnerability: Option<Threshold>,
+}
+
+/// A full set of thresholds sets every domain.
+impl From<&RiskThresholds> for PartialThresholds {
+    fn from(thresholds: &RiskThresholds) -> Self {
+        Self {
+            total: Some(thresholds.total.clone()),
+            author: Some(thresholds.author.clone()),
+            engineering: Some(thresholds.engineering.clone()),
+            license: Some(thresholds.license.clone()),
+            malicious: Some(thresholds.malicious.clone()),
+            vulnerability: Some(thresholds.vulnerability.clone()),
+        }
+    }
+}
+
+/// Preferences of a single layer, where every setting is optional.
+///
+/// Stored group and project preferences deserialize into this type, so a
+/// threshold domain they don't contain stays unset, while one they contain
+/// is set even if it holds the built-in [`Threshold::default`].
+#[derive(PartialEq, Clone, Debug, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+#[serde(default)]
+pub struct PreferenceOverrides {
+    pub default_label: Option<String>,
+    pub thresholds: PartialThresholds,
+    /// Ignored issues are combined across all layers.
+    #[serde(deserialize_with = "null_as_empty")]
+    pub ignored_issues: Vec<IgnoredIssue>,
+}
+
+/// Stored preferences write `null` if no issues are ignored.
+fn null_as_empty<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Vec<IgnoredIssue>, D::Error> {
+    Ok(Option::deserialize(deserializer)?.unwrap_or_default())
+}
+
+impl From<&CorePreferences> for PreferenceOverrides {
+    fn from(preferences: &CorePreferences) -> Self {
+        Self {
+            default_label: preferences.default_label.clone(),
+            thresholds: PartialThresholds::from(&preferences.thresholds),
+            ignored_issues: preferences.ignored_issues.clone().unwrap_or_default(),
+        }
+    }
+}
+
+/// Combines the preference layers of a project.
+#[derive(Clone, Debug, Default)]
+pub struct PreferenceResolver {
+    layers: Vec<(Layer, PreferenceOverrides)>,
+}
+
+impl PreferenceResolver {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add the user's settings for a project.
+    ///
+    /// Thresholds are read by their risk domain name, and the default label
+    /// from a `default_label` entry.
+    pub fn user_settings(self, settings: &UserSettings, project_id: &str) -> Self {
+        let mut overrides = PreferenceOverrides::default();
+        match settings.projects.get(project_id) {
+            Some(Setting::Project(project)) => {
+                overrides.thresholds = user_thresholds(&project.thresholds)
+            }
+            Some(Setting::DefaultLabel(labels)) => {
+                overrides.default_label = labels.get("default_label").cloned()
+            }
+            None => (),
+        }
+        self.layer(Layer::User, overrides)
+    }
+
+    /// Add the preferences of the project's group.
+    ///
+    /// [`CorePreferences`] always carry all six thresholds, so converting
+    /// them sets every domain. Deserialize the stored preferences into
+    /// [`PreferenceOverrides`] to leave domains the group didn't set to lower
+    /// layers.
+    pub fn group(self, preferences: PreferenceOverrides) -> Self {
+        self.layer(Layer::Group, preferences)
+    }
+
+    /// Add the preferences of the project, like [`Self::group`].
+    pub fn project(self, preferences: PreferenceOverrides) -> Self {
+        self.layer(Layer::Project, preferences)
+    }
+
+    pub fn overrides(self, overrides: PreferenceOverrides) -> Self {
+        self.layer(Layer::Override, overrides)
+    }
+
+    /// Add preferences for a layer.
+    ///
+    /// Layers may be added in any order, precedence only depends on the
+    /// [`Layer`]. Of multiple preferences for the same layer, the last one
+    /// takes precedence.
+    pub fn layer(mut self, layer: Layer, overrides: PreferenceOverrides) -> Self {
+        self.layers.push((layer, overrides));
+        self
+    }
+
+    /// Combine all layers into the effective preferences.
+    pub fn resolve(&self) -> EffectivePreferences {
+        let mut layers: Vec<&(Layer, PreferenceOverrides)> = self.layers.iter().collect();
+        layers.sort_by_key(|(layer, _)| *layer);
+
+        let default = |value: Threshold| Resolved {
+            value,
+            layer: Layer::Default,
+        };
+        let mut effective = EffectivePreferences {
+            default_label: None,
+            thresholds: ResolvedThresholds {
+                total: default(Threshold::default()),
+                author: default(Threshold::default()),
+                engineering: default(Threshold::default()),
+                license: default(Threshold::default()),
+                malicious: default(Threshold::default()),
+                vulnerability: default(Threshold::default()),
+            },
+            ignored_issues: Vec::new(),
+        };
+
+        for (layer, overrides) in layers {
+            let resolve = |value: &Option<Threshold>, resolved: &mut Resolved<Threshold>| {
+                if let Some(value) = value {
+                    *resolved = Resolved {
+                        value: value.clone(),
+                        layer: *layer,
+                    };
+                }
+            };
+
+            if let Some(label) = &overrides.default_label {
+                effective.default_label = Some(Resolved {
+                    value: label.clone(),
+                    layer: *layer,
+                });
+            }
+
+            let thresholds = &overrides.thresholds;
+            let resolved = &mut effective.thresholds;
+            resolve(&thresholds.total, &mut resolved.total);
+            resolve(&thresholds.author, &mut resolved.author);
+            resolve(&thresholds.engineering, &mut resolved.engineering);
+            resolve(&thresholds.license, &mut resolved.license);
+            resolve(&thresholds.malicious, &mut resolved.malicious);
+            resolve(&thresholds.vulnerability, &mut resolved.vulnerability);
+
+            // An issue ignored again by a later layer takes its reason.
+            for issue in &overrides.ignored_issues {
+                effective.ignored_issues.retain(|ignored| {
+                    ignored.value.id != issue.id || ignored.value.tag != issue.tag
+                });
+                effective.ignored_issues.push(Resolved {
+                    value: issue.clone(),
+                    layer: *layer,
+                });
+            }
+        }
+
+        effective
+    }
+}
+
+/// Thresholds with the layer each one was set by.
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize, JsonSchema)]
+pub struct ResolvedThresholds {
+    pub total: Resolved<Threshold>,
+    pub author: Resolved<Threshold>,
+    pub engineering: Resolved<Threshold>,
+    pub license: Resolved<Threshold>,
+    #[serde(rename = "maliciousCode")]
+    pub malicious: Resolved<Threshold>,
+    pub vulnerability: Resolved<Threshold>,
+}
+
+impl ResolvedThresholds {
+    /// The threshold for a risk domain.
+    pub fn get(&self, risk_type: RiskType) -> &Resolved<Threshold> {
+        match risk_type {
+            RiskType::TotalRisk => &self.total,
+            RiskType::AuthorsRisk => &self.author,
+            RiskType::EngineeringRisk => &self.engineering,
+            RiskType::LicenseRisk => &self.license,
+            RiskType::MaliciousRisk => &self.malicious,
+            RiskType::Vulnerabilities => &self.vulnerability,
+        }
+    }
+}
+
+/// The preferences in effect for a project, with their provenance.
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct EffectivePreferences {
+    pub default_label: Option<Resolved<String>>,
+    pub thresholds: ResolvedThresholds,
+    pub ignored_issues: Vec<Resolved<IgnoredIssue>>,
+}
+
+impl EffectivePreferences {
+    /// The thresholds without provenance.
+    pub fn risk_thresholds(&self) -> RiskThresholds {
+        let thresholds = &self.thresholds;
+        RiskThresholds {
+            total: thresholds.total.value.clone(),
+            author: thresholds.author.value.clone(),
+            engineering: thresholds.engineering.value.clone(),
+            license: thresholds.license.value.clone(),
+            malicious: thresholds.malicious.value.clone(),
+            vulnerability: thresholds.vulnerability.value.clone(),
+        }
+    }
+
+    /// The preferences without provenance.
+    pub fn core_preferences(&self) -> CorePreferences {
+        CorePreferences {
+            default_label: self.default_label.as_ref().map(|label| label.value.clone()),
+            thresholds: self.risk_thresholds(),
+            ignored_issues: Some(
+                self.ignored_issues
+                    .iter()
+                    .map(|issue| issue.value.clone())
+                    .collect(),
+            ),
+        }
+    }
+}
+
+/// Read thresholds keyed by risk domain name, as stored in user settings.
+fn user_thresholds(thresholds: &HashMap<String, Threshold>) -> PartialThresholds {
+    let get = |names: &[&str]| names.iter().find_map(|name| thresholds.get(*name)).cloned();
+
+    PartialThresholds {
+        total: get(&["total"]),
+        author: get(&["author"]),
+        engineering: get(&["engineering"]),
+        license: get(&["license"]),
+        malicious: get(&["maliciousCode", "malicious_code", "malicious"]),
+        vulnerability: get(&["vulnerability", "vulnerabilities"]),
+    }
+}
@@ -0,0 +1,130 @@
+//! Allow and deny lists of licenses.
+//!
+//! A project's license policy is checked against the license of each
+//! package, producing issues in the license risk domain:
+//!
+//! ```toml
+//! allow = ["MIT", "Apache-2.0", "BSD-*"]
+//! deny = ["AGPL-*", "GPL-*"]
+//! ```
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::license::{LicenseExpression, SimpleLicense};
+use crate::policy::rules::glob_matches;
+use crate::types::package::{Issue, RiskDomain, RiskLevel};
+
+/// Tag of issues for packages whose license is denied.
+pub const DENIED_TAG: &str = "LIC-DENIED";
+/// Tag of issues for packages whose license is not allowed.
+pub const UNLISTED_TAG: &str = "LIC-UNLISTED";
+/// Tag of issues for packages without a recognizable license.
+pub const UNKNOWN_TAG: &str = "LIC-UNKNOWN";
+
+/// Licenses a project accepts.
+///
+/// Patterns like `GPL-*` match license identifiers ignoring case. A license
+/// with an exception, like `GPL-2.0-only WITH Classpath-exception-2.0`, also
+/// matches patterns for the full license including the exception.
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+#[serde(default)]
+pub struct LicensePolicy {
+    /// Licenses which are allowed. If empty, every license which is not
+    /// denied is allowed.
+    pub allow: Vec<String>,
+    /// Licenses which are never allowed, even if they match `allow`.
+    pub deny: Vec<String>,
+    /// Severity of issues for denied licenses.
+    pub deny_severity: RiskLevel,
+    /// Severity of issues for licenses which are not allowed.
+    pub unlisted_severity: RiskLevel,
+    /// Severity of issues for missing or unparsable licenses, `None` to
+    /// accept them.
+    pub unknown_severity: Option<RiskLevel>,
+}
+
+impl Default for LicensePolicy {
+    fn default() -> Self {
+        Self {
+            allow: Vec::new(),
+            deny: Vec::new(),
+            deny_severity: RiskLevel::High,
+            unlisted_severity: RiskLevel::Medium,
+            unknown_severity: Some(RiskLevel::Low),
+        }
+    }
+}
+
+impl LicensePolicy {
+    /// Check a package's license against the policy.
+    ///
+    /// Licenses are normalized first, so names like `Apache 2` are
+    /// recognized. For expressions like `MIT OR GPL-3.0-only` the package
+    /// complies if any choice of licenses complies.
+    pub fn evaluate(&self, license: Option<&str>) -> Option<Issue> {
+        let expression = match license.map(LicenseExpression::normalize) {
+            Some(Ok(expression)) => expression,
+            _ => {
+                let severity = self.unknown_severity?;
+                let description = match license {
+                    Some(license) => format!("The license {license:?} is not recognizable."),
+                    None => "The package has no license.".into(),
+                };
+                return Some(issue(UNKNOWN_TAG, "Unknown license", description, severity));
+            }
+        };
+
+        if expression.is_satisfied_by(|license| self.allows(license)) {
+            return None;
+        }
+
+        // Denied if every choice includes a denied license.
+        let denied = !expression.is_satisfied_by(|license| !self.denies(license));
+        Some(match denied {
+            true => issue(
+                DENIED_TAG,
+                "Denied license",
+                format!("The license {expression} is denied by the project's license policy."),
+                self.deny_severity,
+            ),
+            false => issue(
+                UNLISTED_TAG,
+                "License not allowed",
+                format!("The license {expression} is not allowed by the project's license policy."),
+                self.unlisted_severity,
+            ),
+        })
+    }
+
+    fn allows(&self, license: &SimpleLicense) -> bool {
+        !self.denies(license)
+            && (self.allow.is_empty()
+                || self
+                    .allow
+                    .iter()
+                    .any(|pattern| license_matches(pattern, license)))
+    }
+
+    fn denies(&self, license: &SimpleLicense) -> bool {
+        self.deny
+            .iter()
+            .any(|pattern| license_matches(pattern, license))
+    }
+}
+
+fn license_matches(pattern: &str, license: &SimpleLicense) -> bool {
+    glob_matches(pattern, &license.id, true) || glob_matches(pattern, &license.to_string(), true)
+}
+
+fn issue(tag: &str, title: &str, description: String, severity: RiskLevel) -> Issue {
+    Issue {
+        tag: Some(tag.into()),
+        id: None,
+        title: title.into(),
+        description,
+        severity,
+        domain: RiskDomain::LicenseRisk,
+    }
+}
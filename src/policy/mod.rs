@@ -0,0 +1,194 @@
+//! Evaluation of job results against a project's risk thresholds.
+//!
+//! Every active threshold is checked against the score of each package in
+//! its domain. Packages scoring below a cutoff fail that threshold, and the
+//! strictest action of all failures decides how the job is handled.
+
+pub mod diff;
+pub mod ignore;
+pub mod layers;
+pub mod license;
+pub mod rules;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::types::common::Status;
+use crate::types::job::Action;
+use crate::types::package::{
+    IgnoredReason, IssueStatus, PackageDescriptor, PackageStatusExtended, RiskScores, RiskType,
+};
+use crate::types::preferences::{IgnoredIssue, RiskThresholds};
+use crate::types::user_settings::Threshold;
+
+/// Result of evaluating a job against its thresholds.
+#[derive(PartialEq, PartialOrd, Clone, Debug, Serialize, Deserialize, JsonSchema)]
+pub struct Verdict {
+    /// The strictest action of all failures, [`Action::None`] if nothing
+    /// failed.
+    pub action: Action,
+    /// Failed thresholds, ordered by package and risk domain.
+    pub failures: Vec<ThresholdFailure>,
+    /// Packages which have not completed processing and could not be
+    /// evaluated yet, ordered by package.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pending: Vec<PackageDescriptor>,
+}
+
+impl Verdict {
+    /// Check if the job passed all thresholds which demand an action.
+    ///
+    /// A verdict with pending packages is not final and doesn't pass.
+    pub fn is_pass(&self) -> bool {
+        self.action == Action::None && self.pending.is_empty()
+    }
+}
+
+/// A package scoring below a threshold's cutoff.
+#[derive(PartialEq, PartialOrd, Clone, Debug, Serialize, Deserialize, JsonSchema)]
+pub struct ThresholdFailure {
+    pub package: PackageDescriptor,
+    pub risk_type: RiskType,
+    pub score: f32,
+    pub threshold: f32,
+    pub action: Action,
+}
+
+/// Evaluate the packages of a job against risk thresholds.
+///
+/// Issues which are ignored, either on the issue itself or through
+/// `ignored_issues`, don't count towards a package's score: the score of
+/// their domain is recomputed from the remaining issues. Packages which have
+/// not completed processing are reported as pending.
+pub fn evaluate(
+    packages: &[PackageStatusExtended],
+    thresholds: &RiskThresholds,
+    ignored_issues: &[IgnoredIssue],
+) -> Verdict {
+    let mut failures = Vec::new();
+    let mut pending = Vec::new();
+    for package in packages {
+        if package.basic_status.status != Status::Complete {
+            pending.push(PackageDescriptor {
+                name: package.basic_status.name.clone(),
+                version: package.basic_status.version.clone(),
+                package_type: package.package_type.clone(),
+            });
+            continue;
+        }
+
+        let scores = package_scores(package, ignored_issues);
+        for (risk_type, threshold) in thresholds_by_type(thresholds) {
+            if !threshold.active {
+                continue;
+            }
+
+            let score = scores.get(risk_type);
+            if score < threshold.threshold {
+                failures.push(ThresholdFailure {
+                    package: PackageDescriptor {
+                        name: package.basic_status.name.clone(),
+                        version: package.basic_status.version.clone(),
+                        package_type: package.package_type.clone(),
+                    },
+                    risk_type,
+                    score,
+                    threshold: threshold.threshold,
+                    action: threshold.action,
+                });
+            }
+        }
+    }
+
+    failures.sort_by(|a, b| {
+        a.package
+            .cmp(&b.package)
+            .then_with(|| a.risk_type.cmp(&b.risk_type))
+    });
+
+    let action = failures
+        .iter()
+        .map(|failure| failure.action)
+        .max()
+        .unwrap_or(Action::None);
+
+    pending.sort();
+
+    Verdict {
+        action,
+        failures,
+        pending,
+    }
+}
+
+fn thresholds_by_type(thresholds: &RiskThresholds) -> [(RiskType, &Threshold); 6] {
+    [
+        (RiskType::TotalRisk, &thresholds.total),
+        (RiskType::Vulnerabilities, &thresholds.vulnerability),
+        (RiskType::MaliciousRisk, &thresholds.malicious),
+        (RiskType::AuthorsRisk, &thresholds.author),
+        (RiskType::EngineeringRisk, &thresholds.engineering),
+        (RiskType::LicenseRisk, &thresholds.license),
+    ]
+}
+
+fn is_ignored(issue: &IssueStatus, ignored_issues: &[IgnoredIssue]) -> bool {
+    issue.ignored != IgnoredReason::False
+        || ignored_issues.iter().any(|ignored| {
+            issue.issue.id.as_deref() == Some(ignored.id.as_str())
+                && issue.issue.tag.as_deref() == Some(ignored.tag.as_str())
+        })
+}
+
+/// Scores of a package, with ignored issues removed.
+///
+/// Domains without ignored issues keep the scores reported by the API.
+/// Domains with ignored issues are scored from the remaining issues with
+/// [`RiskScores::from_issues`], and the total becomes the lowest domain
+/// score.
+pub(crate) fn package_scores(
+    package: &PackageStatusExtended,
+    ignored_issues: &[IgnoredIssue],
+) -> RiskScores {
+    let (ignored, remaining): (Vec<&IssueStatus>, Vec<&IssueStatus>) = package
+        .issues
+        .iter()
+        .partition(|issue| is_ignored(issue, ignored_issues));
+    let mut scores = RiskScores::from_issues(
+        remaining
+            .iter()
+            .map(|issue| (issue.issue.domain.into(), issue.issue.severity)),
+    );
+
+    let domains = [
+        (RiskType::Vulnerabilities, &["vulnerability"][..]),
+        (
+            RiskType::MaliciousRisk,
+            &["malicious_code", "malicious"][..],
+        ),
+        (RiskType::AuthorsRisk, &["author"][..]),
+        (RiskType::EngineeringRisk, &["engineering"][..]),
+        (RiskType::LicenseRisk, &["license"][..]),
+    ];
+    for (risk_type, keys) in domains {
+        let any_ignored = ignored
+            .iter()
+            .any(|issue| RiskType::from(issue.issue.domain) == risk_type);
+        let reported = keys.iter().find_map(|key| package.risk_vectors.get(*key));
+        if let (false, Some(score)) = (any_ignored, reported) {
+            *scores.get_mut(risk_type) = *score as f32;
+        }
+    }
+
+    let reported_total = package
+        .basic_status
+        .package_score
+        .or_else(|| package.risk_vectors.get("total").copied())
+        .map(|score| score as f32);
+    scores.total = match reported_total {
+        Some(total) if ignored.is_empty() => total,
+        _ => scores.lowest_domain_score(1.),
+    };
+
+    scores
+}
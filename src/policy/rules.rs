@@ -0,0 +1,335 @@
+//! Declarative rules for conditions thresholds can't express.
+//!
+//! Rules are read from JSON or TOML, for example:
+//!
+//! ```toml
+//! [[rules]]
+//! name = "No critical malicious code"
+//! action = "break"
+//! when = { issue = { domain = "malicious_code", minSeverity = "critical" } }
+//!
+//! [[rules]]
+//! name = "Copyleft license"
+//! action = "warn"
+//! when = { license = "GPL-*" }
+//!
+//! [[rules]]
+//! name = "Abandoned direct dependency"
+//! action = "break"
+//! when = { all = ["abandoned", "direct"] }
+//! ```
+//!
+//! The JSON Schema of [`RuleSet`] can be used to validate rule files in
+//! editors.
+
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::policy::{is_ignored, package_scores};
+use crate::types::job::Action;
+use crate::types::package::{
+    IgnoredReason, Package, PackageDescriptor, PackageStatusExtended, PackageType, RiskDomain,
+    RiskLevel, RiskType,
+};
+
+/// A list of rules, as stored in a policy file.
+#[derive(PartialEq, Clone, Debug, Default, Serialize, Deserialize, JsonSchema)]
+pub struct RuleSet {
+    #[serde(default)]
+    pub rules: Vec<Rule>,
+}
+
+impl RuleSet {
+    /// All rules matching the package, in the order they are declared.
+    ///
+    /// `now` is the reference time for age based conditions.
+    pub fn evaluate(&self, subject: &RuleSubject, now: DateTime<Utc>) -> Vec<RuleMatch> {
+        self.rules
+            .iter()
+            .filter(|rule| rule.when.matches(subject, now))
+            .map(|rule| RuleMatch {
+                package: subject.package.clone(),
+                rule: rule.name.clone(),
+                action: rule.action,
+            })
+            .collect()
+    }
+}
+
+/// An action to take when a package matches a condition.
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize, JsonSchema)]
+pub struct Rule {
+    /// Name of the rule, shown when it matches.
+    pub name: String,
+    pub action: Action,
+    pub when: Condition,
+}
+
+/// A condition on a package.
+///
+/// Conditions on information which is not known for a package, like its
+/// publish date for a job's package status, never match.
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum Condition {
+    /// All conditions match.
+    All(Vec<Condition>),
+    /// At least one condition matches.
+    Any(Vec<Condition>),
+    /// The condition doesn't match.
+    Not(Box<Condition>),
+    /// The package has a matching issue.
+    Issue(IssueCondition),
+    /// The license matches a pattern like `GPL-*`, ignoring case.
+    ///
+    /// For license expressions like `MIT OR GPL-3.0` any license in the
+    /// expression can match.
+    License(String),
+    /// The package name matches a pattern like `@types/*`.
+    Name(String),
+    Ecosystem(PackageType),
+    /// A risk score is below the cutoff.
+    #[serde(rename_all = "camelCase")]
+    ScoreBelow {
+        risk_type: RiskType,
+        threshold: f32,
+    },
+    /// The package is no longer maintained.
+    Abandoned,
+    /// The maintainers of the package changed recently.
+    MaintainersChanged,
+    /// The package is a direct dependency of the project.
+    Direct,
+    /// The version was published less than this many days ago.
+    PublishedWithinDays(u32),
+}
+
+impl Condition {
+    fn matches(&self, subject: &RuleSubject, now: DateTime<Utc>) -> bool {
+        match self {
+            Condition::All(conditions) => conditions.iter().all(|c| c.matches(subject, now)),
+            Condition::Any(conditions) => conditions.iter().any(|c| c.matches(subject, now)),
+            Condition::Not(condition) => !condition.matches(subject, now),
+            Condition::Issue(condition) => {
+                subject.issues.iter().any(|issue| condition.matches(issue))
+            }
+            Condition::License(pattern) => subject
+                .license
+                .as_deref()
+                .is_some_and(|license| license_matches(pattern, license)),
+            Condition::Name(pattern) => glob_matches(pattern, &subject.package.name, false),
+            Condition::Ecosystem(package_type) => subject.package.package_type == *package_type,
+            Condition::ScoreBelow {
+                risk_type,
+                threshold,
+            } => subject
+                .scores
+                .iter()
+                .any(|(score_type, score)| score_type == risk_type && score < threshold),
+            Condition::Abandoned => subject.is_abandonware == Some(true),
+            Condition::MaintainersChanged => subject.maintainers_recently_changed == Some(true),
+            Condition::Direct => subject.direct == Some(true),
+            Condition::PublishedWithinDays(days) => subject
+                .published
+                .is_some_and(|published| now - published < Duration::days(i64::from(*days))),
+        }
+    }
+}
+
+/// Properties an issue must have, all of which are optional.
+#[derive(PartialEq, Clone, Debug, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct IssueCondition {
+    pub domain: Option<RiskDomain>,
+    /// Lowest severity of matching issues.
+    pub min_severity: Option<RiskLevel>,
+    /// Issue tag pattern, like `HV*`.
+    pub tag: Option<String>,
+    /// Whether ignored issues match.
+    #[serde(default)]
+    pub include_ignored: bool,
+}
+
+impl IssueCondition {
+    fn matches(&self, issue: &SubjectIssue) -> bool {
+        (self.include_ignored || !issue.ignored)
+            && self
+                .domain
+                .is_none_or(|domain| issue.domain == Some(domain))
+            && self
+                .min_severity
+                .is_none_or(|severity| issue.severity >= severity)
+            && self.tag.as_ref().is_none_or(|pattern| {
+                issue
+                    .tag
+                    .as_deref()
+                    .is_some_and(|tag| glob_matches(pattern, tag, false))
+            })
+    }
+}
+
+/// A rule which matched a package.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Debug, Serialize, Deserialize, JsonSchema)]
+pub struct RuleMatch {
+    pub package: PackageDescriptor,
+    pub rule: String,
+    pub action: Action,
+}
+
+/// The package information rules are evaluated against.
+///
+/// Built from a [`Package`] or a job's [`PackageStatusExtended`], which
+/// carry different details about a package.
+#[derive(PartialEq, Clone, Debug)]
+pub struct RuleSubject {
+    pub package: PackageDescriptor,
+    pub license: Option<String>,
+    pub issues: Vec<SubjectIssue>,
+    pub scores: Vec<(RiskType, f32)>,
+    pub is_abandonware: Option<bool>,
+    pub maintainers_recently_changed: Option<bool>,
+    pub published: Option<DateTime<Utc>>,
+    /// Whether the package is a direct dependency, which only the project's
+    /// manifest knows.
+    pub direct: Option<bool>,
+}
+
+impl RuleSubject {
+    pub fn with_direct(mut self, direct: bool) -> Self {
+        self.direct = Some(direct);
+        self
+    }
+}
+
+#[derive(PartialEq, Clone, Debug)]
+pub struct SubjectIssue {
+    pub tag: Option<String>,
+    pub domain: Option<RiskDomain>,
+    pub severity: RiskLevel,
+    pub ignored: bool,
+}
+
+impl From<&Package> for RuleSubject {
+    fn from(package: &Package) -> Self {
+        Self {
+            package: PackageDescriptor {
+                name: package.name.clone(),
+                version: package.version.clone(),
+                package_type: package.package_type(),
+            },
+            license: package.license.clone(),
+            issues: package
+                .issues
+                .iter()
+                .map(|issue| SubjectIssue {
+                    tag: issue.tag.clone(),
+                    domain: risk_domain(issue.risk_type),
+                    severity: issue.impact,
+                    ignored: issue.ignored != IgnoredReason::False,
+                })
+                .collect(),
+            scores: package.risk_scores.by_type().to_vec(),
+            is_abandonware: package.is_abandonware,
+            maintainers_recently_changed: package.maintainers_recently_changed,
+            published: package.published_date.as_deref().and_then(parse_date),
+            direct: None,
+        }
+    }
+}
+
+impl From<&PackageStatusExtended> for RuleSubject {
+    fn from(package: &PackageStatusExtended) -> Self {
+        let scores = package_scores(package, &[]).by_type().to_vec();
+
+        Self {
+            package: PackageDescriptor {
+                name: package.basic_status.name.clone(),
+                version: package.basic_status.version.clone(),
+                package_type: package.package_type.clone(),
+            },
+            license: package.basic_status.license.clone(),
+            issues: package
+                .issues
+                .iter()
+                .map(|issue| SubjectIssue {
+                    tag: issue.issue.tag.clone(),
+                    domain: Some(issue.issue.domain),
+                    severity: issue.issue.severity,
+                    ignored: is_ignored(issue, &[]),
+                })
+                .collect(),
+            scores,
+            is_abandonware: None,
+            maintainers_recently_changed: None,
+            published: None,
+            direct: None,
+        }
+    }
+}
+
+fn risk_domain(risk_type: RiskType) -> Option<RiskDomain> {
+    match risk_type {
+        RiskType::TotalRisk => None,
+        RiskType::Vulnerabilities => Some(RiskDomain::Vulnerabilities),
+        RiskType::MaliciousRisk => Some(RiskDomain::Malicious),
+        RiskType::AuthorsRisk => Some(RiskDomain::AuthorRisk),
+        RiskType::EngineeringRisk => Some(RiskDomain::EngineeringRisk),
+        RiskType::LicenseRisk => Some(RiskDomain::LicenseRisk),
+    }
+}
+
+/// Parse an RFC 3339 timestamp or a plain `YYYY-MM-DD` date.
+fn parse_date(date: &str) -> Option<DateTime<Utc>> {
+    if let Ok(date) = DateTime::parse_from_rfc3339(date) {
+        return Some(date.with_timezone(&Utc));
+    }
+    let date = NaiveDate::parse_from_str(date, "%Y-%m-%d").ok()?;
+    Some(DateTime::from_naive_utc_and_offset(
+        date.and_hms_opt(0, 0, 0)?,
+        Utc,
+    ))
+}
+
+fn license_matches(pattern: &str, license: &str) -> bool {
+    if glob_matches(pattern, license, true) {
+        return true;
+    }
+
+    license
+        .split(|c: char| c.is_whitespace() || c == '(' || c == ')')
+        .filter(|id| !id.is_empty() && !matches!(*id, "AND" | "OR" | "WITH"))
+        .any(|id| glob_matches(pattern, id, true))
+}
+
+/// Match a pattern where `*` matches any sequence of characters.
+pub(super) fn glob_matches(pattern: &str, text: &str, ignore_case: bool) -> bool {
+    let (pattern, text) = match ignore_case {
+        true => (pattern.to_lowercase(), text.to_lowercase()),
+        false => (pattern.to_owned(), text.to_owned()),
+    };
+
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let mut rest = match text.strip_prefix(first) {
+        Some(rest) => rest,
+        None => return false,
+    };
+
+    let parts: Vec<&str> = parts.collect();
+    let last = match parts.split_last() {
+        Some((last, middle)) => {
+            for part in middle {
+                match rest.find(part) {
+                    Some(index) => rest = &rest[index + part.len()..],
+                    None => return false,
+                }
+            }
+            last
+        }
+        // No wildcard, the whole text must match.
+        None => return rest.is_empty(),
+    };
+
+    rest.ends_with(last)
+}
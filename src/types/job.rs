@@ -1,19 +1,25 @@
 //! This module contains types involved with handling phylum processing jobs.
 
+use std::collections::HashSet;
+use std::fmt;
+use std::str::FromStr;
+
 use schemars::JsonSchema;
-use serde::{Deserialize, Serialize};
+use serde::{de, Deserialize, Deserializer, Serialize};
 
 use super::common::*;
 use super::project::*;
-use crate::types::package::{PackageDescriptorAndLockfile, PackageStatus, PackageStatusExtended};
+use crate::error::Error;
+use crate::types::package::{
+    edit_distance, PackageDescriptorAndLockfile, PackageStatus, PackageStatusExtended,
+};
+use crate::types::preferences::RiskThresholds;
 
 /// When a job is completed, and some requirement is not met ( such as quality
 /// level ), what action should be taken?
 /// In the case of the CLI, the value of this result is used to determine if the
 /// CLI should print a warning, or exit with a non-zero exit code.
-#[derive(
-    PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Debug, Serialize, Deserialize, JsonSchema,
-)]
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Copy, Clone, Debug, Serialize, JsonSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum Action {
     None,
@@ -21,6 +27,55 @@ pub enum Action {
     Break,
 }
 
+impl Action {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Action::None => "none",
+            Action::Warn => "warn",
+            Action::Break => "break",
+        }
+    }
+}
+
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Action {
+    type Err = Error;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        const ACTIONS: [Action; 3] = [Action::None, Action::Warn, Action::Break];
+
+        let lowercase = input.to_lowercase();
+        if let Some(action) = ACTIONS.iter().find(|action| action.as_str() == lowercase) {
+            return Ok(*action);
+        }
+
+        // Typos like `brake` must not silently disable a gate.
+        let suggestion = ACTIONS
+            .iter()
+            .map(|action| (edit_distance(action.as_str(), &lowercase), *action))
+            .filter(|(distance, _)| *distance <= lowercase.len() / 3 + 1)
+            .min_by_key(|(distance, _)| *distance)
+            .map(|(_, action)| action);
+
+        Err(Error::UnknownAction {
+            name: input.into(),
+            suggestion,
+        })
+    }
+}
+
+impl<'de> Deserialize<'de> for Action {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let action = String::deserialize(deserializer)?;
+        action.parse().map_err(de::Error::custom)
+    }
+}
+
 /// Metadata about a job
 #[derive(PartialEq, PartialOrd, Clone, Debug, Serialize, Deserialize, JsonSchema)]
 pub struct JobDescriptor {
@@ -57,6 +112,29 @@ pub struct SubmitPackageRequest {
     pub group_name: Option<String>,
 }
 
+impl SubmitPackageRequest {
+    /// Remove packages which only differ from an earlier package by the
+    /// spelling of their name, like `Django` and `django`.
+    ///
+    /// Names which are invalid for their ecosystem are compared as-is.
+    pub fn dedup_packages(&mut self) {
+        let mut seen = HashSet::new();
+        self.packages.retain(|package| {
+            let descriptor = &package.package_descriptor;
+            let name = descriptor
+                .package_name()
+                .map(|name| name.canonical().to_owned())
+                .unwrap_or_else(|_| descriptor.name.clone());
+            seen.insert((
+                descriptor.package_type.clone(),
+                name,
+                descriptor.version.clone(),
+                package.lockfile.clone(),
+            ))
+        });
+    }
+}
+
 /// Initial response after package has been submitted
 #[derive(
     PartialEq, Eq, PartialOrd, Ord, Hash, Copy, Clone, Debug, Serialize, Deserialize, JsonSchema,
@@ -125,6 +203,13 @@ pub struct JobStatusResponse<T> {
     pub packages: Vec<T>,
 }
 
+impl<T> JobStatusResponse<T> {
+    /// The job's thresholds, with the job's action for failures.
+    pub fn risk_thresholds(&self) -> RiskThresholds {
+        RiskThresholds::from_project_thresholds(self.thresholds, self.action)
+    }
+}
+
 /// Response from canceling a job
 #[derive(
     PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Debug, Serialize, Deserialize, JsonSchema,
@@ -7,6 +7,10 @@ pub mod common;
 pub mod group;
 pub mod job;
 pub mod package;
+pub mod package_name;
+pub mod package_url;
 pub mod preferences;
 pub mod project;
+pub mod upgrade;
 pub mod user_settings;
+pub mod version;
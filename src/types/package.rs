@@ -1,15 +1,21 @@
 //! Module containing data types reprsenting on-the-wire data for packages
 
+use std::cmp::Ordering;
 use std::collections::HashMap;
 use std::convert::TryFrom;
 use std::fmt;
+use std::iter::FromIterator;
 use std::str::FromStr;
 
 use chrono::{DateTime, Utc};
+use schemars::gen::SchemaGenerator;
+use schemars::schema::{InstanceType, Schema, SchemaObject, SubschemaValidation};
 use schemars::JsonSchema;
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
+use crate::error::Error;
 use crate::types::common::Status;
+use crate::types::version::compare_versions;
 
 /// Risk domains.
 #[derive(
@@ -80,10 +86,11 @@ impl fmt::Display for RiskLevel {
 }
 
 /// The package ecosystem
-#[derive(
-    PartialEq, Eq, PartialOrd, Ord, Hash, Copy, Clone, Debug, Serialize, Deserialize, JsonSchema,
-)]
-#[serde(rename_all = "lowercase")]
+///
+/// Ecosystems unknown to this version of the crate deserialize into
+/// [`PackageType::Other`], so older clients can still read responses from a
+/// newer API.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Debug)]
 pub enum PackageType {
     Npm,
     PyPi,
@@ -92,9 +99,33 @@ pub enum PackageType {
     Nuget,
     Cargo,
     Golang,
+    Composer,
+    Hex,
+    Pub,
+    CocoaPods,
+    Swift,
+    Conda,
+    Other(Box<str>),
 }
 
 impl PackageType {
+    /// All ecosystems known to this version of the crate.
+    pub const KNOWN: [PackageType; 13] = [
+        PackageType::Npm,
+        PackageType::PyPi,
+        PackageType::Maven,
+        PackageType::RubyGems,
+        PackageType::Nuget,
+        PackageType::Cargo,
+        PackageType::Golang,
+        PackageType::Composer,
+        PackageType::Hex,
+        PackageType::Pub,
+        PackageType::CocoaPods,
+        PackageType::Swift,
+        PackageType::Conda,
+    ];
+
     pub fn language(&self) -> &str {
         match self {
             PackageType::Npm => "Javascript",
@@ -104,37 +135,238 @@ impl PackageType {
             PackageType::Nuget => ".NET",
             PackageType::Cargo => "Rust",
             PackageType::Golang => "Golang",
+            PackageType::Composer => "PHP",
+            PackageType::Hex => "Elixir",
+            PackageType::Pub => "Dart",
+            PackageType::CocoaPods => "Objective-C",
+            PackageType::Swift => "Swift",
+            PackageType::Conda => "Python",
+            PackageType::Other(_) => "Unknown",
+        }
+    }
+
+    /// The name used for this ecosystem on the wire.
+    pub fn as_str(&self) -> &str {
+        match self {
+            PackageType::Npm => "npm",
+            PackageType::PyPi => "pypi",
+            PackageType::Maven => "maven",
+            PackageType::RubyGems => "rubygems",
+            PackageType::Nuget => "nuget",
+            PackageType::Cargo => "cargo",
+            PackageType::Golang => "golang",
+            PackageType::Composer => "composer",
+            PackageType::Hex => "hex",
+            PackageType::Pub => "pub",
+            PackageType::CocoaPods => "cocoapods",
+            PackageType::Swift => "swift",
+            PackageType::Conda => "conda",
+            PackageType::Other(name) => name,
+        }
+    }
+
+    /// The package URL type of this ecosystem.
+    ///
+    /// See <https://github.com/package-url/purl-spec/blob/master/PURL-TYPES.rst>.
+    pub fn purl_type(&self) -> &str {
+        match self {
+            PackageType::RubyGems => "gem",
+            package_type => package_type.as_str(),
+        }
+    }
+
+    /// Get the ecosystem for a package URL type.
+    pub fn from_purl_type(purl_type: &str) -> Result<Self, Error> {
+        Ok(match purl_type.to_lowercase().as_str() {
+            "npm" => PackageType::Npm,
+            "pypi" => PackageType::PyPi,
+            "maven" => PackageType::Maven,
+            "gem" => PackageType::RubyGems,
+            "nuget" => PackageType::Nuget,
+            "cargo" => PackageType::Cargo,
+            "golang" => PackageType::Golang,
+            "composer" => PackageType::Composer,
+            "hex" => PackageType::Hex,
+            "pub" => PackageType::Pub,
+            "cocoapods" => PackageType::CocoaPods,
+            "swift" => PackageType::Swift,
+            "conda" => PackageType::Conda,
+            _ => {
+                return Err(Error::UnknownEcosystem {
+                    name: purl_type.into(),
+                    suggestion: None,
+                })
+            }
+        })
+    }
+
+    fn from_wire_name(name: &str) -> Self {
+        match name {
+            "npm" => PackageType::Npm,
+            "pypi" => PackageType::PyPi,
+            "maven" => PackageType::Maven,
+            "rubygems" => PackageType::RubyGems,
+            "nuget" => PackageType::Nuget,
+            "cargo" => PackageType::Cargo,
+            "golang" => PackageType::Golang,
+            "composer" => PackageType::Composer,
+            "hex" => PackageType::Hex,
+            "pub" => PackageType::Pub,
+            "cocoapods" => PackageType::CocoaPods,
+            "swift" => PackageType::Swift,
+            "conda" => PackageType::Conda,
+            _ => PackageType::Other(name.into()),
         }
     }
 }
 
+/// Names accepted by [`PackageType::from_str`].
+const PACKAGE_TYPE_ALIASES: &[(&str, PackageType)] = &[
+    ("npm", PackageType::Npm),
+    ("python", PackageType::PyPi),
+    ("pypi", PackageType::PyPi),
+    ("maven", PackageType::Maven),
+    ("maven-central", PackageType::Maven),
+    ("ruby", PackageType::RubyGems),
+    ("rubygems", PackageType::RubyGems),
+    ("gem", PackageType::RubyGems),
+    ("nuget", PackageType::Nuget),
+    ("dotnet", PackageType::Nuget),
+    ("cargo", PackageType::Cargo),
+    ("golang", PackageType::Golang),
+    ("composer", PackageType::Composer),
+    ("packagist", PackageType::Composer),
+    ("php", PackageType::Composer),
+    ("hex", PackageType::Hex),
+    ("hexpm", PackageType::Hex),
+    ("elixir", PackageType::Hex),
+    ("pub", PackageType::Pub),
+    ("dart", PackageType::Pub),
+    ("flutter", PackageType::Pub),
+    ("cocoapods", PackageType::CocoaPods),
+    ("pod", PackageType::CocoaPods),
+    ("pods", PackageType::CocoaPods),
+    ("swift", PackageType::Swift),
+    ("swiftpm", PackageType::Swift),
+    ("spm", PackageType::Swift),
+    ("conda", PackageType::Conda),
+    ("anaconda", PackageType::Conda),
+];
+
 impl FromStr for PackageType {
-    type Err = ();
+    type Err = Error;
 
     fn from_str(input: &str) -> Result<Self, Self::Err> {
-        match input.to_lowercase().as_str() {
-            "npm" => Ok(Self::Npm),
-            "python" | "pypi" => Ok(Self::PyPi),
-            "maven" | "maven-central" => Ok(Self::Maven),
-            "ruby" | "rubygems" | "gem" => Ok(Self::RubyGems),
-            "nuget" | "dotnet" => Ok(Self::Nuget),
-            "cargo" => Ok(Self::Cargo),
-            "golang" => Ok(Self::Golang),
-            _ => Err(()),
+        let lowercase = input.to_lowercase();
+        if let Some((_, package_type)) = PACKAGE_TYPE_ALIASES
+            .iter()
+            .find(|(alias, _)| *alias == lowercase)
+        {
+            return Ok(package_type.clone());
         }
+
+        // Suggest the closest alias, unless it's too different to be a typo.
+        let suggestion = PACKAGE_TYPE_ALIASES
+            .iter()
+            .map(|(alias, package_type)| (edit_distance(alias, &lowercase), package_type))
+            .filter(|(distance, _)| *distance <= lowercase.len() / 3 + 1)
+            .min_by_key(|(distance, _)| *distance)
+            .map(|(_, package_type)| package_type.clone());
+
+        Err(Error::UnknownEcosystem {
+            name: input.into(),
+            suggestion,
+        })
     }
 }
 
+/// Edit distance between two strings, counting adjacent transpositions as a
+/// single edit.
+pub(crate) fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut distances = vec![vec![0; b.len() + 1]; a.len() + 1];
+
+    for (i, row) in distances.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, distance) in distances[0].iter_mut().enumerate() {
+        *distance = j;
+    }
+
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            let mut distance = (distances[i - 1][j] + 1)
+                .min(distances[i][j - 1] + 1)
+                .min(distances[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                distance = distance.min(distances[i - 2][j - 2] + 1);
+            }
+            distances[i][j] = distance;
+        }
+    }
+
+    distances[a.len()][b.len()]
+}
+
 impl fmt::Display for PackageType {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let package_type = format!("{self:?}");
-        write!(f, "{}", package_type.to_lowercase())
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl Serialize for PackageType {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for PackageType {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        Ok(PackageType::from_wire_name(&name))
     }
 }
 
-impl From<PackageType> for purl::PackageType {
-    fn from(package_type: PackageType) -> purl::PackageType {
-        match package_type {
+impl JsonSchema for PackageType {
+    fn schema_name() -> String {
+        String::from("PackageType")
+    }
+
+    /// One of the known ecosystems, or any other name for ecosystems added
+    /// after this version of the crate.
+    fn json_schema(_gen: &mut SchemaGenerator) -> Schema {
+        let known = SchemaObject {
+            instance_type: Some(InstanceType::String.into()),
+            enum_values: Some(
+                PackageType::KNOWN
+                    .iter()
+                    .map(|package_type| package_type.as_str().into())
+                    .collect(),
+            ),
+            ..SchemaObject::default()
+        };
+        let other = SchemaObject {
+            instance_type: Some(InstanceType::String.into()),
+            ..SchemaObject::default()
+        };
+
+        Schema::Object(SchemaObject {
+            subschemas: Some(Box::new(SubschemaValidation {
+                any_of: Some(vec![known.into(), other.into()]),
+                ..SubschemaValidation::default()
+            })),
+            ..SchemaObject::default()
+        })
+    }
+}
+
+impl TryFrom<PackageType> for purl::PackageType {
+    type Error = Error;
+
+    fn try_from(package_type: PackageType) -> Result<purl::PackageType, Self::Error> {
+        Ok(match package_type {
             PackageType::Npm => purl::PackageType::Npm,
             PackageType::PyPi => purl::PackageType::PyPI,
             PackageType::Maven => purl::PackageType::Maven,
@@ -142,26 +374,16 @@ impl From<PackageType> for purl::PackageType {
             PackageType::Nuget => purl::PackageType::NuGet,
             PackageType::Cargo => purl::PackageType::Cargo,
             PackageType::Golang => purl::PackageType::Golang,
-        }
+            package_type => return Err(Error::UnsupportedEcosystem(package_type)),
+        })
     }
 }
 
 impl TryFrom<purl::PackageType> for PackageType {
-    type Error = purl::UnsupportedPackageType;
+    type Error = Error;
 
-    fn try_from(
-        package_type: purl::PackageType,
-    ) -> Result<PackageType, purl::UnsupportedPackageType> {
-        Ok(match package_type {
-            purl::PackageType::Cargo => PackageType::Cargo,
-            purl::PackageType::Gem => PackageType::RubyGems,
-            purl::PackageType::Golang => PackageType::Golang,
-            purl::PackageType::Maven => PackageType::Maven,
-            purl::PackageType::Npm => PackageType::Npm,
-            purl::PackageType::NuGet => PackageType::Nuget,
-            purl::PackageType::PyPI => PackageType::PyPi,
-            _ => return Err(purl::UnsupportedPackageType),
-        })
+    fn try_from(package_type: purl::PackageType) -> Result<PackageType, Self::Error> {
+        PackageType::from_purl_type(package_type.name())
     }
 }
 
@@ -169,6 +391,9 @@ impl TryFrom<purl::PackageType> for PackageType {
 pub struct ScoredVersion {
     pub version: String,
     pub total_risk_score: Option<f32>,
+    /// Version was removed from the registry by its publisher.
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub yanked: bool,
 }
 
 #[derive(
@@ -193,7 +418,7 @@ impl From<&PackageDescriptor> for PackageSpecifier {
 }
 
 impl TryFrom<PackageSpecifier> for PackageDescriptor {
-    type Error = String;
+    type Error = Error;
 
     fn try_from(value: PackageSpecifier) -> Result<Self, Self::Error> {
         let PackageSpecifier {
@@ -201,8 +426,7 @@ impl TryFrom<PackageSpecifier> for PackageDescriptor {
             name,
             version,
         } = value;
-        let package_type = PackageType::from_str(&registry)
-            .map_err(|()| format!("Failed to convert registry {registry} to package type"))?;
+        let package_type = PackageType::from_str(&registry)?;
         Ok(PackageDescriptor {
             name,
             version,
@@ -226,6 +450,113 @@ pub struct RiskScores {
     pub license: f32,
 }
 
+impl RiskScores {
+    /// Compute scores from the severity of issues.
+    ///
+    /// The score of a domain is the score of its most severe issue, or `1.0`
+    /// if it has no issues. The total is the lowest of all domain scores and
+    /// of issues which affect the total only.
+    ///
+    /// The conversions from issue lists skip ignored issues, so the result
+    /// reflects a package's scores after its issues were suppressed.
+    pub fn from_issues<I>(issues: I) -> Self
+    where
+        I: IntoIterator<Item = (RiskType, RiskLevel)>,
+    {
+        let mut scores = RiskScores {
+            total: 1.,
+            vulnerability: 1.,
+            malicious: 1.,
+            author: 1.,
+            engineering: 1.,
+            license: 1.,
+        };
+
+        for (risk_type, severity) in issues {
+            let score = scores.get_mut(risk_type);
+            *score = score.min(severity.score());
+        }
+
+        scores.total = scores.lowest_domain_score(scores.total);
+        scores
+    }
+
+    /// The score of a risk domain.
+    pub fn get(&self, risk_type: RiskType) -> f32 {
+        match risk_type {
+            RiskType::TotalRisk => self.total,
+            RiskType::Vulnerabilities => self.vulnerability,
+            RiskType::MaliciousRisk => self.malicious,
+            RiskType::AuthorsRisk => self.author,
+            RiskType::EngineeringRisk => self.engineering,
+            RiskType::LicenseRisk => self.license,
+        }
+    }
+
+    pub fn get_mut(&mut self, risk_type: RiskType) -> &mut f32 {
+        match risk_type {
+            RiskType::TotalRisk => &mut self.total,
+            RiskType::Vulnerabilities => &mut self.vulnerability,
+            RiskType::MaliciousRisk => &mut self.malicious,
+            RiskType::AuthorsRisk => &mut self.author,
+            RiskType::EngineeringRisk => &mut self.engineering,
+            RiskType::LicenseRisk => &mut self.license,
+        }
+    }
+
+    /// All scores, ordered by risk type.
+    pub fn by_type(&self) -> [(RiskType, f32); 6] {
+        [
+            (RiskType::TotalRisk, self.total),
+            (RiskType::Vulnerabilities, self.vulnerability),
+            (RiskType::MaliciousRisk, self.malicious),
+            (RiskType::AuthorsRisk, self.author),
+            (RiskType::EngineeringRisk, self.engineering),
+            (RiskType::LicenseRisk, self.license),
+        ]
+    }
+
+    /// The lowest of `total` and all domain scores.
+    pub(crate) fn lowest_domain_score(&self, total: f32) -> f32 {
+        self.by_type()
+            .iter()
+            .filter(|(risk_type, _)| *risk_type != RiskType::TotalRisk)
+            .fold(total, |total, (_, score)| total.min(*score))
+    }
+}
+
+impl From<&[Issue]> for RiskScores {
+    fn from(issues: &[Issue]) -> Self {
+        Self::from_issues(
+            issues
+                .iter()
+                .map(|issue| (issue.domain.into(), issue.severity)),
+        )
+    }
+}
+
+impl From<&[IssueStatus]> for RiskScores {
+    fn from(issues: &[IssueStatus]) -> Self {
+        Self::from_issues(
+            issues
+                .iter()
+                .filter(|issue| issue.ignored == IgnoredReason::False)
+                .map(|issue| (issue.issue.domain.into(), issue.issue.severity)),
+        )
+    }
+}
+
+impl From<&[IssuesListItem]> for RiskScores {
+    fn from(issues: &[IssuesListItem]) -> Self {
+        Self::from_issues(
+            issues
+                .iter()
+                .filter(|issue| issue.ignored == IgnoredReason::False)
+                .map(|issue| (issue.risk_type, issue.impact)),
+        )
+    }
+}
+
 /// Change in score over time.
 #[derive(PartialEq, PartialOrd, Clone, Debug, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
@@ -371,29 +702,92 @@ pub struct DeveloperResponsiveness {
 #[serde(rename_all = "camelCase")]
 #[serde(default)]
 pub struct IssueImpacts {
+    /// Omitted when zero, for clients which predate this field.
+    #[serde(skip_serializing_if = "is_zero")]
+    pub info: u32,
     pub low: u32,
     pub medium: u32,
     pub high: u32,
     pub critical: u32,
 }
 
-impl From<&[Issue]> for IssueImpacts {
-    fn from(issues: &[Issue]) -> Self {
+impl IssueImpacts {
+    /// Count issues of the given severity.
+    pub fn add(&mut self, severity: RiskLevel) {
+        match severity {
+            RiskLevel::Info => self.info += 1,
+            RiskLevel::Low => self.low += 1,
+            RiskLevel::Medium => self.medium += 1,
+            RiskLevel::High => self.high += 1,
+            RiskLevel::Critical => self.critical += 1,
+        }
+    }
+
+    /// Number of issues of any severity.
+    pub fn total(&self) -> u32 {
+        self.info + self.low + self.medium + self.high + self.critical
+    }
+
+    /// Count job issues, optionally skipping ignored ones.
+    pub fn from_statuses(issues: &[IssueStatus], include_ignored: bool) -> Self {
+        issues
+            .iter()
+            .filter(|issue| include_ignored || issue.ignored == IgnoredReason::False)
+            .map(|issue| issue.issue.severity)
+            .collect()
+    }
+
+    /// Count package issues, optionally skipping ignored ones.
+    pub fn from_list_items(issues: &[IssuesListItem], include_ignored: bool) -> Self {
+        issues
+            .iter()
+            .filter(|issue| include_ignored || issue.ignored == IgnoredReason::False)
+            .map(|issue| issue.impact)
+            .collect()
+    }
+}
+
+impl FromIterator<RiskLevel> for IssueImpacts {
+    fn from_iter<I: IntoIterator<Item = RiskLevel>>(severities: I) -> Self {
         let mut impacts = IssueImpacts::default();
-        for issue in issues {
-            match issue.severity.score() {
-                score if score >= 0.8 => impacts.low += 1,
-                score if (0.5..0.8).contains(&score) => impacts.medium += 1,
-                score if (0.2..0.5).contains(&score) => impacts.high += 1,
-                _ => impacts.critical += 1,
-            }
+        for severity in severities {
+            impacts.add(severity);
         }
         impacts
     }
 }
 
+impl From<&[Issue]> for IssueImpacts {
+    fn from(issues: &[Issue]) -> Self {
+        issues.iter().map(|issue| issue.severity).collect()
+    }
+}
+
+/// Counts all issues, including ignored ones.
+impl From<&[IssueStatus]> for IssueImpacts {
+    fn from(issues: &[IssueStatus]) -> Self {
+        Self::from_statuses(issues, true)
+    }
+}
+
+/// Counts all issues, including ignored ones.
+impl From<&[IssuesListItem]> for IssueImpacts {
+    fn from(issues: &[IssuesListItem]) -> Self {
+        Self::from_list_items(issues, true)
+    }
+}
+
+fn is_zero(count: &u32) -> bool {
+    *count == 0
+}
+
+fn is_false(value: &bool) -> bool {
+    !*value
+}
+
 #[derive(Deserialize, Serialize, PartialEq, Debug)]
 #[serde(tag = "status", content = "data")]
+#[allow(clippy::large_enum_variant)]
 pub enum PackageSubmitResponse {
     AlreadyProcessed(Package),
     AlreadySubmitted,
@@ -472,9 +866,14 @@ pub struct Vulnerability {
 }
 
 /// Describes a package in the system
-#[derive(
-    PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Debug, Serialize, Deserialize, JsonSchema,
-)]
+///
+/// Descriptors are ordered by name and ecosystem, then by version using the
+/// ecosystem's version semantics. Versions are only parsed when name and
+/// ecosystem match and the versions aren't plain numbers like `1.10.0`, sort
+/// large lists by [`EcosystemVersion`] keys to parse every version once.
+///
+/// [`EcosystemVersion`]: crate::types::version::EcosystemVersion
+#[derive(PartialEq, Eq, Hash, Clone, Debug, Serialize, Deserialize, JsonSchema)]
 pub struct PackageDescriptor {
     pub name: String,
     pub version: String,
@@ -483,6 +882,22 @@ pub struct PackageDescriptor {
     pub package_type: PackageType,
 }
 
+impl PartialOrd for PackageDescriptor {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for PackageDescriptor {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.name
+            .cmp(&other.name)
+            .then_with(|| self.package_type.cmp(&other.package_type))
+            .then_with(|| compare_versions(&self.package_type, &self.version, &other.version))
+            .then_with(|| self.version.cmp(&other.version))
+    }
+}
+
 /// `PackageDescriptorAndLockfile` represents a parsed package
 /// (`package_descriptor`) and the optional path to its lockfile (`lockfile`).
 #[derive(
@@ -0,0 +1,278 @@
+//! Ecosystem-aware validation and normalization of package names.
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+
+use crate::error::Error;
+use crate::types::package::{PackageDescriptor, PackageType};
+use crate::types::package_url::normalize_pypi_name;
+
+/// A package name which follows its registry's naming rules.
+///
+/// Names are compared, ordered and hashed by their canonical form, so
+/// `Django` and `django` are the same PyPI package, while the original
+/// spelling is kept for display.
+#[derive(Clone, Debug)]
+pub struct PackageName {
+    package_type: PackageType,
+    name: String,
+    canonical: String,
+}
+
+impl PackageName {
+    /// Validate a package name for the given ecosystem.
+    pub fn new(package_type: PackageType, name: impl Into<String>) -> Result<Self, Error> {
+        let name = name.into();
+        let canonical = match canonicalize(&package_type, &name) {
+            Ok(canonical) => canonical,
+            Err(reason) => {
+                return Err(Error::InvalidName {
+                    package_type,
+                    name,
+                    reason: reason.into(),
+                })
+            }
+        };
+
+        Ok(Self {
+            package_type,
+            name,
+            canonical,
+        })
+    }
+
+    /// The name as it was originally spelled.
+    pub fn as_str(&self) -> &str {
+        &self.name
+    }
+
+    /// The normalized name used by the registry to identify the package.
+    pub fn canonical(&self) -> &str {
+        &self.canonical
+    }
+
+    pub fn package_type(&self) -> &PackageType {
+        &self.package_type
+    }
+}
+
+impl PartialEq for PackageName {
+    fn eq(&self, other: &Self) -> bool {
+        self.package_type == other.package_type && self.canonical == other.canonical
+    }
+}
+
+impl Eq for PackageName {}
+
+impl PartialOrd for PackageName {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for PackageName {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (&self.package_type, &self.canonical).cmp(&(&other.package_type, &other.canonical))
+    }
+}
+
+impl Hash for PackageName {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.package_type.hash(state);
+        self.canonical.hash(state);
+    }
+}
+
+impl fmt::Display for PackageName {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
+impl AsRef<str> for PackageName {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl PackageDescriptor {
+    /// Validated name of this package.
+    pub fn package_name(&self) -> Result<PackageName, Error> {
+        PackageName::new(self.package_type.clone(), self.name.as_str())
+    }
+}
+
+/// Validate a name and return its canonical form.
+fn canonicalize(package_type: &PackageType, name: &str) -> Result<String, &'static str> {
+    if name.is_empty() {
+        return Err("name is empty");
+    }
+    if name.chars().any(char::is_whitespace) {
+        return Err("name contains whitespace");
+    }
+
+    match package_type {
+        // https://github.com/npm/validate-npm-package-name
+        PackageType::Npm => {
+            if name.len() > 214 {
+                return Err("name is longer than 214 characters");
+            }
+            let unscoped = match name.strip_prefix('@') {
+                Some(scoped) => {
+                    let (scope, unscoped) = scoped
+                        .split_once('/')
+                        .ok_or("scoped name must be `@scope/name`")?;
+                    if scope.is_empty() {
+                        return Err("scope is empty");
+                    }
+                    check_chars(scope, is_url_safe)?;
+                    unscoped
+                }
+                None => name,
+            };
+            if unscoped.is_empty() {
+                return Err("name is empty");
+            }
+            if unscoped.starts_with('.') || unscoped.starts_with('_') {
+                return Err("name can't start with `.` or `_`");
+            }
+            check_chars(unscoped, is_url_safe)?;
+            // Names with capital letters are only allowed for legacy packages,
+            // the registry treats them case-insensitively.
+            Ok(name.to_lowercase())
+        }
+        // https://peps.python.org/pep-0508/#names
+        PackageType::PyPi => {
+            check_chars(name, is_name_char)?;
+            check_alphanumeric_ends(name)?;
+            Ok(normalize_pypi_name(name))
+        }
+        // https://doc.rust-lang.org/cargo/reference/manifest.html#the-name-field
+        PackageType::Cargo => {
+            if name.len() > 64 {
+                return Err("name is longer than 64 characters");
+            }
+            if !name.starts_with(|c: char| c.is_ascii_alphabetic()) {
+                return Err("name must start with a letter");
+            }
+            check_chars(name, |c| {
+                c.is_ascii_alphanumeric() || matches!(c, '-' | '_')
+            })?;
+            // crates.io considers `-` and `_` equivalent.
+            Ok(name.to_lowercase().replace('_', "-"))
+        }
+        PackageType::Maven => {
+            let (group, artifact) = name
+                .split_once(':')
+                .ok_or("expected `groupId:artifactId`")?;
+            for part in [group, artifact] {
+                if part.is_empty() {
+                    return Err("expected `groupId:artifactId`");
+                }
+                check_chars(part, is_name_char)?;
+            }
+            Ok(name.into())
+        }
+        PackageType::RubyGems => {
+            check_chars(name, is_name_char)?;
+            if !name.contains(|c: char| c.is_ascii_alphabetic()) {
+                return Err("name must contain a letter");
+            }
+            Ok(name.into())
+        }
+        // https://learn.microsoft.com/en-us/nuget/reference/nuspec#id
+        PackageType::Nuget => {
+            if name.len() > 100 {
+                return Err("name is longer than 100 characters");
+            }
+            check_chars(name, is_name_char)?;
+            Ok(name.to_lowercase())
+        }
+        // https://go.dev/ref/mod#module-path
+        PackageType::Golang => {
+            if name.starts_with('/') || name.ends_with('/') || name.contains("//") {
+                return Err("module path has an empty element");
+            }
+            check_chars(name, |c| {
+                c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-' | '~' | '/')
+            })?;
+            Ok(name.into())
+        }
+        // https://getcomposer.org/doc/04-schema.md#name
+        PackageType::Composer => {
+            let (vendor, package) = name.split_once('/').ok_or("expected `vendor/package`")?;
+            for part in [vendor, package] {
+                if part.is_empty() {
+                    return Err("expected `vendor/package`");
+                }
+                check_chars(part, is_name_char)?;
+                check_alphanumeric_ends(part)?;
+            }
+            Ok(name.to_lowercase())
+        }
+        // https://hex.pm/docs/publish
+        PackageType::Hex => {
+            if !name.starts_with(|c: char| c.is_ascii_lowercase()) {
+                return Err("name must start with a lowercase letter");
+            }
+            check_chars(name, |c| {
+                c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_'
+            })?;
+            Ok(name.into())
+        }
+        // https://dart.dev/tools/pub/pubspec#name
+        PackageType::Pub => {
+            if name.starts_with(|c: char| c.is_ascii_digit()) {
+                return Err("name can't start with a digit");
+            }
+            check_chars(name, |c| {
+                c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_'
+            })?;
+            Ok(name.into())
+        }
+        PackageType::CocoaPods => {
+            check_chars(name, |c| !c.is_control() && !matches!(c, '/' | '\'' | '"'))?;
+            Ok(name.into())
+        }
+        PackageType::Swift => {
+            if name.starts_with('/') || name.ends_with('/') || name.contains("//") {
+                return Err("repository path has an empty element");
+            }
+            // Swift packages are identified by their repository location, and
+            // hosts like GitHub resolve paths case-insensitively.
+            Ok(name.to_lowercase())
+        }
+        PackageType::Conda => {
+            check_chars(name, is_name_char)?;
+            Ok(name.to_lowercase())
+        }
+        PackageType::Other(_) => Ok(name.into()),
+    }
+}
+
+fn check_chars(name: &str, valid: impl Fn(char) -> bool) -> Result<(), &'static str> {
+    if name.chars().all(valid) {
+        Ok(())
+    } else {
+        Err("name contains invalid characters")
+    }
+}
+
+fn check_alphanumeric_ends(name: &str) -> Result<(), &'static str> {
+    let alphanumeric = |c: Option<char>| c.is_some_and(|c| c.is_ascii_alphanumeric());
+    if alphanumeric(name.chars().next()) && alphanumeric(name.chars().last()) {
+        Ok(())
+    } else {
+        Err("name must start and end with a letter or digit")
+    }
+}
+
+/// Characters most registries allow in names.
+fn is_name_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-')
+}
+
+fn is_url_safe(c: char) -> bool {
+    c.is_ascii_alphanumeric() || matches!(c, '-' | '.' | '_' | '~')
+}
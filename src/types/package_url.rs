@@ -0,0 +1,203 @@
+//! Conversions between package descriptors and package URLs.
+//!
+//! Package names are split into purl namespace and name following the rules
+//! of each ecosystem, see
+//! <https://github.com/package-url/purl-spec/blob/master/PURL-TYPES.rst>.
+
+use std::convert::TryFrom;
+
+use purl::GenericPurl;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::error::Error;
+use crate::types::package::{PackageDescriptor, PackageType};
+use crate::types::package_name::PackageName;
+
+/// A package descriptor with the optional purl qualifiers which identify a
+/// specific artifact of the package.
+#[derive(
+    PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Debug, Serialize, Deserialize, JsonSchema,
+)]
+pub struct PackageUrlDescriptor {
+    #[serde(flatten)]
+    pub package_descriptor: PackageDescriptor,
+    /// Registry the package is hosted on, if not the ecosystem's default.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub repository_url: Option<String>,
+    /// Maven classifier, like `sources` or `jdk8`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub classifier: Option<String>,
+    /// Artifact type, like `jar` or `pom` for Maven.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub artifact_type: Option<String>,
+}
+
+impl PackageUrlDescriptor {
+    /// Package URL for this package, including its qualifiers.
+    pub fn to_purl(&self) -> Result<String, Error> {
+        let qualifiers = [
+            ("classifier", &self.classifier),
+            ("repository_url", &self.repository_url),
+            ("type", &self.artifact_type),
+        ];
+
+        let mut builder = purl_builder(&self.package_descriptor)?;
+        for (key, value) in qualifiers {
+            if let Some(value) = value {
+                builder = builder.with_qualifier(key, value.as_str())?;
+            }
+        }
+
+        Ok(builder.build()?.to_string())
+    }
+}
+
+impl From<PackageDescriptor> for PackageUrlDescriptor {
+    fn from(package_descriptor: PackageDescriptor) -> Self {
+        Self {
+            package_descriptor,
+            repository_url: None,
+            classifier: None,
+            artifact_type: None,
+        }
+    }
+}
+
+impl TryFrom<&str> for PackageUrlDescriptor {
+    type Error = Error;
+
+    fn try_from(purl: &str) -> Result<Self, Self::Error> {
+        let purl: GenericPurl<String> = purl.parse()?;
+        let package_type = PackageType::from_purl_type(purl.package_type())?;
+
+        let version = purl.version().ok_or_else(|| Error::InvalidVersion {
+            package_type: package_type.clone(),
+            version: String::new(),
+            reason: String::from("package URL has no version"),
+        })?;
+
+        let name = join_name(&package_type, purl.namespace(), purl.name());
+        let qualifier = |key: &str| purl.qualifiers().get(key).map(String::from);
+
+        Ok(Self {
+            repository_url: qualifier("repository_url"),
+            classifier: qualifier("classifier"),
+            artifact_type: qualifier("type"),
+            package_descriptor: PackageDescriptor {
+                name,
+                version: version.into(),
+                package_type,
+            },
+        })
+    }
+}
+
+impl PackageDescriptor {
+    /// Package URL for this package.
+    pub fn to_purl(&self) -> Result<String, Error> {
+        Ok(purl_builder(self)?.build()?.to_string())
+    }
+}
+
+impl TryFrom<&str> for PackageDescriptor {
+    type Error = Error;
+
+    /// Parse a package URL, discarding its qualifiers.
+    fn try_from(purl: &str) -> Result<Self, Self::Error> {
+        PackageUrlDescriptor::try_from(purl).map(|purl| purl.package_descriptor)
+    }
+}
+
+/// Name and version of a package URL which may lack a version, ignoring its
+/// qualifiers.
+pub(crate) fn name_and_version(purl: &str) -> Result<(PackageName, Option<String>), Error> {
+    let purl: GenericPurl<String> = purl.parse()?;
+    let package_type = PackageType::from_purl_type(purl.package_type())?;
+    let name = join_name(&package_type, purl.namespace(), purl.name());
+    let version = purl.version().map(String::from);
+    Ok((PackageName::new(package_type, name)?, version))
+}
+
+fn purl_builder(descriptor: &PackageDescriptor) -> Result<purl::GenericPurlBuilder<String>, Error> {
+    let PackageDescriptor {
+        name,
+        version,
+        package_type,
+    } = descriptor;
+
+    let (namespace, name) = split_name(package_type, name)?;
+    let mut builder = GenericPurl::builder(package_type.purl_type().to_owned(), name);
+    if let Some(namespace) = namespace {
+        builder = builder.with_namespace(namespace);
+    }
+    Ok(builder.with_version(version.as_str()))
+}
+
+/// Split a package name into purl namespace and name.
+fn split_name<'a>(
+    package_type: &PackageType,
+    name: &'a str,
+) -> Result<(Option<&'a str>, String), Error> {
+    let invalid = |reason: &str| Error::InvalidName {
+        package_type: package_type.clone(),
+        name: name.into(),
+        reason: reason.into(),
+    };
+
+    if name.is_empty() {
+        return Err(invalid("name is empty"));
+    }
+
+    match package_type {
+        PackageType::Npm if name.starts_with('@') => {
+            let (scope, name) = name
+                .split_once('/')
+                .ok_or_else(|| invalid("scoped package has no name"))?;
+            Ok((Some(scope), name.into()))
+        }
+        PackageType::Maven => {
+            let (group, artifact) = name
+                .split_once(':')
+                .ok_or_else(|| invalid("expected `groupId:artifactId`"))?;
+            Ok((Some(group), artifact.into()))
+        }
+        PackageType::PyPi => Ok((None, normalize_pypi_name(name))),
+        PackageType::Golang | PackageType::Composer | PackageType::Swift => {
+            match name.rsplit_once('/') {
+                Some((namespace, name)) => Ok((Some(namespace), name.into())),
+                None => Ok((None, name.into())),
+            }
+        }
+        _ => Ok((None, name.into())),
+    }
+}
+
+/// Join purl namespace and name back into a package name.
+fn join_name(package_type: &PackageType, namespace: Option<&str>, name: &str) -> String {
+    match (package_type, namespace) {
+        (PackageType::PyPi, _) => normalize_pypi_name(name),
+        (PackageType::Maven, Some(group)) => format!("{group}:{name}"),
+        (_, Some(namespace)) => format!("{namespace}/{name}"),
+        (_, None) => name.into(),
+    }
+}
+
+/// PyPI names are compared after lowercasing and collapsing runs of `-`, `_`
+/// and `.` into a single `-`, see PEP 503.
+pub(crate) fn normalize_pypi_name(name: &str) -> String {
+    let mut normalized = String::with_capacity(name.len());
+    let mut separator = false;
+    for c in name.chars() {
+        if matches!(c, '-' | '_' | '.') {
+            if !separator {
+                normalized.push('-');
+            }
+            separator = true;
+        } else {
+            normalized.extend(c.to_lowercase());
+            separator = false;
+        }
+    }
+    normalized
+}
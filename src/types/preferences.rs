@@ -2,7 +2,10 @@ use schemars::JsonSchema;
 use serde::{self, Deserialize, Serialize};
 use uuid::Uuid;
 
+use crate::error::Error;
+use crate::types::job::Action;
 use crate::types::package::IgnoredReason;
+use crate::types::project::ProjectThresholds;
 use crate::types::user_settings::Threshold;
 
 #[derive(Debug, Default, Serialize, Deserialize, JsonSchema, Clone)]
@@ -27,7 +30,10 @@ pub struct ProjectPreferences {
 }
 
 /// Capture the project threshold settings.
-#[derive(Serialize, Deserialize, Clone, Debug, Default, JsonSchema)]
+///
+/// This is the canonical thresholds model, [`ProjectThresholds`] can be
+/// converted to and from it.
+#[derive(PartialEq, Serialize, Deserialize, Clone, Debug, Default, JsonSchema)]
 pub struct RiskThresholds {
     pub total: Threshold,
     pub author: Threshold,
@@ -39,6 +45,74 @@ pub struct RiskThresholds {
     pub vulnerability: Threshold,
 }
 
+impl RiskThresholds {
+    /// Thresholds from a job's cutoffs and the action it reported for
+    /// failures.
+    ///
+    /// The job format has one action for all domains and writes inactive
+    /// thresholds as a cutoff of zero, which can't fail.
+    pub fn from_project_thresholds(thresholds: ProjectThresholds, action: Action) -> Self {
+        let threshold = |threshold: f32| Threshold {
+            action,
+            active: threshold > 0.,
+            threshold,
+        };
+
+        Self {
+            total: threshold(thresholds.total),
+            author: threshold(thresholds.author),
+            engineering: threshold(thresholds.engineering),
+            license: threshold(thresholds.license),
+            malicious: threshold(thresholds.malicious),
+            vulnerability: threshold(thresholds.vulnerability),
+        }
+    }
+
+    /// Cutoffs and the shared action in the job format, the inverse of
+    /// [`Self::from_project_thresholds`].
+    ///
+    /// Fails if the thresholds can't be written without losing information:
+    /// if the domains use different actions, if an inactive threshold has a
+    /// cutoff or if an active threshold has a cutoff of zero.
+    pub fn to_project_thresholds(&self) -> Result<(ProjectThresholds, Action), Error> {
+        let domains = [
+            ("total", &self.total),
+            ("author", &self.author),
+            ("engineering", &self.engineering),
+            ("license", &self.license),
+            ("maliciousCode", &self.malicious),
+            ("vulnerability", &self.vulnerability),
+        ];
+
+        let action = self.total.action;
+        for (name, threshold) in domains {
+            if threshold.action != action {
+                return Err(Error::IncompatibleThresholds(format!(
+                    "{name} uses action \"{}\" but total uses \"{action}\"",
+                    threshold.action
+                )));
+            }
+            if threshold.active != (threshold.threshold > 0.) {
+                let reason = match threshold.active {
+                    true => "is active with a cutoff of zero",
+                    false => "is inactive with a cutoff above zero",
+                };
+                return Err(Error::IncompatibleThresholds(format!("{name} {reason}")));
+            }
+        }
+
+        let thresholds = ProjectThresholds {
+            author: self.author.threshold,
+            engineering: self.engineering.threshold,
+            license: self.license.threshold,
+            malicious: self.malicious.threshold,
+            total: self.total.threshold,
+            vulnerability: self.vulnerability.threshold,
+        };
+        Ok((thresholds, action))
+    }
+}
+
 /// Issues ignored from package score
 #[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq, JsonSchema)]
 pub struct IgnoredIssue {
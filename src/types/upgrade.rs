@@ -0,0 +1,163 @@
+//! Upgrade recommendations based on the risk scores of a package's versions.
+
+use std::fmt;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::types::package::{IssueImpacts, IssuesListItem, Package};
+use crate::types::version::{EcosystemVersion, VersionRange};
+
+/// Versions which may be considered for an upgrade.
+#[derive(Clone, Debug)]
+pub enum UpgradeConstraint {
+    /// Versions with the same major version as the current one.
+    SameMajor,
+    /// Versions within a declared range, like the requirement in a manifest.
+    Range(VersionRange),
+    /// Any newer version.
+    Any,
+}
+
+/// A suggested upgrade to a lower-risk version.
+#[derive(PartialEq, PartialOrd, Clone, Debug, Serialize, Deserialize, JsonSchema)]
+pub struct Recommendation {
+    pub current_version: String,
+    pub recommended_version: String,
+    pub current_score: f32,
+    pub recommended_score: f32,
+    /// Issues of the current version that the recommended version no longer
+    /// has, if the issues of both versions are known.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub removed_issues: Option<IssueImpacts>,
+}
+
+impl Recommendation {
+    /// Add the issues fixed by the upgrade, from the analysis of the current
+    /// and recommended versions.
+    pub fn with_issues(mut self, current: &Package, recommended: &Package) -> Self {
+        let impacts = current
+            .issues
+            .iter()
+            .filter(|issue| {
+                !recommended
+                    .issues
+                    .iter()
+                    .any(|other| same_issue(issue, other))
+            })
+            .map(|issue| issue.impact)
+            .collect();
+
+        self.removed_issues = Some(impacts);
+        self
+    }
+}
+
+/// Formats like `1.4.2 → 1.4.7 removes 2 critical issues, total 0.42 → 0.91`.
+impl fmt::Display for Recommendation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} → {}", self.current_version, self.recommended_version)?;
+
+        if let Some(removed) = &self.removed_issues {
+            let counts: Vec<String> = [
+                (removed.critical, "critical"),
+                (removed.high, "high"),
+                (removed.medium, "medium"),
+                (removed.low, "low"),
+            ]
+            .iter()
+            .filter(|(count, _)| *count > 0)
+            .map(|(count, severity)| format!("{count} {severity}"))
+            .collect();
+
+            if let Some((last, rest)) = counts.split_last() {
+                let total: u32 = removed.critical + removed.high + removed.medium + removed.low;
+                let issues = if total == 1 { "issue" } else { "issues" };
+                match rest.is_empty() {
+                    true => write!(f, " removes {last} {issues}")?,
+                    false => write!(f, " removes {} and {last} {issues}", rest.join(", "))?,
+                }
+            }
+        }
+
+        write!(
+            f,
+            ", total {:.2} → {:.2}",
+            self.current_score, self.recommended_score
+        )
+    }
+}
+
+impl Package {
+    /// The lowest-risk newer version allowed by the constraint.
+    ///
+    /// Yanked versions, versions without a score and pre-releases not
+    /// explicitly allowed by a range are skipped. If several versions share
+    /// the best score, the smallest upgrade is recommended. Returns `None`
+    /// if no candidate scores better than the current version.
+    pub fn recommend_upgrade(&self, constraint: &UpgradeConstraint) -> Option<Recommendation> {
+        let package_type = self.package_type();
+        let current = EcosystemVersion::parse(&package_type, &self.version).ok()?;
+        let current_score = self
+            .versions
+            .iter()
+            .find(|version| version.version == self.version)
+            .and_then(|version| version.total_risk_score)
+            .unwrap_or(self.risk_scores.total);
+
+        let mut best: Option<(EcosystemVersion, f32)> = None;
+        for scored in self.versions.iter().filter(|version| !version.yanked) {
+            let score = match scored.total_risk_score {
+                Some(score) => score,
+                None => continue,
+            };
+            let version = match EcosystemVersion::parse(&package_type, &scored.version) {
+                Ok(version) if version > current => version,
+                _ => continue,
+            };
+
+            let allowed = match constraint {
+                UpgradeConstraint::SameMajor => {
+                    !version.is_prerelease() && version.major() == current.major()
+                }
+                UpgradeConstraint::Range(range) => range.matches(&version),
+                UpgradeConstraint::Any => !version.is_prerelease(),
+            };
+            if !allowed {
+                continue;
+            }
+
+            let better = match &best {
+                Some((best_version, best_score)) => {
+                    score > *best_score || (score == *best_score && version < *best_version)
+                }
+                None => true,
+            };
+            if better {
+                best = Some((version, score));
+            }
+        }
+
+        let (version, score) = best?;
+        if score <= current_score {
+            return None;
+        }
+
+        Some(Recommendation {
+            current_version: self.version.clone(),
+            recommended_version: version.as_str().into(),
+            current_score,
+            recommended_score: score,
+            removed_issues: None,
+        })
+    }
+}
+
+/// Issues are matched by tag or ID, falling back to their title.
+fn same_issue(a: &IssuesListItem, b: &IssuesListItem) -> bool {
+    match (&a.tag, &b.tag, &a.id, &b.id) {
+        (Some(a), Some(b), ..) => a == b,
+        (.., Some(a), Some(b)) => a == b,
+        _ => a.title == b.title,
+    }
+}
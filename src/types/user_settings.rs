@@ -1,15 +1,21 @@
 //! This module contains types for manipulating user settings data
 
 use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::fmt;
 
 use schemars::JsonSchema;
-use serde::{Deserialize, Serialize};
+use serde::{de, Deserialize, Deserializer, Serialize};
+use serde_json::{Map, Value};
+
+use crate::error::Error;
+use crate::types::job::Action;
 
 /// Threshold for a given risk
 #[derive(PartialEq, PartialOrd, Clone, Debug, Serialize, Deserialize, JsonSchema)]
 pub struct Threshold {
-    // TODO Should this be the Action enum?
-    pub action: String,
+    /// The action to take if a package scores below the cutoff
+    pub action: Action,
     /// Is this threshold active
     pub active: bool,
     /// The risk threshold cutoff
@@ -19,7 +25,7 @@ pub struct Threshold {
 impl Default for Threshold {
     fn default() -> Self {
         Self {
-            action: String::from("none"),
+            action: Action::None,
             active: true,
             threshold: 0.6,
         }
@@ -31,13 +37,40 @@ pub struct UserProject {
     pub thresholds: HashMap<String, Threshold>,
 }
 
-#[derive(PartialEq, Clone, Debug, Serialize, Deserialize, JsonSchema)]
+/// Settings stored for a project.
+///
+/// Entries with `thresholds` are project settings, entries with only string
+/// values are labels. Anything else is rejected rather than guessed.
+#[derive(PartialEq, Clone, Debug, Serialize, JsonSchema)]
 #[serde(untagged)]
 pub enum Setting {
     DefaultLabel(HashMap<String, String>),
     Project(UserProject),
 }
 
+impl<'de> Deserialize<'de> for Setting {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let entries = Map::<String, Value>::deserialize(deserializer)?;
+
+        if entries.contains_key("thresholds") {
+            return UserProject::deserialize(Value::Object(entries))
+                .map(Setting::Project)
+                .map_err(de::Error::custom);
+        }
+
+        entries
+            .into_iter()
+            .map(|(key, value)| match value {
+                Value::String(value) => Ok((key, value)),
+                _ => Err(de::Error::custom(format!(
+                    "expected `thresholds` or a string value for {key:?}"
+                ))),
+            })
+            .collect::<Result<_, _>>()
+            .map(Setting::DefaultLabel)
+    }
+}
+
 /// Exposes the user settings most often used by the CLI
 // TODO Unify with API user settings type
 #[derive(PartialEq, Clone, Debug, Serialize, Deserialize, JsonSchema)]
@@ -46,7 +79,76 @@ pub struct UserSettings {
     pub projects: HashMap<String, Setting>,
 }
 
+/// A step migrating settings from `version` to the next version.
+struct Migration {
+    version: u32,
+    migrate: fn(&mut Map<String, Value>),
+}
+
+/// All migrations, ordered by version. A migration is registered here
+/// whenever the format changes.
+const MIGRATIONS: &[Migration] = &[Migration {
+    version: 1,
+    migrate: rename_malicious_threshold,
+}];
+
+/// Older clients wrote the `maliciousCode` threshold as `malicious`, both
+/// spellings exist in version 1 settings. If a project has both, the current
+/// spelling is kept.
+fn rename_malicious_threshold(settings: &mut Map<String, Value>) {
+    let projects = settings.get_mut("projects").and_then(Value::as_object_mut);
+    for project in projects
+        .into_iter()
+        .flat_map(|projects| projects.values_mut())
+    {
+        let thresholds = project.get_mut("thresholds").and_then(Value::as_object_mut);
+        if let Some(thresholds) = thresholds {
+            if let Some(threshold) = thresholds.remove("malicious") {
+                thresholds.entry("maliciousCode").or_insert(threshold);
+            }
+        }
+    }
+}
+
 impl UserSettings {
+    /// The settings version written by this crate.
+    pub const CURRENT_VERSION: u32 = 2;
+
+    /// Load settings of any known version from YAML or JSON, migrating them
+    /// to the current version.
+    ///
+    /// Settings without a version are treated as version 1.
+    pub fn load(input: &str) -> Result<Self, Error> {
+        let invalid = |err: &dyn fmt::Display| Error::InvalidSettings(err.to_string());
+
+        let mut settings: Map<String, Value> =
+            serde_yaml::from_str(input).map_err(|err| invalid(&err))?;
+
+        let mut version = match settings.get("version") {
+            None => 1,
+            Some(version) => version
+                .as_u64()
+                .and_then(|version| u32::try_from(version).ok())
+                .ok_or_else(|| invalid(&"version must be a positive integer"))?,
+        };
+        if version == 0 || version > Self::CURRENT_VERSION {
+            return Err(Error::UnsupportedSettingsVersion {
+                version,
+                latest: Self::CURRENT_VERSION,
+            });
+        }
+
+        for migration in MIGRATIONS {
+            if migration.version == version {
+                (migration.migrate)(&mut settings);
+                version += 1;
+            }
+        }
+        settings.insert("version".into(), version.into());
+
+        serde_json::from_value(Value::Object(settings)).map_err(|err| invalid(&err))
+    }
+
     /// Sets the threshold for the given risk domain.
     pub fn set_threshold(&mut self, project_id: String, name: String, threshold: Threshold) {
         // log::debug!("Retrieving user settings for project: {}", project_id);
@@ -0,0 +1,1635 @@
+//! Ecosystem-aware version ordering and range matching.
+//!
+//! Every ecosystem has its own idea of what a version looks like and how
+//! versions and ranges compare. [`EcosystemVersion`] and [`VersionRange`]
+//! pick the rules based on the [`PackageType`]:
+//!
+//! * npm, Cargo, Pub, Swift, Composer and Hex use semantic versioning, each
+//!   with their own range syntax. Cargo treats bare versions as caret
+//!   requirements, npm treats them as exact versions.
+//! * Go uses semantic versioning with a `v` prefix, pseudo-versions sort as
+//!   pre-releases.
+//! * NuGet uses up to four numeric components with case-insensitive pre-release
+//!   labels, and interval ranges where a bare version is a minimum.
+//! * PyPI follows PEP 440.
+//! * Maven follows `ComparableVersion`, with interval ranges.
+//! * RubyGems and CocoaPods follow `Gem::Version` and `Gem::Requirement`.
+//! * Conda and unknown ecosystems use a lenient segment-wise comparison.
+
+use std::cmp::Ordering;
+use std::str::FromStr;
+
+use crate::error::Error;
+use crate::types::package::{Package, PackageType, ScoredVersion};
+
+/// A version parsed with the rules of its ecosystem.
+///
+/// Versions are compared with the ecosystem's semantics, so `1.10.0` is
+/// greater than `1.9.0`, and Maven's `1.0` is equal to `1.0.0`. Versions of
+/// different ecosystems are ordered by ecosystem first.
+#[derive(Clone, Debug)]
+pub struct EcosystemVersion {
+    package_type: PackageType,
+    raw: String,
+    parsed: Parsed,
+}
+
+impl EcosystemVersion {
+    /// Parse a version for the given ecosystem.
+    pub fn parse(package_type: &PackageType, version: &str) -> Result<Self, Error> {
+        let parsed = Parsed::parse(Scheme::of(package_type), version).map_err(|reason| {
+            Error::InvalidVersion {
+                package_type: package_type.clone(),
+                version: version.into(),
+                reason: reason.into(),
+            }
+        })?;
+
+        Ok(Self {
+            package_type: package_type.clone(),
+            raw: version.into(),
+            parsed,
+        })
+    }
+
+    /// The version as it was originally written.
+    pub fn as_str(&self) -> &str {
+        &self.raw
+    }
+
+    pub fn package_type(&self) -> &PackageType {
+        &self.package_type
+    }
+
+    /// Check if this is a pre-release, like `1.0.0-beta.1` or `2.0rc1`.
+    pub fn is_prerelease(&self) -> bool {
+        self.parsed.is_prerelease()
+    }
+
+    /// The first release component, like `1` for `1.4.2`.
+    pub fn major(&self) -> Option<u64> {
+        match &self.parsed {
+            Parsed::Semver(version) => version.release.first().copied(),
+            Parsed::Pep440(version) => version.release.first().copied(),
+            Parsed::Maven(items) => match items.first() {
+                Some(MavenItem::Int(digits)) => digits.parse().ok(),
+                // Null items are dropped, so `0` is an empty list.
+                None => Some(0),
+                _ => None,
+            },
+            Parsed::Gem(version) => match version.segments.first() {
+                Some(GemSegment::Numeric(major)) => Some(*major),
+                _ => None,
+            },
+        }
+    }
+
+    /// Check if this version is within the range.
+    ///
+    /// Versions never match ranges of a different ecosystem.
+    pub fn satisfies(&self, range: &VersionRange) -> bool {
+        range.matches(self)
+    }
+}
+
+impl PartialEq for EcosystemVersion {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for EcosystemVersion {}
+
+impl PartialOrd for EcosystemVersion {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for EcosystemVersion {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.package_type
+            .cmp(&other.package_type)
+            .then_with(|| self.parsed.cmp(&other.parsed))
+    }
+}
+
+/// Compare two version strings of an ecosystem.
+///
+/// Unparsable versions sort after all valid versions and are compared as
+/// strings, which keeps the ordering total.
+pub fn compare_versions(package_type: &PackageType, a: &str, b: &str) -> Ordering {
+    let scheme = Scheme::of(package_type);
+    if let Some(ordering) = compare_plain_releases(scheme, a, b) {
+        return ordering;
+    }
+
+    match (Parsed::parse(scheme, a), Parsed::parse(scheme, b)) {
+        (Ok(a), Ok(b)) => a.cmp(&b),
+        (Ok(_), Err(_)) => Ordering::Less,
+        (Err(_), Ok(_)) => Ordering::Greater,
+        (Err(_), Err(_)) => a.cmp(b),
+    }
+}
+
+/// Compare versions which are only dot-separated numbers, like `1.10.0`,
+/// without parsing them.
+///
+/// Every scheme compares those component-wise with missing components as
+/// zero. Returns `None` for anything else, or if the scheme would reject
+/// either version.
+fn compare_plain_releases(scheme: Scheme, a: &str, b: &str) -> Option<Ordering> {
+    if a == b {
+        return Some(Ordering::Equal);
+    }
+
+    let valid = |version: &str| {
+        let mut components = 0;
+        for component in version.split('.') {
+            components += 1;
+            let leading_zero = component.len() > 1 && component.starts_with('0');
+            if component.is_empty()
+                || component.len() > 19
+                || !component.bytes().all(|b| b.is_ascii_digit())
+                || (scheme.is_strict_semver() && leading_zero)
+            {
+                return false;
+            }
+        }
+        match scheme {
+            _ if scheme.is_strict_semver() => components == 3,
+            Scheme::Go | Scheme::Composer | Scheme::Nuget => components <= 4,
+            _ => true,
+        }
+    };
+    if !valid(a) || !valid(b) {
+        return None;
+    }
+
+    let mut a = a.split('.').map(|n| n.parse::<u64>().unwrap_or(0));
+    let mut b = b.split('.').map(|n| n.parse::<u64>().unwrap_or(0));
+    loop {
+        match (a.next(), b.next()) {
+            (None, None) => return Some(Ordering::Equal),
+            (a, b) => match a.unwrap_or(0).cmp(&b.unwrap_or(0)) {
+                Ordering::Equal => continue,
+                ordering => return Some(ordering),
+            },
+        }
+    }
+}
+
+/// A version requirement in the syntax of its ecosystem, like `^1.2.3` for
+/// npm, `>=2.0,<3` for PyPI or `[1.0,2.0)` for Maven.
+#[derive(Clone, Debug)]
+pub struct VersionRange {
+    package_type: PackageType,
+    raw: String,
+    /// Alternatives, each of which is a list of constraints that must all
+    /// hold. No alternatives means any version matches.
+    sets: Vec<Vec<Constraint>>,
+}
+
+impl VersionRange {
+    /// Parse a version range for the given ecosystem.
+    pub fn parse(package_type: &PackageType, range: &str) -> Result<Self, Error> {
+        let scheme = Scheme::of(package_type);
+        let sets = match scheme {
+            Scheme::Pep440 => parse_pep440_range(range),
+            Scheme::Maven | Scheme::Nuget => parse_interval_range(scheme, range),
+            Scheme::RubyGems | Scheme::Generic => parse_gem_range(scheme, range),
+            _ => parse_semver_range(scheme, range),
+        }
+        .map_err(|reason| Error::InvalidVersion {
+            package_type: package_type.clone(),
+            version: range.into(),
+            reason: reason.into(),
+        })?;
+
+        Ok(Self {
+            package_type: package_type.clone(),
+            raw: range.into(),
+            sets,
+        })
+    }
+
+    /// The range as it was originally written.
+    pub fn as_str(&self) -> &str {
+        &self.raw
+    }
+
+    pub fn package_type(&self) -> &PackageType {
+        &self.package_type
+    }
+
+    /// Check if a version is within this range.
+    pub fn matches(&self, version: &EcosystemVersion) -> bool {
+        if version.package_type != self.package_type {
+            return false;
+        }
+
+        let scheme = Scheme::of(&self.package_type);
+        if self.sets.is_empty() {
+            return !version.is_prerelease() || scheme.prerelease_policy() == Prerelease::Include;
+        }
+
+        self.sets.iter().any(|set| {
+            set.iter()
+                .all(|constraint| constraint.matches(&version.parsed))
+                && allows_prerelease(scheme, set, &version.parsed)
+        })
+    }
+}
+
+impl Package {
+    /// The ecosystem of this package, falling back to
+    /// [`PackageType::Other`] for unknown registries.
+    pub(crate) fn package_type(&self) -> PackageType {
+        PackageType::from_str(&self.registry)
+            .unwrap_or_else(|_| PackageType::Other(self.registry.as_str().into()))
+    }
+
+    /// All known versions from oldest to newest.
+    pub fn versions_sorted(&self) -> Vec<&ScoredVersion> {
+        let scheme = Scheme::of(&self.package_type());
+        let mut versions: Vec<&ScoredVersion> = self.versions.iter().collect();
+        // Same order as `compare_versions`, but every version is parsed once.
+        versions.sort_by_cached_key(|version| {
+            Parsed::parse(scheme, &version.version).map_err(|_| version.version.clone())
+        });
+        versions
+    }
+
+    /// Check if this is the latest version of the package.
+    ///
+    /// Uses `latest_version` if the registry reported one, otherwise the
+    /// highest version in `versions`.
+    pub fn is_latest(&self) -> bool {
+        let package_type = self.package_type();
+        let latest = match &self.latest_version {
+            Some(latest) => latest.as_str(),
+            None => match self.versions_sorted().last() {
+                Some(latest) => latest.version.as_str(),
+                None => return true,
+            },
+        };
+        compare_versions(&package_type, &self.version, latest) != Ordering::Less
+    }
+}
+
+/// The versioning rules of an ecosystem.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Scheme {
+    Npm,
+    Cargo,
+    /// Semantic versioning with npm-like ranges, used by Pub and Swift.
+    Semver,
+    Go,
+    Composer,
+    Hex,
+    Nuget,
+    Pep440,
+    Maven,
+    RubyGems,
+    Generic,
+}
+
+/// When pre-release versions can match a range.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Prerelease {
+    /// Pre-releases match like any other version.
+    Include,
+    /// Pre-releases only match if a constraint in the same set is a
+    /// pre-release of the same `major.minor.patch`.
+    SameRelease,
+    /// Pre-releases only match if a constraint in the same set is a
+    /// pre-release.
+    Explicit,
+}
+
+impl Scheme {
+    fn of(package_type: &PackageType) -> Self {
+        match package_type {
+            PackageType::Npm => Scheme::Npm,
+            PackageType::Cargo => Scheme::Cargo,
+            PackageType::Pub | PackageType::Swift => Scheme::Semver,
+            PackageType::Golang => Scheme::Go,
+            PackageType::Composer => Scheme::Composer,
+            PackageType::Hex => Scheme::Hex,
+            PackageType::Nuget => Scheme::Nuget,
+            PackageType::PyPi => Scheme::Pep440,
+            PackageType::Maven => Scheme::Maven,
+            PackageType::RubyGems | PackageType::CocoaPods => Scheme::RubyGems,
+            PackageType::Conda | PackageType::Other(_) => Scheme::Generic,
+        }
+    }
+
+    /// Whether versions must be complete semantic versions.
+    fn is_strict_semver(self) -> bool {
+        matches!(
+            self,
+            Scheme::Npm | Scheme::Cargo | Scheme::Semver | Scheme::Hex
+        )
+    }
+
+    fn prerelease_policy(self) -> Prerelease {
+        match self {
+            Scheme::Npm | Scheme::Cargo | Scheme::Semver | Scheme::Hex => Prerelease::SameRelease,
+            Scheme::Pep440 => Prerelease::Explicit,
+            _ => Prerelease::Include,
+        }
+    }
+}
+
+fn allows_prerelease(scheme: Scheme, set: &[Constraint], version: &Parsed) -> bool {
+    if !version.is_prerelease() {
+        return true;
+    }
+
+    let bounds = set.iter().flat_map(Constraint::bounds);
+    match scheme.prerelease_policy() {
+        Prerelease::Include => true,
+        Prerelease::Explicit => bounds.into_iter().any(Parsed::is_prerelease),
+        Prerelease::SameRelease => match version {
+            Parsed::Semver(version) => bounds.into_iter().any(|bound| match bound {
+                Parsed::Semver(bound) => !bound.pre.is_empty() && bound.same_release(version),
+                _ => false,
+            }),
+            _ => true,
+        },
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Op {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+#[derive(Clone, Debug)]
+enum Constraint {
+    Compare(Op, Parsed),
+    /// Outside of `[lower, upper)`, for PEP 440's `!=1.2.*`.
+    Outside(Parsed, Parsed),
+    /// PEP 440 arbitrary string equality, `===`.
+    Identical(String),
+}
+
+impl Constraint {
+    fn matches(&self, version: &Parsed) -> bool {
+        match self {
+            Constraint::Compare(op, bound) => {
+                let ordering = match (version, bound) {
+                    // Local versions are ignored unless the bound has one.
+                    (Parsed::Pep440(version), Parsed::Pep440(bound)) if bound.local.is_none() => {
+                        if !pep440_exclusive_ok(*op, version, bound) {
+                            return false;
+                        }
+                        version.without_local().cmp(bound)
+                    }
+                    _ => version.cmp(bound),
+                };
+                match op {
+                    Op::Eq => ordering == Ordering::Equal,
+                    Op::Ne => ordering != Ordering::Equal,
+                    Op::Lt => ordering == Ordering::Less,
+                    Op::Le => ordering != Ordering::Greater,
+                    Op::Gt => ordering == Ordering::Greater,
+                    Op::Ge => ordering != Ordering::Less,
+                }
+            }
+            Constraint::Outside(lower, upper) => version < lower || version >= upper,
+            Constraint::Identical(raw) => match version {
+                Parsed::Pep440(version) => version.raw.eq_ignore_ascii_case(raw),
+                _ => false,
+            },
+        }
+    }
+
+    fn bounds(&self) -> Vec<&Parsed> {
+        match self {
+            Constraint::Compare(_, bound) => vec![bound],
+            Constraint::Outside(lower, upper) => vec![lower, upper],
+            Constraint::Identical(_) => Vec::new(),
+        }
+    }
+}
+
+/// PEP 440 excludes pre-releases of `V` from `<V` and post-releases of `V`
+/// from `>V`, unless `V` itself is one.
+fn pep440_exclusive_ok(op: Op, version: &Pep440, bound: &Pep440) -> bool {
+    let same_release =
+        version.epoch == bound.epoch && trim_zeros(&version.release) == trim_zeros(&bound.release);
+    match op {
+        Op::Lt => !(same_release && version.is_prerelease() && !bound.is_prerelease()),
+        Op::Gt => !(same_release && version.post.is_some() && bound.post.is_none()),
+        _ => true,
+    }
+}
+
+#[derive(Clone, Debug)]
+enum Parsed {
+    Semver(Semver),
+    Pep440(Pep440),
+    Maven(Vec<MavenItem>),
+    Gem(GemVersion),
+}
+
+impl Parsed {
+    fn parse(scheme: Scheme, version: &str) -> Result<Self, &'static str> {
+        match scheme {
+            Scheme::Pep440 => Pep440::parse(version).map(Parsed::Pep440),
+            Scheme::Maven => parse_maven(version).map(Parsed::Maven),
+            Scheme::RubyGems => GemVersion::parse(version, true).map(Parsed::Gem),
+            Scheme::Generic => GemVersion::parse(version, false).map(Parsed::Gem),
+            _ => Semver::parse(scheme, version).map(Parsed::Semver),
+        }
+    }
+
+    fn is_prerelease(&self) -> bool {
+        match self {
+            Parsed::Semver(version) => !version.pre.is_empty(),
+            Parsed::Pep440(version) => version.is_prerelease(),
+            Parsed::Maven(items) => maven_is_prerelease(items),
+            Parsed::Gem(version) => version.is_prerelease(),
+        }
+    }
+}
+
+impl PartialEq for Parsed {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Parsed {}
+
+impl PartialOrd for Parsed {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Parsed {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (Parsed::Semver(a), Parsed::Semver(b)) => a.cmp(b),
+            (Parsed::Pep440(a), Parsed::Pep440(b)) => a.cmp(b),
+            (Parsed::Maven(a), Parsed::Maven(b)) => compare_maven_lists(a, b),
+            (Parsed::Gem(a), Parsed::Gem(b)) => a.cmp(b),
+            // Versions of one ecosystem always share a representation.
+            _ => Ordering::Equal,
+        }
+    }
+}
+
+fn trim_zeros(release: &[u64]) -> &[u64] {
+    let len = release.iter().rposition(|&n| n != 0).map_or(0, |i| i + 1);
+    &release[..len]
+}
+
+// Semantic versioning
+
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Debug)]
+enum Identifier {
+    Numeric(u64),
+    Alpha(String),
+}
+
+impl Identifier {
+    fn parse(identifier: &str, lowercase: bool) -> Result<Self, &'static str> {
+        if identifier.is_empty() {
+            return Err("empty pre-release identifier");
+        }
+        if !identifier
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-')
+        {
+            return Err("invalid pre-release identifier");
+        }
+
+        match identifier.parse() {
+            Ok(number) => Ok(Identifier::Numeric(number)),
+            Err(_) if lowercase => Ok(Identifier::Alpha(identifier.to_ascii_lowercase())),
+            Err(_) => Ok(Identifier::Alpha(identifier.into())),
+        }
+    }
+}
+
+/// A semantic version, with build metadata dropped.
+///
+/// npm, Cargo, Pub, Swift and Hex require exactly `major.minor.patch`
+/// without leading zeros. The other schemes accept one to four release
+/// components, missing components compare as zero.
+#[derive(Clone, Debug)]
+struct Semver {
+    release: Vec<u64>,
+    pre: Vec<Identifier>,
+}
+
+impl Semver {
+    fn parse(scheme: Scheme, version: &str) -> Result<Self, &'static str> {
+        let strict = scheme.is_strict_semver();
+        let lowercase = scheme == Scheme::Nuget;
+
+        let version = version.trim();
+        let version = version.strip_prefix(['v', 'V', '=']).unwrap_or(version);
+        let version = version
+            .split_once('+')
+            .map_or(version, |(version, _build)| version);
+        let (release, pre) = match version.split_once('-') {
+            Some((release, pre)) => (release, Some(pre)),
+            None => (version, None),
+        };
+
+        let release = release
+            .split('.')
+            .map(|n| match n.bytes().all(|b| b.is_ascii_digit()) {
+                _ if strict && n.len() > 1 && n.starts_with('0') => {
+                    Err("version component has a leading zero")
+                }
+                true => n.parse().map_err(|_| "version component is out of range"),
+                false => Err("version component is not a number"),
+            })
+            .collect::<Result<Vec<u64>, _>>()?;
+        let components = if strict { 3..=3 } else { 1..=4 };
+        if !components.contains(&release.len()) {
+            return Err("expected `major.minor.patch`");
+        }
+
+        let pre = match pre {
+            Some(pre) => pre
+                .split('.')
+                .map(
+                    |identifier| match Identifier::parse(identifier, lowercase)? {
+                        Identifier::Numeric(_)
+                            if strict && identifier.len() > 1 && identifier.starts_with('0') =>
+                        {
+                            Err("pre-release identifier has a leading zero")
+                        }
+                        identifier => Ok(identifier),
+                    },
+                )
+                .collect::<Result<_, _>>()?,
+            None => Vec::new(),
+        };
+
+        Ok(Self { release, pre })
+    }
+
+    fn new(major: u64, minor: u64, patch: u64) -> Self {
+        Self {
+            release: vec![major, minor, patch],
+            pre: Vec::new(),
+        }
+    }
+
+    fn same_release(&self, other: &Semver) -> bool {
+        trim_zeros(&self.release) == trim_zeros(&other.release)
+    }
+}
+
+impl PartialEq for Semver {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Semver {}
+
+impl PartialOrd for Semver {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Semver {
+    fn cmp(&self, other: &Self) -> Ordering {
+        trim_zeros(&self.release)
+            .cmp(trim_zeros(&other.release))
+            .then_with(|| match (self.pre.is_empty(), other.pre.is_empty()) {
+                (true, true) => Ordering::Equal,
+                (true, false) => Ordering::Greater,
+                (false, true) => Ordering::Less,
+                (false, false) => self.pre.cmp(&other.pre),
+            })
+    }
+}
+
+/// A version in a range which may have wildcard components, like `1.x`.
+struct PartialVersion {
+    major: Option<u64>,
+    minor: Option<u64>,
+    patch: Option<u64>,
+    pre: Vec<Identifier>,
+}
+
+impl PartialVersion {
+    fn parse(version: &str) -> Result<Self, &'static str> {
+        let version = version.trim();
+        let version = version.strip_prefix(['v', 'V']).unwrap_or(version);
+        let version = version
+            .split_once('+')
+            .map_or(version, |(version, _build)| version);
+        let (release, pre) = match version.split_once('-') {
+            Some((release, pre)) => (release, Some(pre)),
+            None => (version, None),
+        };
+
+        let mut components = [None; 3];
+        let mut parts = release.split('.');
+        for component in components.iter_mut() {
+            match parts.next() {
+                None | Some("x" | "X" | "*") => break,
+                Some(n) => *component = Some(n.parse().map_err(|_| "invalid version")?),
+            }
+        }
+        // Anything after a wildcard must be a wildcard too.
+        if parts.any(|part| !matches!(part, "x" | "X" | "*")) {
+            return Err("invalid version");
+        }
+
+        let pre = match pre {
+            Some(pre) => pre
+                .split('.')
+                .map(|identifier| Identifier::parse(identifier, false))
+                .collect::<Result<_, _>>()?,
+            None => Vec::new(),
+        };
+
+        let [major, minor, patch] = components;
+        Ok(Self {
+            major,
+            minor,
+            patch,
+            pre,
+        })
+    }
+
+    fn is_complete(&self) -> bool {
+        self.patch.is_some()
+    }
+
+    /// The lowest version matching this partial version.
+    fn lower(&self) -> Parsed {
+        Parsed::Semver(Semver {
+            release: vec![
+                self.major.unwrap_or(0),
+                self.minor.unwrap_or(0),
+                self.patch.unwrap_or(0),
+            ],
+            pre: self.pre.clone(),
+        })
+    }
+
+    /// The first version above this partial version, like `1.3.0` for `1.2`.
+    fn next(&self) -> Parsed {
+        let major = self.major.unwrap_or(0);
+        Parsed::Semver(match (self.minor, self.patch) {
+            (None, _) => Semver::new(major + 1, 0, 0),
+            (Some(minor), None) => Semver::new(major, minor + 1, 0),
+            (Some(minor), Some(patch)) => Semver::new(major, minor, patch + 1),
+        })
+    }
+
+    /// Upper bound of `^version`.
+    fn caret_upper(&self) -> Parsed {
+        let major = self.major.unwrap_or(0);
+        Parsed::Semver(match (self.minor, self.patch) {
+            (Some(0), Some(patch)) if major == 0 => Semver::new(0, 0, patch + 1),
+            (Some(minor), _) if major == 0 => Semver::new(0, minor + 1, 0),
+            _ => Semver::new(major + 1, 0, 0),
+        })
+    }
+
+    /// Upper bound of `~version`, or of the pessimistic `~>` operator which
+    /// lets the last given component increase.
+    fn tilde_upper(&self, pessimistic: bool) -> Parsed {
+        let major = self.major.unwrap_or(0);
+        Parsed::Semver(match (self.minor, self.patch) {
+            (Some(_), None) if pessimistic => Semver::new(major + 1, 0, 0),
+            (Some(minor), _) => Semver::new(major, minor + 1, 0),
+            (None, _) => Semver::new(major + 1, 0, 0),
+        })
+    }
+}
+
+const SEMVER_OPERATORS: &[&str] = &["~>", "==", "!=", ">=", "<=", "^", "~", ">", "<", "="];
+
+/// Parse ranges for npm and the ecosystems with similar syntax.
+fn parse_semver_range(scheme: Scheme, range: &str) -> Result<Vec<Vec<Constraint>>, &'static str> {
+    // Hex uses `and` and `or` instead of symbols.
+    let range = if scheme == Scheme::Hex {
+        range.replace(" or ", "||").replace(" and ", ",")
+    } else {
+        range.to_owned()
+    };
+
+    // Composer also accepts a single `|` between alternatives.
+    let alternatives: Vec<&str> = match scheme {
+        Scheme::Composer => range.split('|').filter(|set| !set.is_empty()).collect(),
+        _ => range.split("||").collect(),
+    };
+
+    let mut sets = Vec::new();
+    for set in alternatives.into_iter().map(str::trim) {
+        if matches!(set, "" | "*" | "x" | "X" | "any") {
+            return Ok(Vec::new());
+        }
+
+        let mut constraints = Vec::new();
+        if let Some((lower, upper)) = set.split_once(" - ") {
+            let lower = PartialVersion::parse(lower)?;
+            let upper = PartialVersion::parse(upper)?;
+            constraints.push(Constraint::Compare(Op::Ge, lower.lower()));
+            constraints.push(match upper.is_complete() {
+                true => Constraint::Compare(Op::Le, upper.lower()),
+                false => Constraint::Compare(Op::Lt, upper.next()),
+            });
+            sets.push(constraints);
+            continue;
+        }
+
+        let mut tokens = set
+            .split(|c: char| c.is_whitespace() || c == ',')
+            .filter(|t| !t.is_empty());
+        while let Some(token) = tokens.next() {
+            let operator = SEMVER_OPERATORS
+                .iter()
+                .find(|op| token.starts_with(**op))
+                .copied()
+                .unwrap_or("");
+            let mut version = &token[operator.len()..];
+            // Operators may be separated from their version by whitespace.
+            if version.is_empty() {
+                version = tokens.next().ok_or("operator without version")?;
+            }
+            semver_constraints(
+                scheme,
+                operator,
+                &PartialVersion::parse(version)?,
+                &mut constraints,
+            );
+        }
+        sets.push(constraints);
+    }
+
+    Ok(sets)
+}
+
+fn semver_constraints(
+    scheme: Scheme,
+    operator: &str,
+    version: &PartialVersion,
+    constraints: &mut Vec<Constraint>,
+) {
+    // Nothing is below `0.0.0-0`, which is how unsatisfiable ranges are
+    // expressed.
+    let never = || {
+        Constraint::Compare(
+            Op::Lt,
+            Parsed::Semver(Semver {
+                release: vec![0, 0, 0],
+                pre: vec![Identifier::Numeric(0)],
+            }),
+        )
+    };
+
+    let operator = match operator {
+        // Cargo treats bare versions as caret requirements.
+        "" if scheme == Scheme::Cargo => "^",
+        "==" => "=",
+        // The pessimistic operator of Hex, which npm reads as tilde.
+        "~>" if scheme != Scheme::Hex => "~",
+        operator => operator,
+    };
+
+    if version.major.is_none() {
+        if matches!(operator, "<" | ">" | "!=") {
+            constraints.push(never());
+        }
+        return;
+    }
+
+    let pessimistic = matches!(scheme, Scheme::Composer | Scheme::Hex);
+    let (lower, upper) = match operator {
+        "^" => (Some(Op::Ge), Some(version.caret_upper())),
+        "~" | "~>" => (
+            Some(Op::Ge),
+            Some(version.tilde_upper(pessimistic || operator == "~>")),
+        ),
+        "" | "=" if version.is_complete() => {
+            constraints.push(Constraint::Compare(Op::Eq, version.lower()));
+            return;
+        }
+        "" | "=" => (Some(Op::Ge), Some(version.next())),
+        "!=" => {
+            constraints.push(Constraint::Compare(Op::Ne, version.lower()));
+            return;
+        }
+        ">=" => (Some(Op::Ge), None),
+        ">" if version.is_complete() => (Some(Op::Gt), None),
+        ">" => {
+            constraints.push(Constraint::Compare(Op::Ge, version.next()));
+            return;
+        }
+        "<" => (None, Some(version.lower())),
+        "<=" if version.is_complete() => {
+            constraints.push(Constraint::Compare(Op::Le, version.lower()));
+            return;
+        }
+        "<=" => (None, Some(version.next())),
+        _ => unreachable!("unknown operator {}", operator),
+    };
+
+    if let Some(op) = lower {
+        constraints.push(Constraint::Compare(op, version.lower()));
+    }
+    if let Some(upper) = upper {
+        constraints.push(Constraint::Compare(Op::Lt, upper));
+    }
+}
+
+// PEP 440
+
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Debug)]
+enum LocalSegment {
+    Alpha(String),
+    Numeric(u64),
+}
+
+#[derive(Clone, Debug)]
+struct Pep440 {
+    raw: String,
+    epoch: u64,
+    release: Vec<u64>,
+    /// Pre-release phase (`a`, `b` or `rc`) and number.
+    pre: Option<(u8, u64)>,
+    post: Option<u64>,
+    dev: Option<u64>,
+    local: Option<Vec<LocalSegment>>,
+}
+
+impl Pep440 {
+    fn parse(version: &str) -> Result<Self, &'static str> {
+        let raw = version.trim();
+        let lower = raw.to_ascii_lowercase();
+        let mut rest = lower.strip_prefix('v').unwrap_or(&lower);
+
+        let (public, local) = match rest.split_once('+') {
+            Some((public, local)) => (public, Some(local)),
+            None => (rest, None),
+        };
+        rest = public;
+
+        let epoch = match rest.split_once('!') {
+            Some((epoch, release)) => {
+                rest = release;
+                epoch.parse().map_err(|_| "invalid epoch")?
+            }
+            None => 0,
+        };
+
+        let release_len = rest
+            .find(|c: char| !c.is_ascii_digit() && c != '.')
+            .unwrap_or(rest.len());
+        let (release, mut rest) = rest.split_at(release_len);
+        let release = release.trim_end_matches('.');
+        if release.is_empty() || release.contains("..") || release.starts_with('.') {
+            return Err("invalid release segment");
+        }
+        let release = release
+            .split('.')
+            .map(|n| n.parse().map_err(|_| "invalid release segment"))
+            .collect::<Result<Vec<u64>, _>>()?;
+
+        let pre = take_pep440_label(
+            &mut rest,
+            &[
+                ("alpha", 0),
+                ("a", 0),
+                ("beta", 1),
+                ("b", 1),
+                ("preview", 2),
+                ("pre", 2),
+                ("rc", 2),
+                ("c", 2),
+            ],
+        );
+
+        let post = if let Some(number) = rest.strip_prefix('-').and_then(|r| {
+            let len = r.find(|c: char| !c.is_ascii_digit()).unwrap_or(r.len());
+            (len > 0).then(|| (&r[len..], r[..len].parse::<u64>()))
+        }) {
+            rest = number.0;
+            Some(number.1.map_err(|_| "invalid post-release")?)
+        } else {
+            take_pep440_label(&mut rest, &[("post", 0), ("rev", 0), ("r", 0)]).map(|(_, n)| n)
+        };
+
+        let dev = take_pep440_label(&mut rest, &[("dev", 0)]).map(|(_, n)| n);
+
+        if !rest.is_empty() {
+            return Err("invalid PEP 440 version");
+        }
+
+        let local = match local {
+            Some(local) => {
+                let segments = local
+                    .split(['.', '-', '_'])
+                    .map(|segment| match segment.parse() {
+                        Ok(number) => Ok(LocalSegment::Numeric(number)),
+                        Err(_)
+                            if !segment.is_empty()
+                                && segment.chars().all(|c| c.is_ascii_alphanumeric()) =>
+                        {
+                            Ok(LocalSegment::Alpha(segment.into()))
+                        }
+                        Err(_) => Err("invalid local version"),
+                    })
+                    .collect::<Result<_, _>>()?;
+                Some(segments)
+            }
+            None => None,
+        };
+
+        Ok(Self {
+            raw: raw.into(),
+            epoch,
+            release,
+            pre,
+            post,
+            dev,
+            local,
+        })
+    }
+
+    fn is_prerelease(&self) -> bool {
+        self.pre.is_some() || self.dev.is_some()
+    }
+
+    fn without_local(&self) -> Self {
+        Self {
+            local: None,
+            ..self.clone()
+        }
+    }
+
+    /// The sort key from the `packaging` reference implementation.
+    #[allow(clippy::type_complexity)]
+    fn key(
+        &self,
+    ) -> (
+        u64,
+        &[u64],
+        (i8, u8, u64),
+        Option<u64>,
+        (bool, u64),
+        Option<&Vec<LocalSegment>>,
+    ) {
+        // Development releases without a pre-release phase sort before all
+        // pre-releases, final releases after them.
+        let pre = match (self.pre, self.post, self.dev) {
+            (None, None, Some(_)) => (-1, 0, 0),
+            (None, _, _) => (1, 0, 0),
+            (Some((phase, number)), _, _) => (0, phase, number),
+        };
+        let dev = (self.dev.is_none(), self.dev.unwrap_or(0));
+        (
+            self.epoch,
+            trim_zeros(&self.release),
+            pre,
+            self.post,
+            dev,
+            self.local.as_ref(),
+        )
+    }
+}
+
+/// Take a label like `rc1`, `.post2` or `-dev` from the start of a version,
+/// with an optional separator before the label and its number.
+fn take_pep440_label(rest: &mut &str, labels: &[(&str, u8)]) -> Option<(u8, u64)> {
+    let unseparated = rest.trim_start_matches(['.', '-', '_']);
+    let (label, phase) = labels
+        .iter()
+        .find(|(label, _)| unseparated.starts_with(label))?;
+    let after = unseparated[label.len()..].trim_start_matches(['.', '-', '_']);
+    let len = after
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(after.len());
+    let number = after[..len].parse().unwrap_or(0);
+    *rest = if len > 0 {
+        &after[len..]
+    } else {
+        &unseparated[label.len()..]
+    };
+    Some((*phase, number))
+}
+
+impl PartialEq for Pep440 {
+    fn eq(&self, other: &Self) -> bool {
+        self.key() == other.key()
+    }
+}
+
+impl Eq for Pep440 {}
+
+impl PartialOrd for Pep440 {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Pep440 {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.key().cmp(&other.key())
+    }
+}
+
+fn parse_pep440_range(range: &str) -> Result<Vec<Vec<Constraint>>, &'static str> {
+    const OPERATORS: &[(&str, Op)] = &[
+        ("===", Op::Eq),
+        ("~=", Op::Ge),
+        ("==", Op::Eq),
+        ("!=", Op::Ne),
+        ("<=", Op::Le),
+        (">=", Op::Ge),
+        ("<", Op::Lt),
+        (">", Op::Gt),
+    ];
+
+    let mut constraints = Vec::new();
+    for specifier in range.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+        let (operator, op) = OPERATORS
+            .iter()
+            .find(|(operator, _)| specifier.starts_with(operator))
+            .ok_or("missing comparison operator")?;
+        let version = specifier[operator.len()..].trim();
+
+        if *operator == "===" {
+            constraints.push(Constraint::Identical(version.into()));
+            continue;
+        }
+
+        // `==1.2.*` matches all versions with that release prefix.
+        if let Some(prefix) = version.strip_suffix(".*") {
+            let prefix = Pep440::parse(prefix)?;
+            let (lower, upper) = pep440_prefix_bounds(&prefix);
+            match op {
+                Op::Eq => {
+                    constraints.push(Constraint::Compare(Op::Ge, lower));
+                    constraints.push(Constraint::Compare(Op::Lt, upper));
+                }
+                Op::Ne => constraints.push(Constraint::Outside(lower, upper)),
+                _ => return Err("wildcards are only allowed with `==` and `!=`"),
+            }
+            continue;
+        }
+
+        let parsed = Pep440::parse(version)?;
+        if *operator == "~=" {
+            // `~=1.4.5` is `>=1.4.5, ==1.4.*`.
+            if parsed.release.len() < 2 {
+                return Err("`~=` requires at least two release components");
+            }
+            let prefix = Pep440 {
+                release: parsed.release[..parsed.release.len() - 1].to_vec(),
+                ..parsed.clone()
+            };
+            constraints.push(Constraint::Compare(Op::Lt, pep440_prefix_bounds(&prefix).1));
+        }
+        constraints.push(Constraint::Compare(*op, Parsed::Pep440(parsed)));
+    }
+
+    Ok(if constraints.is_empty() {
+        Vec::new()
+    } else {
+        vec![constraints]
+    })
+}
+
+/// The lowest version with a release prefix, and the lowest version after
+/// all versions with that prefix.
+fn pep440_prefix_bounds(prefix: &Pep440) -> (Parsed, Parsed) {
+    let bound = |release: Vec<u64>| {
+        Parsed::Pep440(Pep440 {
+            raw: String::new(),
+            epoch: prefix.epoch,
+            release,
+            pre: None,
+            post: None,
+            dev: Some(0),
+            local: None,
+        })
+    };
+
+    let mut upper = prefix.release.clone();
+    if let Some(last) = upper.last_mut() {
+        *last += 1;
+    }
+    (bound(prefix.release.clone()), bound(upper))
+}
+
+// Maven
+
+/// An item of Maven's `ComparableVersion`.
+#[derive(Clone, Debug)]
+enum MavenItem {
+    /// Digits without leading zeros, compared by length and then lexically
+    /// to avoid overflows.
+    Int(String),
+    /// A qualifier, already mapped to its comparable form.
+    Str(String),
+    List(Vec<MavenItem>),
+}
+
+const MAVEN_QUALIFIERS: &[&str] = &["alpha", "beta", "milestone", "rc", "snapshot", "", "sp"];
+const MAVEN_RELEASE_INDEX: usize = 5;
+
+impl MavenItem {
+    fn int(digits: &str) -> Self {
+        let digits = digits.trim_start_matches('0');
+        MavenItem::Int(if digits.is_empty() { "0" } else { digits }.to_owned())
+    }
+
+    fn string(qualifier: &str, followed_by_digit: bool) -> Self {
+        let qualifier = qualifier.to_ascii_lowercase();
+        let qualifier = match qualifier.as_str() {
+            "a" if followed_by_digit => "alpha",
+            "b" if followed_by_digit => "beta",
+            "m" if followed_by_digit => "milestone",
+            "ga" | "final" | "release" => "",
+            "cr" => "rc",
+            qualifier => qualifier,
+        };
+
+        // Known qualifiers sort by their index, unknown ones after them.
+        let comparable = match MAVEN_QUALIFIERS.iter().position(|q| *q == qualifier) {
+            Some(index) => index.to_string(),
+            None => format!("{}-{qualifier}", MAVEN_QUALIFIERS.len()),
+        };
+        MavenItem::Str(comparable)
+    }
+
+    fn is_null(&self) -> bool {
+        match self {
+            MavenItem::Int(digits) => digits == "0",
+            MavenItem::Str(comparable) => *comparable == MAVEN_RELEASE_INDEX.to_string(),
+            MavenItem::List(items) => items.is_empty(),
+        }
+    }
+
+    /// Compare against an item, or against a missing item if `None`.
+    fn compare(&self, other: Option<&MavenItem>) -> Ordering {
+        match (self, other) {
+            (MavenItem::Int(digits), None) => match digits.as_str() {
+                "0" => Ordering::Equal,
+                _ => Ordering::Greater,
+            },
+            (MavenItem::Str(comparable), None) => comparable
+                .as_str()
+                .cmp(MAVEN_RELEASE_INDEX.to_string().as_str()),
+            (MavenItem::List(items), None) => match items.first() {
+                Some(first) => first.compare(None),
+                None => Ordering::Equal,
+            },
+            (MavenItem::Int(a), Some(MavenItem::Int(b))) => {
+                a.len().cmp(&b.len()).then_with(|| a.cmp(b))
+            }
+            (MavenItem::Int(_), Some(_)) => Ordering::Greater,
+            (MavenItem::Str(_), Some(MavenItem::Int(_))) => Ordering::Less,
+            (MavenItem::Str(a), Some(MavenItem::Str(b))) => a.cmp(b),
+            (MavenItem::Str(_), Some(MavenItem::List(_))) => Ordering::Less,
+            (MavenItem::List(_), Some(MavenItem::Int(_))) => Ordering::Less,
+            (MavenItem::List(_), Some(MavenItem::Str(_))) => Ordering::Greater,
+            (MavenItem::List(a), Some(MavenItem::List(b))) => compare_maven_lists(a, b),
+        }
+    }
+}
+
+fn compare_maven_lists(a: &[MavenItem], b: &[MavenItem]) -> Ordering {
+    for i in 0..a.len().max(b.len()) {
+        let ordering = match (a.get(i), b.get(i)) {
+            (Some(a), b) => a.compare(b),
+            (None, Some(b)) => b.compare(None).reverse(),
+            (None, None) => Ordering::Equal,
+        };
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+    Ordering::Equal
+}
+
+/// Remove trailing null items, like the `0` in `1.0`. Null items before a
+/// trailing sub-list are removed too, so `1.0-alpha` equals `1-alpha`.
+fn normalize_maven_list(items: &mut Vec<MavenItem>) {
+    let mut i = items.len();
+    while i > 0 {
+        i -= 1;
+        if items[i].is_null() {
+            items.remove(i);
+        } else if !matches!(items[i], MavenItem::List(_)) {
+            break;
+        }
+    }
+}
+
+/// Parse a version like Maven's `ComparableVersion`.
+///
+/// `.` separates items, while `-` and transitions between digits and letters
+/// start a nested list.
+fn parse_maven(version: &str) -> Result<Vec<MavenItem>, &'static str> {
+    let version = version.trim().to_ascii_lowercase();
+    if version.is_empty() {
+        return Err("version is empty");
+    }
+
+    let chars: Vec<char> = version.chars().collect();
+    let mut stack: Vec<Vec<MavenItem>> = vec![Vec::new()];
+    let mut start = 0;
+    let mut is_digit = false;
+
+    let item = |start: usize, end: usize, is_digit: bool| {
+        let text: String = chars[start..end].iter().collect();
+        if is_digit {
+            MavenItem::int(&text)
+        } else {
+            let followed_by_digit = chars.get(end).is_some_and(char::is_ascii_digit);
+            MavenItem::string(&text, followed_by_digit)
+        }
+    };
+
+    for (i, &c) in chars.iter().enumerate() {
+        let current = stack.last_mut().unwrap();
+        match c {
+            '.' | '-' => {
+                if i == start {
+                    current.push(MavenItem::Int(String::from("0")));
+                } else {
+                    current.push(item(start, i, is_digit));
+                }
+                start = i + 1;
+                if c == '-' {
+                    stack.push(Vec::new());
+                }
+            }
+            c if c.is_ascii_digit() => {
+                if !is_digit && i > start {
+                    current.push(item(start, i, false));
+                    start = i;
+                    stack.push(Vec::new());
+                }
+                is_digit = true;
+            }
+            _ => {
+                if is_digit && i > start {
+                    current.push(item(start, i, true));
+                    start = i;
+                    stack.push(Vec::new());
+                }
+                is_digit = false;
+            }
+        }
+    }
+
+    if chars.len() > start {
+        stack
+            .last_mut()
+            .unwrap()
+            .push(item(start, chars.len(), is_digit));
+    }
+
+    while stack.len() > 1 {
+        let mut list = stack.pop().unwrap();
+        normalize_maven_list(&mut list);
+        stack.last_mut().unwrap().push(MavenItem::List(list));
+    }
+
+    let mut items = stack.pop().unwrap();
+    normalize_maven_list(&mut items);
+    Ok(items)
+}
+
+fn maven_is_prerelease(items: &[MavenItem]) -> bool {
+    items.iter().any(|item| match item {
+        MavenItem::Str(comparable) => {
+            comparable.as_str() < MAVEN_RELEASE_INDEX.to_string().as_str()
+        }
+        MavenItem::List(items) => maven_is_prerelease(items),
+        MavenItem::Int(_) => false,
+    })
+}
+
+/// Parse Maven and NuGet interval ranges like `[1.0,2.0)` or `(,1.5],[2.0,)`.
+///
+/// For Maven a bare version only matches itself, for NuGet it is a minimum
+/// version. NuGet also supports floating versions like `1.2.*`.
+fn parse_interval_range(scheme: Scheme, range: &str) -> Result<Vec<Vec<Constraint>>, &'static str> {
+    let range = range.trim();
+    let parse = |version: &str| Parsed::parse(scheme, version.trim());
+
+    if !range.starts_with(['[', '(']) {
+        if scheme == Scheme::Maven {
+            return Ok(vec![vec![Constraint::Compare(Op::Eq, parse(range)?)]]);
+        }
+        return nuget_floating(range);
+    }
+
+    let mut sets = Vec::new();
+    let mut rest = range;
+    while !rest.is_empty() {
+        let lower_inclusive = match rest.chars().next() {
+            Some('[') => true,
+            Some('(') => false,
+            _ => return Err("expected `[` or `(`"),
+        };
+        let end = rest.find([']', ')']).ok_or("unterminated interval")?;
+        let upper_inclusive = rest[end..].starts_with(']');
+        let inner = &rest[1..end];
+        rest = rest[end + 1..]
+            .trim_start()
+            .trim_start_matches(',')
+            .trim_start();
+
+        let mut constraints = Vec::new();
+        match inner.split_once(',') {
+            None if lower_inclusive && upper_inclusive => {
+                constraints.push(Constraint::Compare(Op::Eq, parse(inner)?));
+            }
+            None => return Err("single version intervals must be inclusive"),
+            Some((lower, upper)) => {
+                if !lower.trim().is_empty() {
+                    let op = if lower_inclusive { Op::Ge } else { Op::Gt };
+                    constraints.push(Constraint::Compare(op, parse(lower)?));
+                }
+                if !upper.trim().is_empty() {
+                    let op = if upper_inclusive { Op::Le } else { Op::Lt };
+                    constraints.push(Constraint::Compare(op, parse(upper)?));
+                }
+            }
+        }
+        sets.push(constraints);
+    }
+
+    Ok(sets)
+}
+
+fn nuget_floating(range: &str) -> Result<Vec<Vec<Constraint>>, &'static str> {
+    if range == "*" {
+        return Ok(Vec::new());
+    }
+
+    let parse = |version: &str| Semver::parse(Scheme::Nuget, version).map(Parsed::Semver);
+
+    // `1.0.0-*` floats over the pre-releases of a version.
+    if let Some(release) = range.strip_suffix("-*") {
+        let lowest = format!("{release}-0");
+        return Ok(vec![vec![Constraint::Compare(Op::Ge, parse(&lowest)?)]]);
+    }
+
+    // `1.2.*` floats over the versions with that prefix.
+    if let Some(prefix) = range.strip_suffix(".*") {
+        let lower = Semver::parse(Scheme::Nuget, prefix)?;
+        let mut upper = lower.release.clone();
+        if let Some(last) = upper.last_mut() {
+            *last += 1;
+        }
+        let upper = Semver {
+            release: upper,
+            pre: Vec::new(),
+        };
+        return Ok(vec![vec![
+            Constraint::Compare(Op::Ge, Parsed::Semver(lower)),
+            Constraint::Compare(Op::Lt, Parsed::Semver(upper)),
+        ]]);
+    }
+
+    Ok(vec![vec![Constraint::Compare(Op::Ge, parse(range)?)]])
+}
+
+// RubyGems
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+enum GemSegment {
+    Numeric(u64),
+    Alpha(String),
+}
+
+/// A `Gem::Version`, also used as lenient fallback for other ecosystems.
+#[derive(Clone, Debug)]
+struct GemVersion {
+    segments: Vec<GemSegment>,
+}
+
+impl GemVersion {
+    fn parse(version: &str, strict: bool) -> Result<Self, &'static str> {
+        let version = version.trim();
+        if strict {
+            let valid = version.starts_with(|c: char| c.is_ascii_digit())
+                && version
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '-');
+            if !valid {
+                return Err("invalid gem version");
+            }
+        }
+
+        // `1.0-beta` is `1.0.pre.beta`.
+        let version = version.replace('-', ".pre.");
+        let mut segments = Vec::new();
+        let mut chars = version.char_indices().peekable();
+        while let Some((start, c)) = chars.next() {
+            if !c.is_ascii_alphanumeric() {
+                continue;
+            }
+            let digit = c.is_ascii_digit();
+            let mut end = start + 1;
+            while let Some((i, c)) = chars.peek() {
+                if c.is_ascii_alphanumeric() && c.is_ascii_digit() == digit {
+                    end = i + 1;
+                    chars.next();
+                } else {
+                    break;
+                }
+            }
+            let segment = &version[start..end];
+            segments.push(match digit {
+                true => GemSegment::Numeric(
+                    segment
+                        .parse()
+                        .map_err(|_| "version segment is out of range")?,
+                ),
+                false => GemSegment::Alpha(segment.to_owned()),
+            });
+        }
+
+        if segments.is_empty() {
+            return Err("version has no segments");
+        }
+        Ok(Self { segments })
+    }
+
+    fn is_prerelease(&self) -> bool {
+        self.segments
+            .iter()
+            .any(|segment| matches!(segment, GemSegment::Alpha(_)))
+    }
+
+    /// Segments with trailing zeros of the release and pre-release parts
+    /// removed.
+    fn canonical_segments(&self) -> Vec<&GemSegment> {
+        let split = self
+            .segments
+            .iter()
+            .position(|segment| matches!(segment, GemSegment::Alpha(_)))
+            .unwrap_or(self.segments.len());
+        let (release, pre) = self.segments.split_at(split);
+
+        let trim = |segments: &[GemSegment]| -> usize {
+            segments
+                .iter()
+                .rposition(|segment| *segment != GemSegment::Numeric(0))
+                .map_or(0, |i| i + 1)
+        };
+
+        let mut canonical: Vec<&GemSegment> = release[..trim(release)].iter().collect();
+        canonical.extend(&pre[..trim(pre)]);
+        canonical
+    }
+
+    /// The upper bound of the pessimistic operator, `~> 2.2.1` is `< 2.3`.
+    fn bump(&self) -> Self {
+        let mut segments: Vec<GemSegment> = self
+            .segments
+            .iter()
+            .take_while(|segment| matches!(segment, GemSegment::Numeric(_)))
+            .cloned()
+            .collect();
+        if segments.len() > 1 {
+            segments.pop();
+        }
+        if let Some(GemSegment::Numeric(last)) = segments.last_mut() {
+            *last += 1;
+        }
+        Self { segments }
+    }
+}
+
+impl PartialEq for GemVersion {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for GemVersion {}
+
+impl PartialOrd for GemVersion {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for GemVersion {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let zero = GemSegment::Numeric(0);
+        let (a, b) = (self.canonical_segments(), other.canonical_segments());
+        for i in 0..a.len().max(b.len()) {
+            let ordering = match (
+                a.get(i).copied().unwrap_or(&zero),
+                b.get(i).copied().unwrap_or(&zero),
+            ) {
+                (GemSegment::Numeric(a), GemSegment::Numeric(b)) => a.cmp(b),
+                (GemSegment::Alpha(a), GemSegment::Alpha(b)) => a.cmp(b),
+                (GemSegment::Alpha(_), GemSegment::Numeric(_)) => Ordering::Less,
+                (GemSegment::Numeric(_), GemSegment::Alpha(_)) => Ordering::Greater,
+            };
+            if ordering != Ordering::Equal {
+                return ordering;
+            }
+        }
+        Ordering::Equal
+    }
+}
+
+/// Parse `Gem::Requirement` syntax like `~> 2.2, >= 2.2.1`.
+///
+/// Conda and unknown ecosystems additionally accept `|` for alternatives,
+/// `==` for exact versions, and `1.2.*` or a bare `1.2` for all versions
+/// starting with `1.2`.
+fn parse_gem_range(scheme: Scheme, range: &str) -> Result<Vec<Vec<Constraint>>, &'static str> {
+    const OPERATORS: &[(&str, Option<Op>)] = &[
+        ("~>", None),
+        ("==", Some(Op::Eq)),
+        ("!=", Some(Op::Ne)),
+        (">=", Some(Op::Ge)),
+        ("<=", Some(Op::Le)),
+        (">", Some(Op::Gt)),
+        ("<", Some(Op::Lt)),
+        ("=", Some(Op::Eq)),
+    ];
+
+    let generic = scheme == Scheme::Generic;
+    let parse = |version: &str| GemVersion::parse(version, !generic);
+    let alternatives: Vec<&str> = if generic {
+        range.split('|').collect()
+    } else {
+        vec![range]
+    };
+
+    let mut sets = Vec::new();
+    for alternative in alternatives {
+        let mut constraints = Vec::new();
+        for requirement in alternative
+            .split(',')
+            .map(str::trim)
+            .filter(|r| !r.is_empty())
+        {
+            if requirement == "*" {
+                continue;
+            }
+
+            let (operator, op) = OPERATORS
+                .iter()
+                .find(|(operator, _)| requirement.starts_with(operator))
+                .map_or(("", Some(Op::Eq)), |(operator, op)| (*operator, *op));
+            let version = requirement[operator.len()..].trim();
+
+            // Conda's fuzzy matching, `1.2` and `1.2.*` both match `1.2.3`.
+            let prefix = version
+                .strip_suffix(".*")
+                .or_else(|| version.strip_suffix('*'));
+            if generic && (prefix.is_some() || matches!(operator, "" | "=")) {
+                let prefix = parse(prefix.unwrap_or(version))?;
+                let mut upper = prefix.clone();
+                if let Some(GemSegment::Numeric(last)) = upper.segments.last_mut() {
+                    *last += 1;
+                }
+                constraints.push(Constraint::Compare(Op::Ge, Parsed::Gem(prefix)));
+                constraints.push(Constraint::Compare(Op::Lt, Parsed::Gem(upper)));
+                continue;
+            }
+
+            let version = parse(version)?;
+            match op {
+                Some(op) => constraints.push(Constraint::Compare(op, Parsed::Gem(version))),
+                None => {
+                    let upper = version.bump();
+                    constraints.push(Constraint::Compare(Op::Ge, Parsed::Gem(version)));
+                    constraints.push(Constraint::Compare(Op::Lt, Parsed::Gem(upper)));
+                }
+            }
+        }
+
+        if constraints.is_empty() {
+            return Ok(Vec::new());
+        }
+        sets.push(constraints);
+    }
+
+    Ok(sets)
+}
@@ -0,0 +1,20 @@
+//! Helpers shared by the integration tests.
+
+#![allow(dead_code)]
+
+use std::fs;
+
+use phylum_types::types::job::JobStatusResponse;
+use phylum_types::types::package::PackageStatusExtended;
+
+/// Read a file from `tests/fixtures`.
+pub fn fixture(name: &str) -> String {
+    let path = format!("{}/tests/fixtures/{name}", env!("CARGO_MANIFEST_DIR"));
+    fs::read_to_string(path).unwrap()
+}
+
+/// The results of a finished job of an npm project, with issues in most
+/// risk domains and one ignored vulnerability.
+pub fn job() -> JobStatusResponse<PackageStatusExtended> {
+    serde_json::from_str(&fixture("job.json")).unwrap()
+}
@@ -0,0 +1,162 @@
+use phylum_types::cvss::v3::{AttackVector, CvssV3, Requirement, Scope};
+use phylum_types::cvss::v4::{CvssV4, ExploitMaturity};
+use phylum_types::cvss::CvssVector;
+use phylum_types::formats::osv::Advisory;
+use phylum_types::types::package::RiskLevel;
+
+fn v3(vector: &str) -> CvssV3 {
+    vector.parse().unwrap()
+}
+
+fn v4(vector: &str) -> CvssV4 {
+    vector.parse().unwrap()
+}
+
+#[test]
+fn v3_scores() {
+    let vector = v3("CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:H/A:H");
+    assert_eq!(vector.base_score(), 9.8);
+    assert_eq!(vector.temporal_score(), 9.8);
+    assert_eq!(vector.risk_level(), RiskLevel::Critical);
+
+    assert_eq!(
+        v3("CVSS:3.1/AV:N/AC:L/PR:N/UI:R/S:C/C:L/I:L/A:N").base_score(),
+        6.1
+    );
+    assert_eq!(
+        v3("CVSS:3.1/AV:L/AC:L/PR:L/UI:N/S:U/C:N/I:N/A:N").base_score(),
+        0.
+    );
+
+    let vector = v3("CVSS:3.0/AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:H/A:H/E:P/RL:O/RC:C");
+    assert_eq!(vector.minor_version, 0);
+    assert_eq!(vector.base_score(), 9.8);
+    assert_eq!(vector.temporal_score(), 8.8);
+    assert_eq!(vector.score(), 8.8);
+    assert_eq!(vector.risk_level(), RiskLevel::High);
+}
+
+#[test]
+fn v3_environmental_adjustments() {
+    let mut vector = v3("CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:H/A:H");
+    vector.modified_attack_vector = Some(AttackVector::Local);
+    assert_eq!(vector.environmental_score(), 8.4);
+    assert_eq!(vector.score(), 8.4);
+    assert_eq!(
+        vector.to_string(),
+        "CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:H/A:H/MAV:L"
+    );
+
+    vector.confidentiality_requirement = Some(Requirement::Low);
+    vector.integrity_requirement = Some(Requirement::Low);
+    vector.availability_requirement = Some(Requirement::Low);
+    assert_eq!(vector.environmental_score(), 6.6);
+    assert_eq!(vector.risk_level(), RiskLevel::Medium);
+
+    let vector = v3("CVSS:3.1/AV:N/AC:L/PR:L/UI:N/S:U/C:H/I:H/A:H/MS:C/MPR:X");
+    assert_eq!(vector.modified_scope, Some(Scope::Changed));
+    assert_eq!(vector.modified_privileges_required, None);
+    assert_eq!(vector.base_score(), 8.8);
+    assert_eq!(vector.environmental_score(), 10.);
+}
+
+#[test]
+fn v4_scores() {
+    let vector = v4("CVSS:4.0/AV:N/AC:L/AT:N/PR:N/UI:N/VC:H/VI:H/VA:H/SC:N/SI:N/SA:N");
+    assert_eq!(vector.score(), 9.3);
+    assert_eq!(vector.nomenclature(), "CVSS-B");
+    assert_eq!(vector.risk_level(), RiskLevel::Critical);
+
+    let mut unreported = vector.clone();
+    unreported.exploit_maturity = Some(ExploitMaturity::Unreported);
+    assert_eq!(unreported.score(), 8.1);
+    assert_eq!(unreported.base_score(), 9.3);
+    assert_eq!(unreported.nomenclature(), "CVSS-BT");
+
+    let local = v4("CVSS:4.0/AV:N/AC:L/AT:N/PR:N/UI:N/VC:H/VI:H/VA:H/SC:N/SI:N/SA:N/MAV:L");
+    assert_eq!(local.score(), 8.6);
+    assert_eq!(local.nomenclature(), "CVSS-BE");
+
+    let safety = v4("CVSS:4.0/AV:N/AC:L/AT:N/PR:N/UI:N/VC:L/VI:L/VA:N/SC:N/SI:N/SA:N/MSI:S");
+    assert_eq!(safety.score(), 8.9);
+
+    assert_eq!(
+        v4("CVSS:4.0/AV:L/AC:L/AT:P/PR:L/UI:A/VC:L/VI:N/VA:N/SC:N/SI:N/SA:N").score(),
+        1.
+    );
+    assert_eq!(
+        v4("CVSS:4.0/AV:N/AC:L/AT:N/PR:N/UI:N/VC:N/VI:N/VA:N/SC:N/SI:N/SA:N").risk_level(),
+        RiskLevel::Info
+    );
+}
+
+#[test]
+fn parse_and_format() {
+    for vector in [
+        "CVSS:3.1/AV:P/AC:H/PR:H/UI:R/S:C/C:N/I:L/A:H/E:F/RL:W/RC:R/CR:M/MAV:A/MC:L",
+        "CVSS:4.0/AV:A/AC:H/AT:P/PR:L/UI:P/VC:L/VI:N/VA:H/SC:H/SI:L/SA:N/E:P/CR:L/MSA:S/S:P/AU:Y/U:Amber",
+    ] {
+        let parsed: CvssVector = vector.parse().unwrap();
+        assert_eq!(parsed.to_string(), vector);
+
+        let json = serde_json::to_value(&parsed).unwrap();
+        assert_eq!(json, vector);
+        assert_eq!(serde_json::from_value::<CvssVector>(json).unwrap(), parsed);
+    }
+
+    // Metrics in any order, undefined metrics are dropped.
+    let vector = v3("CVSS:3.1/S:U/AV:N/AC:L/PR:N/UI:N/C:H/I:H/A:H/E:X");
+    assert_eq!(
+        vector.to_string(),
+        "CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:H/A:H"
+    );
+
+    for invalid in [
+        "",
+        "CVSS:2.0/AV:N/AC:L/Au:N/C:P/I:P/A:P",
+        "AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:H/A:H",
+        "CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:H",
+        "CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:H/A:H/A:L",
+        "CVSS:3.1/AV:Q/AC:L/PR:N/UI:N/S:U/C:H/I:H/A:H",
+        "CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:H/A:H/AT:N",
+        "CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:H/A:H/",
+        "CVSS:4.0/AV:N/AC:L/AT:N/PR:N/UI:N/VC:H/VI:H/VA:H/SC:N/SI:S/SA:N",
+    ] {
+        assert!(invalid.parse::<CvssVector>().is_err(), "{}", invalid);
+    }
+}
+
+#[test]
+fn risk_level_bands() {
+    for (score, risk_level) in [
+        (0., RiskLevel::Info),
+        (0.1, RiskLevel::Low),
+        (3.9, RiskLevel::Low),
+        (4., RiskLevel::Medium),
+        (6.9, RiskLevel::Medium),
+        (7., RiskLevel::High),
+        (8.9, RiskLevel::High),
+        (9., RiskLevel::Critical),
+        (10., RiskLevel::Critical),
+    ] {
+        assert_eq!(RiskLevel::from_cvss_score(score), risk_level, "{}", score);
+    }
+}
+
+#[test]
+fn osv_severity_from_vector() {
+    let advisory = Advisory::from_json(
+        r#"{
+            "id": "GHSA-0000-0000-0001",
+            "modified": "2023-01-01T00:00:00Z",
+            "severity": [
+                {"type": "CVSS_V4", "score": "CVSS:4.0/AV:N/AC:L/AT:N/PR:N/UI:N/VC:H/VI:H/VA:H/SC:N/SI:N/SA:N"},
+                {"type": "CVSS_V3", "score": "CVSS:3.1/AV:N/AC:L/PR:N/UI:R/S:C/C:L/I:L/A:N"}
+            ]
+        }"#,
+    )
+    .unwrap();
+    let vulnerability = advisory.vulnerability();
+    assert_eq!(vulnerability.base_severity, 6.1);
+    assert_eq!(vulnerability.risk_level, RiskLevel::Medium);
+}
@@ -0,0 +1,261 @@
+mod common;
+
+use phylum_types::formats::cyclonedx::Bom;
+use phylum_types::formats::osv::Advisory;
+use phylum_types::types::package::{
+    IgnoredReason, PackageDescriptor, PackageDescriptorAndLockfile, PackageType, RiskLevel,
+    Vulnerability,
+};
+use serde_json::Value;
+
+use common::{fixture, job};
+
+fn package(name: &str, version: &str, package_type: PackageType) -> PackageDescriptorAndLockfile {
+    PackageDescriptorAndLockfile::from(PackageDescriptor {
+        name: name.into(),
+        version: version.into(),
+        package_type,
+    })
+}
+
+/// Validate against the properties of the CycloneDX 1.5 schema which `Bom`
+/// writes.
+fn assert_matches_schema(bom: &Bom) {
+    let schema: Value =
+        serde_json::from_str(&fixture("cyclonedx/bom-1.5.subset.schema.json")).unwrap();
+    let schema = jsonschema::JSONSchema::compile(&schema).unwrap();
+
+    let json: Value = serde_json::from_str(&bom.to_json()).unwrap();
+    let errors: Vec<String> = match schema.validate(&json) {
+        Ok(()) => Vec::new(),
+        Err(errors) => errors.map(|error| error.to_string()).collect(),
+    };
+    assert!(errors.is_empty(), "{:?}", errors);
+}
+
+#[test]
+fn job_to_json() {
+    let bom = Bom::from_job(&job());
+    let json: serde_json::Value = serde_json::from_str(&bom.to_json()).unwrap();
+
+    assert_eq!(json["bomFormat"], "CycloneDX");
+    assert_eq!(json["specVersion"], "1.5");
+    assert_eq!(
+        json["serialNumber"],
+        "urn:uuid:59482a54-423b-448d-8325-f171c9dc336b"
+    );
+    assert_eq!(json["metadata"]["timestamp"], "2023-03-10T00:00:00Z");
+
+    let components = json["components"].as_array().unwrap();
+    assert_eq!(components.len(), 3);
+    assert_eq!(components[0]["purl"], "pkg:npm/event-stream@3.3.6");
+    assert_eq!(components[0]["licenses"][0]["license"]["id"], "MIT");
+
+    let vulnerabilities = json["vulnerabilities"].as_array().unwrap();
+    let ids: Vec<&str> = vulnerabilities
+        .iter()
+        .map(|vulnerability| vulnerability["id"].as_str().unwrap())
+        .collect();
+    assert_eq!(ids, ["CM0010", "CVE-2021-44906", "CVE-2020-7598"]);
+    assert_eq!(vulnerabilities[0]["ratings"][0]["severity"], "critical");
+    assert_eq!(
+        vulnerabilities[0]["affects"][0]["ref"],
+        "pkg:npm/event-stream@3.3.6"
+    );
+    assert_eq!(vulnerabilities[1]["analysis"]["state"], "not_affected");
+    assert!(vulnerabilities[2].get("analysis").is_none());
+}
+
+#[test]
+fn vulnerabilities_keep_analysis_per_component() {
+    // The same vulnerability, ignored for one package but not the other.
+    let mut job = job();
+    let ignored = job.packages[1]
+        .issues
+        .iter()
+        .find(|issue| issue.issue.tag.as_deref() == Some("CVE-2021-44906"))
+        .unwrap()
+        .clone();
+    assert_ne!(ignored.ignored, IgnoredReason::False);
+    let mut affected = ignored.clone();
+    affected.ignored = IgnoredReason::False;
+    job.packages[0].issues.push(affected);
+    job.packages[2].issues.push(ignored);
+
+    let bom = Bom::from_job(&job);
+    let entries: Vec<_> = bom
+        .vulnerabilities
+        .iter()
+        .filter(|vulnerability| vulnerability.id == "CVE-2021-44906")
+        .collect();
+    assert_eq!(entries.len(), 2);
+
+    let refs = |index: usize| -> Vec<&str> {
+        entries[index]
+            .affects
+            .iter()
+            .map(|affects| affects.bom_ref.as_str())
+            .collect()
+    };
+    assert!(entries[0].analysis.is_none());
+    assert_eq!(refs(0), ["pkg:npm/event-stream@3.3.6"]);
+    assert!(entries[1].analysis.is_some());
+    assert_eq!(
+        refs(1),
+        ["pkg:npm/minimist@1.2.5", "pkg:npm/left-pad@1.3.0"]
+    );
+}
+
+#[test]
+fn job_to_xml() {
+    let bom = Bom::from_job(&job());
+    let xml = bom.to_xml();
+
+    assert!(xml.starts_with("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<bom xmlns=\"http://cyclonedx.org/schema/bom/1.5\" serialNumber=\"urn:uuid:59482a54-423b-448d-8325-f171c9dc336b\" version=\"1\">\n"));
+    assert!(xml.contains(
+        "    <component type=\"library\" bom-ref=\"pkg:npm/minimist@1.2.5\">\n      \
+         <name>minimist</name>\n      <version>1.2.5</version>\n"
+    ));
+
+    // Every element written is read back into the same BOM.
+    assert_eq!(Bom::from_xml(&xml).unwrap(), bom);
+}
+
+#[test]
+fn boms_match_schema() {
+    let mut bom = Bom::from_job(&job());
+    assert_matches_schema(&bom);
+
+    let advisory = Advisory::from_json(&fixture("osv/GHSA-xvch-5gv4-984h.json")).unwrap();
+    bom.add_vulnerability("pkg:npm/minimist@1.2.5", &advisory.vulnerability());
+    assert_matches_schema(&bom);
+
+    assert_matches_schema(&Bom::from_json(&fixture("cyclonedx/vendor.json")).unwrap());
+}
+
+#[test]
+fn vulnerabilities_list_each_cve() {
+    let vulnerability = Vulnerability {
+        cve: vec!["CVE-2021-44906".into(), "CVE-2021-0001".into()],
+        base_severity: 9.8,
+        risk_level: RiskLevel::Critical,
+        title: "Prototype Pollution in minimist".into(),
+        description: "minimist allows prototype pollution.".into(),
+        remediation: String::new(),
+    };
+
+    let mut bom = Bom::new();
+    let minimist = bom.add_component(
+        &PackageDescriptor {
+            name: "minimist".into(),
+            version: "1.2.5".into(),
+            package_type: PackageType::Npm,
+        },
+        Some("MIT"),
+    );
+    bom.add_vulnerability(&minimist, &vulnerability);
+    bom.add_vulnerability("pkg:npm/minimist@0.2.1", &vulnerability);
+    bom.add_vulnerability(&minimist, &vulnerability);
+
+    let ids: Vec<&str> = bom
+        .vulnerabilities
+        .iter()
+        .map(|vulnerability| vulnerability.id.as_str())
+        .collect();
+    assert_eq!(ids, ["CVE-2021-44906", "CVE-2021-0001"]);
+
+    let first = &bom.vulnerabilities[0];
+    assert_eq!(first.ratings[0].score, Some(9.8));
+    assert_eq!(first.ratings[0].severity.as_deref(), Some("critical"));
+    assert_eq!(first.ratings[0].method.as_deref(), Some("CVSSv3"));
+    assert_eq!(first.recommendation, None);
+    let refs: Vec<&str> = first
+        .affects
+        .iter()
+        .map(|affects| affects.bom_ref.as_str())
+        .collect();
+    assert_eq!(refs, ["pkg:npm/minimist@1.2.5", "pkg:npm/minimist@0.2.1"]);
+
+    // Without CVEs, the vulnerability is listed by title.
+    let mut bom = Bom::new();
+    bom.add_vulnerability(
+        &minimist,
+        &Vulnerability {
+            cve: Vec::new(),
+            ..vulnerability
+        },
+    );
+    assert_eq!(bom.vulnerabilities[0].id, "Prototype Pollution in minimist");
+}
+
+#[test]
+fn job_packages_round_trip() {
+    let job = job();
+    let bom = Bom::from_json(&Bom::from_job(&job).to_json()).unwrap();
+
+    let expected: Vec<PackageDescriptorAndLockfile> = job
+        .packages
+        .iter()
+        .map(|status| {
+            package(
+                &status.basic_status.name,
+                &status.basic_status.version,
+                status.package_type.clone(),
+            )
+        })
+        .collect();
+    assert_eq!(bom.packages(), expected);
+}
+
+#[test]
+fn vendor_sbom_to_packages() {
+    let bom = Bom::from_json(&fixture("cyclonedx/vendor.json")).unwrap();
+    assert_eq!(
+        bom.packages(),
+        [
+            package(
+                "org.apache.logging.log4j:log4j-core",
+                "2.14.1",
+                PackageType::Maven
+            ),
+            package(
+                "org.apache.logging.log4j:log4j-api",
+                "2.14.1",
+                PackageType::Maven
+            ),
+            package("lodash", "4.17.20", PackageType::Npm),
+            package("chalk", "5.3.0", PackageType::Npm),
+        ]
+    );
+
+    // Invalid package URLs don't stop the import, but are reported.
+    let invalid: Vec<&str> = bom.invalid_purls().iter().map(|(purl, _)| *purl).collect();
+    assert_eq!(invalid, ["pkg:npm/left-pad"]);
+
+    assert!(Bom::from_json(r#"{"bomFormat": "SPDX", "specVersion": "1.5"}"#).is_err());
+}
+
+#[test]
+fn vendor_xml_sbom_to_packages() {
+    let json = Bom::from_json(&fixture("cyclonedx/vendor.json")).unwrap();
+    let xml = Bom::from_xml(&fixture("cyclonedx/vendor.xml")).unwrap();
+
+    // The spec version comes from the namespace.
+    assert_eq!(xml.spec_version, "1.4");
+    assert_eq!(xml.metadata, json.metadata);
+    assert_eq!(xml.packages(), json.packages());
+    let invalid: Vec<&str> = xml.invalid_purls().iter().map(|(purl, _)| *purl).collect();
+    assert_eq!(invalid, ["pkg:npm/left-pad"]);
+
+    let err = Bom::from_xml("<bom xmlns=\"http://spdx.org/rdf/terms\"/>").unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Invalid CycloneDX document: unexpected root element {http://spdx.org/rdf/terms}bom"
+    );
+    assert!(Bom::from_xml("<bom xmlns=\"http://cyclonedx.org/schema/bom/1.5\">").is_err());
+    assert!(Bom::from_xml(
+        "<bom xmlns=\"http://cyclonedx.org/schema/bom/1.5\"><components><component \
+         type=\"library\"/></components></bom>"
+    )
+    .is_err());
+}
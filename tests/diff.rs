@@ -0,0 +1,318 @@
+mod common;
+
+use phylum_types::policy::diff::{JobDiff, PackageChange, PackageDiff};
+use phylum_types::policy::evaluate;
+use phylum_types::types::common::Status;
+use phylum_types::types::job::{Action, JobStatusResponse};
+use phylum_types::types::package::{
+    IgnoredReason, Issue, PackageDescriptor, PackageStatusExtended, PackageType, RiskLevel,
+    RiskType,
+};
+use phylum_types::types::preferences::IgnoredIssue;
+
+use common::job;
+
+fn find<'a>(diff: &'a JobDiff, name: &str) -> &'a PackageDiff {
+    diff.packages
+        .iter()
+        .find(|package| package.name == name)
+        .unwrap()
+}
+
+/// The base job with minimist updated, left-pad removed and a malicious
+/// package added. event-stream is unchanged.
+fn head() -> JobStatusResponse<PackageStatusExtended> {
+    let mut head = job();
+
+    let mut minimist = head.packages.remove(1);
+    minimist.basic_status.version = "1.2.6".into();
+    minimist.issues.remove(1);
+    minimist.risk_vectors.clear();
+    minimist.basic_status.package_score = None;
+
+    let mut added = head.packages[0].clone();
+    added.basic_status.name = "flatmap-stream".into();
+    added.basic_status.version = "0.1.1".into();
+    added.issues.truncate(1);
+    added.risk_vectors.clear();
+    added.basic_status.package_score = None;
+    added.issues[0].issue.id = Some("f1a7b3c5-0000-5000-8000-000000000001".into());
+
+    head.packages.remove(1);
+    head.packages.push(minimist);
+    head.packages.push(added);
+    head
+}
+
+#[test]
+fn package_changes() {
+    let mut base = job();
+    base.pass = true;
+    let diff = JobDiff::new(&base, &head(), &[]);
+
+    let changes: Vec<(&str, PackageChange, Option<&str>, Option<&str>)> = diff
+        .packages
+        .iter()
+        .map(|package| {
+            (
+                package.name.as_str(),
+                package.change,
+                package.base_version.as_deref(),
+                package.head_version.as_deref(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        changes,
+        [
+            ("flatmap-stream", PackageChange::Added, None, Some("0.1.1")),
+            ("left-pad", PackageChange::Removed, Some("1.3.0"), None),
+            (
+                "minimist",
+                PackageChange::VersionChanged,
+                Some("1.2.5"),
+                Some("1.2.6")
+            ),
+        ]
+    );
+
+    assert!(diff.base_pass && !diff.head_pass);
+    assert!(diff.pass_flipped());
+}
+
+#[test]
+fn issues_and_scores() {
+    let diff = JobDiff::new(&job(), &head(), &[]);
+
+    // The ignored CVE-2021-44906 is not resolved, the remaining one is.
+    let minimist = find(&diff, "minimist");
+    assert!(minimist.introduced.is_empty());
+    let resolved: Vec<_> = minimist
+        .resolved
+        .iter()
+        .map(|issue| issue.tag.as_deref())
+        .collect();
+    assert_eq!(resolved, [Some("CVE-2020-7598")]);
+    let deltas = minimist.score_deltas.unwrap();
+    assert!((deltas.vulnerability - 0.35).abs() < 1e-6);
+    assert_eq!(deltas.malicious, 0.);
+
+    // Tags match even though the id differs from event-stream's issue.
+    let added = find(&diff, "flatmap-stream");
+    assert_eq!(added.introduced.len(), 1);
+    assert_eq!(added.introduced[0].tag.as_deref(), Some("CM0010"));
+    assert!(added.base_scores.is_none());
+    let deltas = added.score_deltas.unwrap();
+    assert!((deltas.malicious + 0.9).abs() < 1e-6);
+
+    let removed = find(&diff, "left-pad");
+    assert!(removed.introduced.is_empty() && removed.resolved.is_empty());
+    assert_eq!(removed.score_deltas.unwrap().total, 0.);
+}
+
+#[test]
+fn only_new_risk_fails() {
+    let base = job();
+    let head = head();
+    let thresholds = head.risk_thresholds();
+
+    // event-stream fails the whole job, but it was already in the base job.
+    let whole = evaluate(&head.packages, &thresholds, &[]);
+    assert!(whole
+        .failures
+        .iter()
+        .any(|failure| failure.package.name == "event-stream"));
+
+    let verdict = JobDiff::new(&base, &head, &[]).evaluate(&thresholds);
+    assert_eq!(verdict.action, Action::Break);
+    let failures: Vec<(&str, RiskType)> = verdict
+        .failures
+        .iter()
+        .map(|failure| (failure.package.name.as_str(), failure.risk_type))
+        .collect();
+    assert_eq!(
+        failures,
+        [
+            ("flatmap-stream", RiskType::TotalRisk),
+            ("flatmap-stream", RiskType::MaliciousRisk),
+        ]
+    );
+
+    // Ignoring the new issue makes the check pass.
+    let ignored = [IgnoredIssue {
+        id: "f1a7b3c5-0000-5000-8000-000000000001".into(),
+        tag: "CM0010".into(),
+        reason: IgnoredReason::FalsePositive,
+    }];
+    let diff = JobDiff::new(&base, &head, &ignored);
+    assert!(find(&diff, "flatmap-stream").introduced.is_empty());
+    assert!(diff.evaluate(&thresholds).is_pass());
+}
+
+#[test]
+fn updates_which_keep_existing_risk() {
+    // Updating event-stream to a version with the same issues doesn't
+    // introduce risk.
+    let base = job();
+    let mut head = job();
+    head.packages[0].basic_status.version = "3.3.7".into();
+
+    let diff = JobDiff::new(&base, &head, &[]);
+    assert_eq!(diff.packages.len(), 1);
+    let package = &diff.packages[0];
+    assert_eq!(package.change, PackageChange::VersionChanged);
+    assert!(package.introduced.is_empty() && package.resolved.is_empty());
+    assert_eq!(package.score_deltas.unwrap().total, 0.);
+    assert!(diff.evaluate(&head.risk_thresholds()).is_pass());
+
+    // Without scores for the new version there is no delta, and the check
+    // can't pass until it was analyzed.
+    head.packages[0].basic_status.status = Status::Incomplete;
+    let diff = JobDiff::new(&base, &head, &[]);
+    assert!(diff.packages[0].head_scores.is_none());
+    assert!(diff.packages[0].score_deltas.is_none());
+    let verdict = diff.evaluate(&head.risk_thresholds());
+    assert_eq!(verdict.action, Action::None);
+    assert!(verdict.failures.is_empty());
+    assert_eq!(
+        verdict.pending,
+        [PackageDescriptor {
+            name: "event-stream".into(),
+            version: "3.3.7".into(),
+            package_type: PackageType::Npm,
+        }]
+    );
+    assert!(!verdict.is_pass());
+}
+
+#[test]
+fn unanalyzed_new_packages_are_pending() {
+    let mut head = head();
+    head.packages[2].basic_status.status = Status::Incomplete;
+
+    let verdict = JobDiff::new(&job(), &head, &[]).evaluate(&head.risk_thresholds());
+    let pending: Vec<&str> = verdict
+        .pending
+        .iter()
+        .map(|package| package.name.as_str())
+        .collect();
+    assert_eq!(pending, ["flatmap-stream"]);
+    assert!(verdict.failures.is_empty());
+    assert!(!verdict.is_pass());
+
+    let json = serde_json::to_value(&verdict).unwrap();
+    assert_eq!(json["pending"][0]["version"], "0.1.1");
+}
+
+#[test]
+fn introduced_issues_fail_without_a_score_change() {
+    // The new version swaps the malicious code issue for a different one of
+    // the same severity, so no score got worse.
+    let base = job();
+    let mut head = job();
+    head.packages[0].basic_status.version = "3.3.7".into();
+    let issue = &mut head.packages[0].issues[0].issue;
+    issue.tag = Some("CM0011".into());
+    issue.id = Some("2b9d4e71-6c3a-4f08-9e5d-1a7c3b8f6d24".into());
+
+    let diff = JobDiff::new(&base, &head, &[]);
+    let package = &diff.packages[0];
+    assert_eq!(package.introduced.len(), 1);
+    assert_eq!(package.score_deltas.unwrap().malicious, 0.);
+
+    let verdict = diff.evaluate(&head.risk_thresholds());
+    assert_eq!(verdict.action, Action::Break);
+    let failures: Vec<RiskType> = verdict
+        .failures
+        .iter()
+        .map(|failure| failure.risk_type)
+        .collect();
+    assert_eq!(failures, [RiskType::TotalRisk, RiskType::MaliciousRisk]);
+
+    // An introduced issue above the cutoff doesn't fail on its own.
+    head.packages[0].issues[0].issue.severity = RiskLevel::Low;
+    let verdict = JobDiff::new(&base, &head, &[]).evaluate(&head.risk_thresholds());
+    assert!(verdict.is_pass());
+}
+
+#[test]
+fn packages_are_matched_by_canonical_name() {
+    let mut base = job();
+    base.packages.truncate(1);
+    base.packages[0].package_type = PackageType::PyPi;
+    base.packages[0].basic_status.name = "Django_Filter".into();
+    let mut head = base.clone();
+    head.packages[0].basic_status.name = "django-filter".into();
+    head.packages[0].basic_status.version = "3.3.7".into();
+
+    let diff = JobDiff::new(&base, &head, &[]);
+    assert_eq!(diff.packages.len(), 1);
+    assert_eq!(diff.packages[0].change, PackageChange::VersionChanged);
+    assert_eq!(diff.packages[0].name, "django-filter");
+
+    // Versions are compared with the ecosystem's semantics as well.
+    head.packages[0].basic_status.version = "3.3.6.0".into();
+    assert!(JobDiff::new(&base, &head, &[]).packages.is_empty());
+}
+
+#[test]
+fn versions_are_paired_in_order() {
+    let mut base = job();
+    base.packages.truncate(1);
+    let mut newer = base.packages[0].clone();
+    newer.basic_status.version = "3.10.0".into();
+    base.packages.insert(0, newer);
+
+    let mut head = base.clone();
+    head.packages[0].basic_status.version = "3.3.7".into();
+    head.packages[1].basic_status.version = "3.10.1".into();
+
+    let diff = JobDiff::new(&base, &head, &[]);
+    let versions: Vec<(Option<&str>, Option<&str>)> = diff
+        .packages
+        .iter()
+        .map(|package| {
+            (
+                package.base_version.as_deref(),
+                package.head_version.as_deref(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        versions,
+        [
+            (Some("3.3.6"), Some("3.3.7")),
+            (Some("3.10.0"), Some("3.10.1")),
+        ]
+    );
+}
+
+#[test]
+fn untagged_issues_are_matched_by_title() {
+    let mut base = job();
+    base.packages.truncate(1);
+    for issue in &mut base.packages[0].issues {
+        issue.issue.id = None;
+        issue.issue.tag = None;
+    }
+    let mut head = base.clone();
+    head.packages[0].basic_status.version = "3.3.7".into();
+    head.packages[0].issues[1].issue.title = "Maintainer account takeover".into();
+
+    let diff = JobDiff::new(&base, &head, &[]);
+    let titles = |issues: &[Issue]| -> Vec<String> {
+        issues.iter().map(|issue| issue.title.clone()).collect()
+    };
+    assert_eq!(
+        titles(&diff.packages[0].introduced),
+        ["Maintainer account takeover"]
+    );
+    assert_eq!(titles(&diff.packages[0].resolved), ["Maintainer changed"]);
+}
+
+#[test]
+fn unchanged_jobs() {
+    let diff = JobDiff::new(&job(), &job(), &[]);
+    assert!(diff.packages.is_empty());
+    assert!(!diff.pass_flipped());
+}
@@ -0,0 +1,387 @@
+mod common;
+
+use phylum_types::ecosystems::cocoapods::PodfileLock;
+use phylum_types::ecosystems::composer::ComposerLock;
+use phylum_types::ecosystems::conda::{CondaEnvironment, CondaExplicitLock};
+use phylum_types::ecosystems::gradle::{GradleLockfile, VersionCatalog};
+use phylum_types::ecosystems::hex::{MixDependency, MixLock};
+use phylum_types::ecosystems::pubspec::PubspecLock;
+use phylum_types::ecosystems::swift::PackageResolved;
+use phylum_types::types::package::{PackageDescriptor, PackageType};
+
+use common::fixture;
+
+fn descriptors(packages: &[PackageDescriptor]) -> Vec<(&str, &str, PackageType)> {
+    packages
+        .iter()
+        .map(|package| {
+            (
+                package.name.as_str(),
+                package.version.as_str(),
+                package.package_type.clone(),
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn composer_lock() {
+    let lock: ComposerLock = fixture("lockfiles/composer.lock").parse().unwrap();
+    assert_eq!(lock.minimum_stability.as_deref(), Some("stable"));
+    assert_eq!(lock.packages[0].require["psr/log"], "^2.0 || ^3.0");
+    assert_eq!(lock.packages[0].license, ["MIT"]);
+
+    // Development packages are included.
+    assert_eq!(
+        descriptors(&lock.packages()),
+        [
+            ("monolog/monolog", "3.5.0", PackageType::Composer),
+            ("psr/log", "3.0.0", PackageType::Composer),
+            ("phpunit/php-timer", "6.0.0", PackageType::Composer),
+        ]
+    );
+
+    assert!("{".parse::<ComposerLock>().is_err());
+}
+
+#[test]
+fn mix_lock() {
+    let lock: MixLock = fixture("lockfiles/mix.lock").parse().unwrap();
+    assert_eq!(lock.dependencies.len(), 4);
+
+    match &lock.dependencies["jason"] {
+        MixDependency::Hex {
+            dependencies, repo, ..
+        } => {
+            assert_eq!(repo, "hexpm");
+            assert_eq!(dependencies[0].name, "decimal");
+            assert_eq!(dependencies[0].requirement, "~> 1.0 or ~> 2.0");
+            assert!(dependencies[0].optional);
+        }
+        dependency => panic!("unexpected dependency {:?}", dependency),
+    }
+    assert!(matches!(
+        &lock.dependencies["phoenix_html"],
+        MixDependency::Git { revision, .. } if revision.starts_with("4cb5ed6")
+    ));
+
+    // Git dependencies are skipped.
+    assert_eq!(
+        descriptors(&lock.packages()),
+        [
+            ("decimal", "2.1.1", PackageType::Hex),
+            ("jason", "1.4.1", PackageType::Hex),
+            ("telemetry", "1.2.1", PackageType::Hex),
+        ]
+    );
+
+    assert!(r#"%{"jason": {:hex, :jason}"#.parse::<MixLock>().is_err());
+    assert!("[]".parse::<MixLock>().is_err());
+
+    // Deeply nested terms are rejected instead of overflowing the stack.
+    let nested = format!("%{{\"a\": {}{}}}", "[".repeat(100_000), "]".repeat(100_000));
+    let err = nested.parse::<MixLock>().unwrap_err();
+    assert!(err.to_string().contains("nested too deeply"), "{}", err);
+}
+
+#[test]
+fn pubspec_lock() {
+    let lock: PubspecLock = fixture("lockfiles/pubspec.lock").parse().unwrap();
+    assert_eq!(lock.sdks["dart"], ">=3.0.0 <4.0.0");
+    assert_eq!(lock.packages["http"].dependency, "direct main");
+
+    // SDK, git and path packages are skipped.
+    assert_eq!(
+        descriptors(&lock.packages()),
+        [
+            ("async", "2.11.0", PackageType::Pub),
+            ("http", "1.1.0", PackageType::Pub),
+        ]
+    );
+}
+
+#[test]
+fn podfile_lock() {
+    let lock: PodfileLock = fixture("lockfiles/Podfile.lock").parse().unwrap();
+    assert_eq!(lock.cocoapods.as_deref(), Some("1.14.3"));
+    assert_eq!(lock.pods[1].pod(), "Firebase/CoreOnly (10.18.0)");
+    assert_eq!(lock.pods[1].dependencies(), ["FirebaseCore (= 10.18.0)"]);
+
+    // Subspecs collapse into their root, external sources are skipped.
+    assert_eq!(
+        descriptors(&lock.packages()),
+        [
+            ("Alamofire", "5.8.1", PackageType::CocoaPods),
+            ("Firebase", "10.18.0", PackageType::CocoaPods),
+            ("FirebaseCore", "10.18.0", PackageType::CocoaPods),
+            ("FirebaseCoreInternal", "10.18.0", PackageType::CocoaPods),
+            ("GoogleUtilities", "7.12.0", PackageType::CocoaPods),
+        ]
+    );
+}
+
+#[test]
+fn package_resolved() {
+    let resolved: PackageResolved = fixture("lockfiles/Package.resolved").parse().unwrap();
+    assert_eq!(resolved.version, 2);
+    assert_eq!(
+        descriptors(&resolved.packages()),
+        [
+            ("github.com/apple/swift-log", "1.5.3", PackageType::Swift),
+            (
+                "github.com/apple/swift-nio",
+                "702cd7c56d5d44eeba73fdf83918339b26dc855c",
+                PackageType::Swift
+            ),
+        ]
+    );
+
+    let resolved: PackageResolved = fixture("lockfiles/Package.v1.resolved").parse().unwrap();
+    assert_eq!(resolved.version, 1);
+    assert_eq!(resolved.pins[0].identity, "swift-argument-parser");
+    assert_eq!(
+        descriptors(&resolved.packages()),
+        [(
+            "github.com/apple/swift-argument-parser",
+            "1.2.3",
+            PackageType::Swift
+        )]
+    );
+}
+
+#[test]
+fn gradle_lockfile() {
+    let lockfile: GradleLockfile = fixture("lockfiles/gradle.lockfile").parse().unwrap();
+    assert_eq!(lockfile.dependencies.len(), 8);
+    assert_eq!(
+        lockfile.empty,
+        ["annotationProcessor", "testAnnotationProcessor"]
+    );
+
+    let guava = &lockfile.dependencies[2];
+    assert_eq!(guava.package_descriptor.name, "com.google.guava:guava");
+    assert_eq!(guava.package_descriptor.version, "32.1.3-jre");
+    assert!(guava.in_configuration("runtimeClasspath"));
+    assert!(!guava.is_test_only());
+
+    // Dependencies only resolved by test configurations are dropped.
+    let names: Vec<String> = lockfile
+        .non_test_packages()
+        .into_iter()
+        .map(|package| package.name)
+        .collect();
+    assert_eq!(
+        names,
+        [
+            "com.google.code.findbugs:jsr305",
+            "com.google.guava:failureaccess",
+            "com.google.guava:guava",
+            "org.slf4j:slf4j-api",
+        ]
+    );
+    assert!(lockfile
+        .packages()
+        .iter()
+        .all(|package| package.package_type == PackageType::Maven));
+
+    let err = "com.google.guava:guava=runtimeClasspath\n"
+        .parse::<GradleLockfile>()
+        .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Invalid gradle.lockfile: malformed dependency coordinates \"com.google.guava:guava\" on \
+         line 1"
+    );
+    assert!("# comment\ncom.google.guava:guava:32.1.3-jre"
+        .parse::<GradleLockfile>()
+        .is_err());
+}
+
+#[test]
+fn version_catalog() {
+    let catalog: VersionCatalog = fixture("lockfiles/libs.versions.toml").parse().unwrap();
+
+    // The BOM-managed launcher has no version and is skipped.
+    assert_eq!(
+        descriptors(&catalog.packages().unwrap()),
+        [
+            ("com.google.guava:guava", "32.1.3-jre", PackageType::Maven),
+            ("org.junit:junit-bom", "5.10.1", PackageType::Maven),
+            (
+                "org.junit.jupiter:junit-jupiter-api",
+                "5.10.1",
+                PackageType::Maven
+            ),
+            (
+                "org.junit.jupiter:junit-jupiter-engine",
+                "5.10.1",
+                PackageType::Maven
+            ),
+            ("org.slf4j:slf4j-api", "2.0.9", PackageType::Maven),
+            ("org.slf4j:slf4j-simple", "2.0.9", PackageType::Maven),
+        ]
+    );
+
+    // Aliases match with any of `-`, `_` and `.` as separators.
+    let bundle: Vec<String> = catalog
+        .bundle("junit")
+        .unwrap()
+        .into_iter()
+        .map(|package| package.name)
+        .collect();
+    assert_eq!(
+        bundle,
+        [
+            "org.junit.jupiter:junit-jupiter-api",
+            "org.junit.jupiter:junit-jupiter-engine"
+        ]
+    );
+    assert!(catalog.library("slf4j.api").unwrap().is_some());
+    assert_eq!(catalog.library("junit.platform.launcher").unwrap(), None);
+    assert!(catalog.library("log4j").is_err());
+    assert!(catalog.bundle("missing").is_err());
+
+    let dangling = r#"
+        [libraries]
+        guava = { module = "com.google.guava:guava", version.ref = "missing" }
+    "#;
+    let err = dangling
+        .parse::<VersionCatalog>()
+        .unwrap()
+        .packages()
+        .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Invalid version catalog: unknown version reference \"missing\""
+    );
+}
+
+#[test]
+fn conda_environment() {
+    let environment: CondaEnvironment = fixture("lockfiles/environment.yml").parse().unwrap();
+    assert_eq!(environment.name.as_deref(), Some("analysis"));
+    assert_eq!(environment.channels, ["conda-forge", "defaults"]);
+
+    // `python=3.11` matches any 3.11 release and `scipy>=1.11` is a range,
+    // neither can be resolved without a solver.
+    assert_eq!(
+        descriptors(&environment.packages()),
+        [
+            ("numpy", "1.26.2", PackageType::Conda),
+            ("pandas", "2.1.4", PackageType::Conda),
+            ("matplotlib-base", "3.8.2", PackageType::Conda),
+            ("requests", "2.31.0", PackageType::PyPi),
+        ]
+    );
+}
+
+#[test]
+fn conda_match_specs() {
+    let cases = [
+        ("numpy==1.21.0", Some("1.21.0")),
+        ("numpy=1.21.0=py39h_0", Some("1.21.0")),
+        ("numpy==1.21.0=py39h_0", Some("1.21.0")),
+        ("conda-forge::numpy==1.21.0", Some("1.21.0")),
+        // A single `=` without build is a prefix match.
+        ("numpy=1.21", None),
+        ("numpy=1.21.0", None),
+        ("numpy=1.21.*=py39h_0", None),
+        ("numpy>=1.21", None),
+        ("numpy", None),
+    ];
+    for (spec, version) in cases {
+        let environment = CondaEnvironment {
+            dependencies: vec![serde_yaml::from_str(spec).unwrap()],
+            ..CondaEnvironment::default()
+        };
+        let packages = environment.packages();
+        assert_eq!(
+            packages.first().map(|package| package.version.as_str()),
+            version,
+            "{}",
+            spec
+        );
+    }
+}
+
+#[test]
+fn conda_explicit_lock() {
+    let lock: CondaExplicitLock = fixture("lockfiles/conda-linux-64.lock").parse().unwrap();
+    assert_eq!(lock.platform.as_deref(), Some("linux-64"));
+
+    let numpy = &lock.packages[2];
+    assert_eq!(numpy.channel, "https://conda.anaconda.org/conda-forge");
+    assert_eq!(numpy.subdir, "linux-64");
+    assert_eq!(numpy.build, "py311h64a7726_0");
+    assert_eq!(
+        numpy.md5.as_deref(),
+        Some("fb4ad3b5a1bb8b9c8b83d4d1e27ab1e2")
+    );
+
+    assert_eq!(
+        descriptors(&lock.packages()),
+        [
+            ("ca-certificates", "2023.11.17", PackageType::Conda),
+            ("tzdata", "2023c", PackageType::Conda),
+            ("numpy", "1.26.2", PackageType::Conda),
+            ("libffi", "3.4.4", PackageType::Conda),
+        ]
+    );
+
+    let err = "https://example.com/numpy-1.0-0.conda"
+        .parse::<CondaExplicitLock>()
+        .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Invalid conda lockfile: missing @EXPLICIT marker"
+    );
+    let err = "@EXPLICIT\nnumpy-1.0-0.conda"
+        .parse::<CondaExplicitLock>()
+        .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Invalid conda lockfile: malformed package URL \"numpy-1.0-0.conda\""
+    );
+}
+
+#[test]
+fn package_type_wire_names() {
+    for package_type in PackageType::KNOWN {
+        let json = serde_json::to_string(&package_type).unwrap();
+        assert_eq!(
+            serde_json::from_str::<PackageType>(&json).unwrap(),
+            package_type
+        );
+        assert_eq!(
+            package_type.as_str().parse::<PackageType>().unwrap(),
+            package_type
+        );
+    }
+
+    // Ecosystems added to the API later still deserialize.
+    let other: PackageType = serde_json::from_str(r#""bioconductor""#).unwrap();
+    assert_eq!(other, PackageType::Other("bioconductor".into()));
+    assert_eq!(other.as_str(), "bioconductor");
+    assert_eq!(serde_json::to_string(&other).unwrap(), r#""bioconductor""#);
+
+    // Long names round-trip unchanged.
+    let long = format!(r#""{}""#, "x".repeat(40));
+    let other: PackageType = serde_json::from_str(&long).unwrap();
+    assert_eq!(serde_json::to_string(&other).unwrap(), long);
+}
+
+#[test]
+fn package_type_schema() {
+    let schema = serde_json::to_value(schemars::schema_for!(PackageType)).unwrap();
+    let any_of = schema["anyOf"].as_array().unwrap();
+    let known: Vec<&str> = any_of[0]["enum"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|name| name.as_str().unwrap())
+        .collect();
+    assert_eq!(known.len(), PackageType::KNOWN.len());
+    assert!(known.contains(&"npm") && known.contains(&"conda"));
+    assert_eq!(any_of[1]["type"], "string");
+    assert!(any_of[1].get("enum").is_none());
+}
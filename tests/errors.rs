@@ -0,0 +1,107 @@
+use phylum_types::error::Error;
+use phylum_types::types::job::Action;
+use phylum_types::types::package::PackageType;
+
+fn ecosystem_suggestion(name: &str) -> Option<PackageType> {
+    match name.parse::<PackageType>() {
+        Err(Error::UnknownEcosystem { suggestion, .. }) => suggestion,
+        result => panic!("{name:?} was accepted: {:?}", result),
+    }
+}
+
+fn action_suggestion(name: &str) -> Option<Action> {
+    match name.parse::<Action>() {
+        Err(Error::UnknownAction { suggestion, .. }) => suggestion,
+        result => panic!("{name:?} was accepted: {:?}", result),
+    }
+}
+
+#[test]
+fn ecosystem_aliases_ignore_case() {
+    let cases = [
+        ("NPM", PackageType::Npm),
+        ("Python", PackageType::PyPi),
+        ("maven-central", PackageType::Maven),
+        ("Gem", PackageType::RubyGems),
+        ("dotnet", PackageType::Nuget),
+        ("Packagist", PackageType::Composer),
+        ("elixir", PackageType::Hex),
+        ("Flutter", PackageType::Pub),
+        ("pods", PackageType::CocoaPods),
+        ("SPM", PackageType::Swift),
+        ("anaconda", PackageType::Conda),
+    ];
+    for (name, expected) in cases {
+        assert_eq!(name.parse::<PackageType>().unwrap(), expected, "{name}");
+    }
+}
+
+#[test]
+fn ecosystem_typos_are_suggested() {
+    // Substitutions, insertions, deletions and adjacent transpositions each
+    // count as a single edit.
+    let cases = [
+        ("nmp", PackageType::Npm),
+        ("np", PackageType::Npm),
+        ("crago", PackageType::Cargo),
+        ("rubygem", PackageType::RubyGems),
+        ("composr", PackageType::Composer),
+        ("mavne", PackageType::Maven),
+        ("cocopods", PackageType::CocoaPods),
+        ("condas", PackageType::Conda),
+        ("swfit", PackageType::Swift),
+    ];
+    for (name, expected) in cases {
+        assert_eq!(ecosystem_suggestion(name), Some(expected), "{name}");
+    }
+
+    // The suggestion is the wire name, even if an alias was closer.
+    let err = "Golnag".parse::<PackageType>().unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Unknown ecosystem \"Golnag\", did you mean \"golang\"?"
+    );
+    let err = "pyhton".parse::<PackageType>().unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Unknown ecosystem \"pyhton\", did you mean \"pypi\"?"
+    );
+}
+
+#[test]
+fn distant_ecosystems_have_no_suggestion() {
+    for name in ["haskell", "github", "", "cpan", "bower"] {
+        assert_eq!(ecosystem_suggestion(name), None, "{name}");
+    }
+
+    let err = "haskell".parse::<PackageType>().unwrap_err();
+    assert_eq!(err.to_string(), "Unknown ecosystem \"haskell\"");
+
+    // Package URL types are matched exactly, without suggestions.
+    let err = PackageType::from_purl_type("crago").unwrap_err();
+    assert_eq!(err.to_string(), "Unknown ecosystem \"crago\"");
+    assert_eq!(
+        PackageType::from_purl_type("GEM").unwrap(),
+        PackageType::RubyGems
+    );
+}
+
+#[test]
+fn action_typos_are_suggested() {
+    assert_eq!(action_suggestion("brake"), Some(Action::Break));
+    assert_eq!(action_suggestion("warm"), Some(Action::Warn));
+    assert_eq!(action_suggestion("nnoe"), Some(Action::None));
+    assert_eq!(action_suggestion("braek"), Some(Action::Break));
+
+    for name in ["block", "", "fail"] {
+        assert_eq!(action_suggestion(name), None, "{name}");
+    }
+
+    let err = "Warnn".parse::<Action>().unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Unknown action \"Warnn\", did you mean \"warn\"?"
+    );
+    let err = "block".parse::<Action>().unwrap_err();
+    assert_eq!(err.to_string(), "Unknown action \"block\"");
+}
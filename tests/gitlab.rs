@@ -0,0 +1,179 @@
+mod common;
+
+use phylum_types::formats::gitlab::{
+    to_code_quality, to_dependency_scanning, DependencyScanningReport,
+};
+use phylum_types::types::package::{PackageDescriptor, PackageDescriptorAndLockfile, PackageType};
+
+use common::job;
+
+fn submitted(name: &str, version: &str, lockfile: Option<&str>) -> PackageDescriptorAndLockfile {
+    PackageDescriptorAndLockfile {
+        package_descriptor: PackageDescriptor {
+            name: name.into(),
+            version: version.into(),
+            package_type: PackageType::Npm,
+        },
+        lockfile: lockfile.map(String::from),
+    }
+}
+
+fn all_submitted() -> Vec<PackageDescriptorAndLockfile> {
+    vec![
+        submitted("event-stream", "3.3.6", Some("package-lock.json")),
+        submitted("minimist", "1.2.5", Some("package-lock.json")),
+        submitted("minimist", "1.2.5", Some("web/yarn.lock")),
+        submitted("left-pad", "1.3.0", None),
+    ]
+}
+
+#[test]
+fn dependency_scanning_report() {
+    let report = to_dependency_scanning(&job(), &all_submitted());
+    let json = serde_json::to_value(report).unwrap();
+
+    assert_eq!(json["version"], "15.0.7");
+    assert_eq!(json["scan"]["type"], "dependency_scanning");
+    assert_eq!(json["scan"]["start_time"], "2023-03-10T00:00:00");
+    assert_eq!(json["scan"]["end_time"], "2023-03-10T00:01:00");
+    assert_eq!(json["scan"]["scanner"]["vendor"]["name"], "Phylum");
+
+    // CVE-2021-44906 is ignored, minimist's other vulnerability is in two
+    // lockfiles.
+    let vulnerabilities = json["vulnerabilities"].as_array().unwrap();
+    let locations: Vec<(&str, &str)> = vulnerabilities
+        .iter()
+        .map(|vulnerability| {
+            (
+                vulnerability["identifiers"][0]["value"].as_str().unwrap(),
+                vulnerability["location"]["file"].as_str().unwrap(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        locations,
+        [
+            ("CM0010", "package-lock.json"),
+            ("CVE-2020-7598", "package-lock.json"),
+            ("CVE-2020-7598", "web/yarn.lock"),
+        ]
+    );
+
+    let malicious = &vulnerabilities[0];
+    assert_eq!(malicious["severity"], "Critical");
+    assert_eq!(malicious["name"], "Malicious code in flatmap-stream");
+    assert_eq!(malicious["identifiers"][0]["type"], "phylum");
+    assert_eq!(malicious["identifiers"][1]["type"], "phylum_issue");
+    assert_eq!(
+        malicious["location"]["dependency"],
+        serde_json::json!({ "package": { "name": "event-stream" }, "version": "3.3.6" })
+    );
+
+    let cve = &vulnerabilities[1];
+    assert_eq!(cve["severity"], "Medium");
+    assert_eq!(cve["identifiers"][0]["type"], "cve");
+    assert_eq!(
+        cve["identifiers"][0]["url"],
+        "https://nvd.nist.gov/vuln/detail/CVE-2020-7598"
+    );
+    assert_ne!(cve["id"], vulnerabilities[2]["id"]);
+
+    let files = json["dependency_files"].as_array().unwrap();
+    assert_eq!(files.len(), 2);
+    assert_eq!(files[0]["path"], "package-lock.json");
+    assert_eq!(files[0]["package_manager"], "npm");
+    assert_eq!(files[0]["dependencies"].as_array().unwrap().len(), 2);
+    assert_eq!(files[1]["path"], "web/yarn.lock");
+    assert_eq!(files[1]["package_manager"], "yarn");
+}
+
+#[test]
+fn code_quality_report() {
+    let findings = to_code_quality(&job(), &all_submitted());
+    let json = serde_json::to_value(&findings).unwrap();
+    let findings = json.as_array().unwrap();
+
+    let checks: Vec<&str> = findings
+        .iter()
+        .map(|finding| finding["check_name"].as_str().unwrap())
+        .collect();
+    assert_eq!(checks, ["HA0001", "HL0002"]);
+
+    assert_eq!(
+        findings[0]["description"],
+        "event-stream@3.3.6: Maintainer changed"
+    );
+    assert_eq!(findings[0]["severity"], "critical");
+    assert_eq!(findings[0]["categories"][0], "Security");
+    assert_eq!(findings[0]["location"]["path"], "package-lock.json");
+    assert_eq!(findings[0]["location"]["lines"]["begin"], 1);
+    assert_eq!(findings[1]["severity"], "minor");
+    assert_eq!(findings[1]["categories"][0], "Compatibility");
+    assert_ne!(findings[0]["fingerprint"], findings[1]["fingerprint"]);
+}
+
+#[test]
+fn packages_without_lockfile_use_manifest() {
+    let submitted: Vec<_> = all_submitted()
+        .into_iter()
+        .map(|package| PackageDescriptorAndLockfile {
+            lockfile: None,
+            ..package
+        })
+        .collect();
+
+    // Every issue is still reported, once, in the manifest.
+    let report = to_dependency_scanning(&job(), &submitted);
+    let files: Vec<(&str, &str)> = report
+        .vulnerabilities
+        .iter()
+        .map(|vulnerability| {
+            (
+                vulnerability.identifiers[0].value.as_str(),
+                vulnerability.location.file.as_str(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        files,
+        [
+            ("CM0010", "package.json"),
+            ("CVE-2020-7598", "package.json")
+        ]
+    );
+    assert!(report.dependency_files.is_empty());
+
+    let findings = to_code_quality(&job(), &[]);
+    let paths: Vec<&str> = findings
+        .iter()
+        .map(|finding| finding.location.path.as_str())
+        .collect();
+    assert_eq!(paths, ["package.json", "package.json"]);
+}
+
+#[test]
+fn report_round_trip() {
+    let report = to_dependency_scanning(&job(), &all_submitted());
+    let json = serde_json::to_string(&report).unwrap();
+    let parsed: DependencyScanningReport = serde_json::from_str(&json).unwrap();
+    assert_eq!(parsed, report);
+}
+
+#[test]
+fn issues_without_tag_or_id_use_title() {
+    let mut job = job();
+    let issue = &mut job.packages[0].issues[0].issue;
+    issue.tag = None;
+    issue.id = None;
+
+    let report = to_dependency_scanning(&job, &all_submitted());
+    let json = serde_json::to_value(report).unwrap();
+    assert_eq!(
+        json["vulnerabilities"][0]["identifiers"],
+        serde_json::json!([{
+            "type": "phylum",
+            "name": "Malicious code in flatmap-stream",
+            "value": "Malicious code in flatmap-stream",
+        }])
+    );
+}
@@ -0,0 +1,246 @@
+use std::collections::HashMap;
+
+use phylum_types::policy::layers::{
+    Layer, PartialThresholds, PreferenceOverrides, PreferenceResolver,
+};
+use phylum_types::types::job::Action;
+use phylum_types::types::package::{IgnoredReason, RiskType};
+use phylum_types::types::preferences::{CorePreferences, IgnoredIssue};
+use phylum_types::types::user_settings::{Setting, Threshold, UserProject, UserSettings};
+
+fn threshold(action: Action, threshold: f32) -> Threshold {
+    Threshold {
+        action,
+        active: true,
+        threshold,
+    }
+}
+
+fn ignored(id: &str, reason: IgnoredReason) -> IgnoredIssue {
+    IgnoredIssue {
+        id: id.into(),
+        tag: "HV00001".into(),
+        reason,
+    }
+}
+
+fn user_settings() -> UserSettings {
+    let thresholds = HashMap::from([
+        ("total".to_owned(), threshold(Action::Warn, 0.5)),
+        ("malicious_code".to_owned(), threshold(Action::Warn, 0.4)),
+    ]);
+    UserSettings {
+        version: 1,
+        projects: HashMap::from([(
+            "project".to_owned(),
+            Setting::Project(UserProject { thresholds }),
+        )]),
+    }
+}
+
+#[test]
+fn defaults_without_layers() {
+    let effective = PreferenceResolver::new().resolve();
+    for risk_type in [
+        RiskType::TotalRisk,
+        RiskType::AuthorsRisk,
+        RiskType::EngineeringRisk,
+        RiskType::LicenseRisk,
+        RiskType::MaliciousRisk,
+        RiskType::Vulnerabilities,
+    ] {
+        let resolved = effective.thresholds.get(risk_type);
+        assert_eq!(resolved.layer, Layer::Default);
+        assert_eq!(resolved.value, Threshold::default());
+    }
+    assert_eq!(effective.default_label, None);
+    assert!(effective.ignored_issues.is_empty());
+}
+
+#[test]
+fn unconfigured_domains_keep_lower_layers() {
+    // The project only configures the license threshold.
+    let project: PreferenceOverrides = serde_json::from_str(
+        r#"{
+            "defaultLabel": null,
+            "thresholds": {
+                "license": { "action": "break", "active": true, "threshold": 0.7 }
+            },
+            "ignoredIssues": null
+        }"#,
+    )
+    .unwrap();
+
+    let effective = PreferenceResolver::new()
+        .project(project.clone())
+        .user_settings(&user_settings(), "project")
+        .resolve();
+    let thresholds = &effective.thresholds;
+
+    assert_eq!(thresholds.license.layer, Layer::Project);
+    assert_eq!(thresholds.license.value, threshold(Action::Break, 0.7));
+    assert_eq!(thresholds.total.layer, Layer::User);
+    assert_eq!(thresholds.total.value, threshold(Action::Warn, 0.5));
+    assert_eq!(thresholds.malicious.layer, Layer::User);
+    assert_eq!(thresholds.author.layer, Layer::Default);
+    assert_eq!(thresholds.vulnerability.layer, Layer::Default);
+
+    assert_eq!(
+        project.thresholds,
+        PartialThresholds {
+            license: Some(threshold(Action::Break, 0.7)),
+            ..PartialThresholds::default()
+        }
+    );
+    assert!(project.ignored_issues.is_empty());
+}
+
+#[test]
+fn default_values_are_set_explicitly() {
+    let group = PreferenceOverrides {
+        thresholds: PartialThresholds {
+            author: Some(threshold(Action::Break, 0.8)),
+            ..PartialThresholds::default()
+        },
+        ..PreferenceOverrides::default()
+    };
+
+    // The project relaxes the group's author threshold to the default.
+    let project: PreferenceOverrides = serde_json::from_str(
+        r#"{ "thresholds": { "author": { "action": "none", "active": true, "threshold": 0.6 } } }"#,
+    )
+    .unwrap();
+
+    let effective = PreferenceResolver::new()
+        .group(group)
+        .project(project)
+        .resolve();
+    let author = &effective.thresholds.author;
+    assert_eq!(author.layer, Layer::Project);
+    assert_eq!(author.value, Threshold::default());
+    assert_eq!(effective.thresholds.total.layer, Layer::Default);
+
+    // Typed preferences set every domain.
+    let core = CorePreferences::default();
+    let effective = PreferenceResolver::new()
+        .user_settings(&user_settings(), "project")
+        .project((&core).into())
+        .resolve();
+    assert_eq!(effective.thresholds.total.layer, Layer::Project);
+    assert_eq!(effective.thresholds.total.value, Threshold::default());
+    assert_eq!(effective.thresholds.malicious.layer, Layer::Project);
+}
+
+#[test]
+fn later_layers_take_precedence() {
+    let group = PreferenceOverrides {
+        default_label: Some("group".into()),
+        thresholds: PartialThresholds {
+            total: Some(threshold(Action::Warn, 0.6)),
+            author: Some(threshold(Action::Warn, 0.3)),
+            ..PartialThresholds::default()
+        },
+        ignored_issues: vec![
+            ignored("a", IgnoredReason::NotRelevant),
+            ignored("b", IgnoredReason::Other),
+        ],
+    };
+
+    let project = PreferenceOverrides {
+        thresholds: PartialThresholds {
+            total: Some(threshold(Action::Break, 0.8)),
+            ..PartialThresholds::default()
+        },
+        ignored_issues: vec![ignored("a", IgnoredReason::FalsePositive)],
+        ..PreferenceOverrides::default()
+    };
+
+    let overrides = PreferenceOverrides {
+        default_label: Some("cli".into()),
+        ..PreferenceOverrides::default()
+    };
+
+    // Layers are ordered by precedence, not insertion order.
+    let effective = PreferenceResolver::new()
+        .overrides(overrides)
+        .project(project)
+        .group(group)
+        .user_settings(&user_settings(), "project")
+        .resolve();
+
+    let label = effective.default_label.as_ref().unwrap();
+    assert_eq!(
+        (label.value.as_str(), label.layer),
+        ("cli", Layer::Override)
+    );
+
+    let thresholds = &effective.thresholds;
+    assert_eq!(thresholds.total.layer, Layer::Project);
+    assert_eq!(thresholds.total.value, threshold(Action::Break, 0.8));
+    assert_eq!(thresholds.author.layer, Layer::Group);
+    assert_eq!(thresholds.malicious.layer, Layer::User);
+    assert_eq!(thresholds.engineering.layer, Layer::Default);
+
+    // Ignored issues combine, an issue ignored again takes the later reason.
+    let issues: Vec<_> = effective
+        .ignored_issues
+        .iter()
+        .map(|issue| (issue.value.id.as_str(), issue.value.reason, issue.layer))
+        .collect();
+    assert_eq!(
+        issues,
+        [
+            ("b", IgnoredReason::Other, Layer::Group),
+            ("a", IgnoredReason::FalsePositive, Layer::Project),
+        ]
+    );
+
+    let core = effective.core_preferences();
+    assert_eq!(core.default_label.as_deref(), Some("cli"));
+    assert_eq!(core.thresholds, effective.risk_thresholds());
+    assert_eq!(core.ignored_issues.unwrap().len(), 2);
+}
+
+#[test]
+fn user_labels_and_other_projects() {
+    let mut settings = user_settings();
+    settings.projects.insert(
+        "labelled".into(),
+        Setting::DefaultLabel(HashMap::from([(
+            "default_label".to_owned(),
+            "main".to_owned(),
+        )])),
+    );
+
+    let effective = PreferenceResolver::new()
+        .user_settings(&settings, "labelled")
+        .resolve();
+    let label = effective.default_label.unwrap();
+    assert_eq!((label.value.as_str(), label.layer), ("main", Layer::User));
+    assert_eq!(effective.thresholds.total.layer, Layer::Default);
+
+    let effective = PreferenceResolver::new()
+        .user_settings(&settings, "missing")
+        .resolve();
+    assert_eq!(
+        effective.thresholds,
+        PreferenceResolver::new().resolve().thresholds
+    );
+}
+
+#[test]
+fn provenance_is_serialized() {
+    let project = PreferenceOverrides {
+        thresholds: PartialThresholds {
+            vulnerability: Some(threshold(Action::Break, 0.9)),
+            ..PartialThresholds::default()
+        },
+        ..PreferenceOverrides::default()
+    };
+
+    let effective = PreferenceResolver::new().project(project).resolve();
+    let json = serde_json::to_value(&effective).unwrap();
+    assert_eq!(json["thresholds"]["vulnerability"]["layer"], "project");
+    assert_eq!(json["thresholds"]["maliciousCode"]["layer"], "default");
+    assert_eq!(Layer::Override.to_string(), "override");
+}
@@ -0,0 +1,232 @@
+use phylum_types::license::{LicenseExpression, SimpleLicense};
+use phylum_types::policy::license::{LicensePolicy, DENIED_TAG, UNKNOWN_TAG, UNLISTED_TAG};
+use phylum_types::types::package::{RiskDomain, RiskLevel};
+
+fn normalized(license: &str) -> String {
+    LicenseExpression::normalize(license).unwrap().to_string()
+}
+
+#[test]
+fn parse_expressions() {
+    let expression: LicenseExpression = "MIT OR (Apache-2.0 AND BSD-3-Clause)".parse().unwrap();
+    assert_eq!(expression.to_string(), "MIT OR Apache-2.0 AND BSD-3-Clause");
+    assert_eq!(expression.licenses().len(), 3);
+
+    let expression: LicenseExpression = "(MIT or Apache-2.0) and ISC".parse().unwrap();
+    assert_eq!(expression.to_string(), "(MIT OR Apache-2.0) AND ISC");
+
+    let expression: LicenseExpression = "GPL-2.0-or-later WITH Classpath-exception-2.0 OR MIT"
+        .parse()
+        .unwrap();
+    let choices = expression.choices();
+    assert_eq!(choices.len(), 2);
+    assert_eq!(
+        choices[0][0].exception.as_deref(),
+        Some("Classpath-exception-2.0")
+    );
+
+    let expression: LicenseExpression = "LicenseRef-Proprietary AND EPL-1.0+".parse().unwrap();
+    let licenses = expression.licenses();
+    assert!(licenses[0].is_license_ref());
+    assert!(licenses[1].or_later);
+    assert_eq!(
+        expression.to_string(),
+        "LicenseRef-Proprietary AND EPL-1.0+"
+    );
+
+    for invalid in ["", "MIT AND", "(MIT", "MIT Apache-2.0", "WITH MIT", "MIT)"] {
+        assert!(invalid.parse::<LicenseExpression>().is_err(), "{}", invalid);
+    }
+}
+
+#[test]
+fn choices_of_nested_expressions() {
+    let expression: LicenseExpression = "(MIT OR Apache-2.0) AND (ISC OR 0BSD)".parse().unwrap();
+    let choices: Vec<Vec<String>> = expression
+        .choices()
+        .iter()
+        .map(|choice| choice.iter().map(|license| license.to_string()).collect())
+        .collect();
+    assert_eq!(
+        choices,
+        [
+            ["MIT", "ISC"],
+            ["MIT", "0BSD"],
+            ["Apache-2.0", "ISC"],
+            ["Apache-2.0", "0BSD"],
+        ]
+    );
+
+    assert!(expression.is_satisfied_by(|license| license.id != "MIT"));
+    assert!(!expression.is_satisfied_by(|license| license.id == "MIT"));
+}
+
+#[test]
+fn large_expressions_are_evaluated_without_choices() {
+    // 2^40 choices, which are never built.
+    let expression: LicenseExpression = vec!["(MIT OR Apache-2.0)"; 40]
+        .join(" AND ")
+        .parse()
+        .unwrap();
+    assert!(expression.is_satisfied_by(|license| license.id == "Apache-2.0"));
+    assert!(!expression.is_satisfied_by(|license| license.id == "ISC"));
+
+    let policy = LicensePolicy {
+        deny: vec!["MIT".into()],
+        ..LicensePolicy::default()
+    };
+    let expression = expression.to_string();
+    assert_eq!(policy.evaluate(Some(&expression)), None);
+}
+
+#[test]
+fn deep_nesting_is_rejected() {
+    let nested = |depth: usize| format!("{}MIT{}", "(".repeat(depth), ")".repeat(depth));
+    assert!(nested(32).parse::<LicenseExpression>().is_ok());
+
+    let err = nested(33).parse::<LicenseExpression>().unwrap_err();
+    assert!(err.to_string().ends_with("too deeply nested"), "{}", err);
+    assert!(nested(100_000).parse::<LicenseExpression>().is_err());
+    assert!(LicenseExpression::normalize(&nested(100_000)).is_ok());
+}
+
+#[test]
+fn normalize_common_names() {
+    assert_eq!(normalized("Apache 2"), "Apache-2.0");
+    assert_eq!(normalized("MIT License"), "MIT");
+    assert_eq!(normalized("The MIT License (MIT)"), "MIT");
+    assert_eq!(
+        normalized("The Apache Software License, Version 2.0"),
+        "Apache-2.0"
+    );
+    assert_eq!(normalized("apache-2.0"), "Apache-2.0");
+    assert_eq!(normalized("GPL-2.0"), "GPL-2.0-only");
+    assert_eq!(normalized("GPL-2.0+"), "GPL-2.0-or-later");
+    assert_eq!(normalized("MIT/Apache-2.0"), "MIT OR Apache-2.0");
+    assert_eq!(normalized("mit or apache-2.0"), "MIT OR Apache-2.0");
+    assert_eq!(normalized("Acme Corp EULA"), "LicenseRef-Acme-Corp-EULA");
+
+    for missing in [
+        "",
+        " ",
+        "NOASSERTION",
+        "NONE",
+        "UNKNOWN",
+        "Unknown",
+        "n/a",
+        "Other",
+        "SEE LICENSE IN LICENSE.md",
+        "MIT OR UNKNOWN",
+    ] {
+        assert!(
+            LicenseExpression::normalize(missing).is_err(),
+            "{}",
+            missing
+        );
+    }
+}
+
+#[test]
+fn normalize_checks_the_spdx_list() {
+    // Identifiers on the list, including rare and deprecated ones.
+    assert_eq!(normalized("beerware"), "Beerware");
+    assert_eq!(normalized("BSD-3-Clause-Clear"), "BSD-3-Clause-Clear");
+    assert_eq!(normalized("wxWindows"), "wxWindows");
+    assert_eq!(normalized("GFDL-1.3"), "GFDL-1.3-only");
+    assert_eq!(
+        normalized("gpl-2.0-or-later with classpath-exception-2.0"),
+        "GPL-2.0-or-later WITH Classpath-exception-2.0"
+    );
+
+    // Anything else is a reference.
+    assert_eq!(normalized("Proprietary"), "LicenseRef-Proprietary");
+    assert_eq!(normalized("UNLICENSED"), "LicenseRef-UNLICENSED");
+    assert_eq!(normalized("Acme-1.0+"), "LicenseRef-Acme-1.0-or-later");
+    assert_eq!(
+        normalized("MIT OR Commercial"),
+        "MIT OR LicenseRef-Commercial"
+    );
+    assert_eq!(normalized("LicenseRef-Acme"), "LicenseRef-Acme");
+    assert_eq!(
+        normalized("GPL-3.0-only WITH Acme-exception"),
+        "LicenseRef-GPL-3.0-only-WITH-Acme-exception"
+    );
+
+    let expression = LicenseExpression::normalize("Proprietary").unwrap();
+    assert!(expression.licenses()[0].is_license_ref());
+}
+
+#[test]
+fn policy_allows_and_denies() {
+    let policy = LicensePolicy {
+        allow: vec!["MIT".into(), "Apache-2.0".into(), "BSD-*".into()],
+        deny: vec!["GPL-*".into(), "AGPL-*".into()],
+        ..LicensePolicy::default()
+    };
+
+    assert_eq!(policy.evaluate(Some("MIT")), None);
+    assert_eq!(policy.evaluate(Some("bsd-3-clause")), None);
+    assert_eq!(policy.evaluate(Some("Apache 2")), None);
+    assert_eq!(policy.evaluate(Some("GPL-3.0-only OR MIT")), None);
+
+    let issue = policy.evaluate(Some("GPL-3.0-only")).unwrap();
+    assert_eq!(issue.tag.as_deref(), Some(DENIED_TAG));
+    assert_eq!(issue.severity, RiskLevel::High);
+    assert_eq!(issue.domain, RiskDomain::LicenseRisk);
+
+    let issue = policy.evaluate(Some("MIT AND GPL-3.0-only")).unwrap();
+    assert_eq!(issue.tag.as_deref(), Some(DENIED_TAG));
+
+    let issue = policy.evaluate(Some("ISC")).unwrap();
+    assert_eq!(issue.tag.as_deref(), Some(UNLISTED_TAG));
+    assert_eq!(issue.severity, RiskLevel::Medium);
+
+    // Only some choices are denied.
+    let issue = policy.evaluate(Some("GPL-3.0-only OR ISC")).unwrap();
+    assert_eq!(issue.tag.as_deref(), Some(UNLISTED_TAG));
+
+    let issue = policy.evaluate(None).unwrap();
+    assert_eq!(issue.tag.as_deref(), Some(UNKNOWN_TAG));
+    assert_eq!(issue.severity, RiskLevel::Low);
+    for placeholder in ["NOASSERTION", "UNKNOWN", "SEE LICENSE IN LICENSE"] {
+        let issue = policy.evaluate(Some(placeholder)).unwrap();
+        assert_eq!(issue.tag.as_deref(), Some(UNKNOWN_TAG), "{}", placeholder);
+    }
+
+    // Unknown licenses are references, which the allow list doesn't match.
+    let issue = policy.evaluate(Some("Proprietary")).unwrap();
+    assert_eq!(issue.tag.as_deref(), Some(UNLISTED_TAG));
+}
+
+#[test]
+fn policy_exceptions_and_defaults() {
+    let policy = LicensePolicy {
+        deny: vec!["GPL-*".into()],
+        unknown_severity: None,
+        ..LicensePolicy::default()
+    };
+    assert_eq!(policy.evaluate(Some("ISC")), None);
+    assert_eq!(policy.evaluate(None), None);
+    assert!(policy.evaluate(Some("GPL-2.0-only")).is_some());
+
+    let policy = LicensePolicy {
+        allow: vec!["GPL-2.0-only WITH Classpath-exception-2.0".into()],
+        ..LicensePolicy::default()
+    };
+    assert_eq!(
+        policy.evaluate(Some("GPL-2.0 WITH Classpath-exception-2.0")),
+        None
+    );
+    assert!(policy.evaluate(Some("GPL-2.0-only")).is_some());
+
+    let policy: LicensePolicy = serde_json::from_str(r#"{"allow": ["MIT"]}"#).unwrap();
+    assert_eq!(policy.deny_severity, RiskLevel::High);
+    assert_eq!(policy.unknown_severity, Some(RiskLevel::Low));
+    assert_eq!(
+        serde_json::to_value(&policy).unwrap()["unlistedSeverity"],
+        "medium"
+    );
+
+    let license = SimpleLicense::new("MIT");
+    assert_eq!(license.to_string(), "MIT");
+}
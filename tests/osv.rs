@@ -0,0 +1,224 @@
+mod common;
+
+use chrono::DateTime;
+use phylum_types::formats::osv::{
+    self, Advisory, AdvisoryDatabase, Event, Range, RangeType, SeverityType,
+};
+use phylum_types::types::job::JobStatusResponse;
+use phylum_types::types::package::{
+    PackageDescriptor, PackageStatusExtended, PackageType, RiskDomain, RiskLevel, Vulnerability,
+};
+
+use common::{fixture, job};
+
+fn advisory(name: &str) -> Advisory {
+    Advisory::from_json(&fixture(&format!("osv/{name}.json"))).unwrap()
+}
+
+fn package(name: &str, version: &str, package_type: PackageType) -> PackageDescriptor {
+    PackageDescriptor {
+        name: name.into(),
+        version: version.into(),
+        package_type,
+    }
+}
+
+#[test]
+fn advisory_to_vulnerability() {
+    let advisory = advisory("GHSA-xvch-5gv4-984h");
+    let vulnerability = advisory.vulnerability();
+    assert_eq!(vulnerability.cve, ["CVE-2021-44906"]);
+    assert_eq!(vulnerability.risk_level, RiskLevel::Critical);
+    assert_eq!(vulnerability.title, "Prototype Pollution in minimist");
+    assert_eq!(
+        vulnerability.remediation,
+        "Upgrade to a fixed version: 0.2.4, 1.2.6."
+    );
+
+    let issue = advisory.issue();
+    assert_eq!(issue.tag.as_deref(), Some("GHSA-xvch-5gv4-984h"));
+    assert_eq!(issue.domain, RiskDomain::Vulnerabilities);
+    assert_eq!(issue.severity, RiskLevel::Critical);
+
+    let issue = self::advisory("MAL-2022-1").issue();
+    assert_eq!(issue.domain, RiskDomain::Malicious);
+    assert_eq!(issue.severity, RiskLevel::Medium);
+}
+
+#[test]
+fn severity_and_risk_level_share_a_score() {
+    // Temporal metrics lower the score to 6.0, but the base score is 7.5.
+    let mut advisory = advisory("GHSA-xvch-5gv4-984h");
+    advisory.database_specific = None;
+    advisory.severity[0].score =
+        "CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:N/A:N/E:U/RL:O/RC:U".into();
+
+    let vulnerability = advisory.vulnerability();
+    assert_eq!(vulnerability.base_severity, 7.5);
+    assert_eq!(vulnerability.risk_level, RiskLevel::High);
+    assert_eq!(advisory.issue().severity, RiskLevel::High);
+
+    // A base score from the database decides the level as well.
+    advisory.database_specific = Some(serde_json::json!({"base_severity": 3.1}));
+    let vulnerability = advisory.vulnerability();
+    assert_eq!(vulnerability.base_severity, 3.1);
+    assert_eq!(vulnerability.risk_level, RiskLevel::Low);
+
+    // CVSS v2 vectors aren't scored.
+    advisory.database_specific = None;
+    advisory.severity[0].severity_type = SeverityType::CvssV2;
+    advisory.severity[0].score = "AV:N/AC:L/Au:N/C:P/I:P/A:P".into();
+    let vulnerability = advisory.vulnerability();
+    assert_eq!(vulnerability.base_severity, 0.0);
+    assert_eq!(vulnerability.risk_level, RiskLevel::Medium);
+}
+
+#[test]
+fn ranges() {
+    let minimist = advisory("GHSA-xvch-5gv4-984h");
+    for (version, affected) in [
+        ("0.0.1", true),
+        ("0.2.3", true),
+        ("0.2.4", false),
+        ("1.0.0", true),
+        ("1.2.5", true),
+        ("1.2.6", false),
+        ("1.2.8", false),
+    ] {
+        let package = package("minimist", version, PackageType::Npm);
+        assert_eq!(minimist.affects(&package), affected, "{}", version);
+    }
+    assert!(!minimist.affects(&package("minimist", "1.2.5", PackageType::PyPi)));
+
+    // PyPI names are normalized and versions follow PEP 440.
+    let django = advisory("PYSEC-2021-19");
+    for (version, affected) in [
+        ("2.1", false),
+        ("2.2.0", true),
+        ("2.2.18", true),
+        ("2.2.19", false),
+        ("3.0.12", true),
+        ("3.0.13", false),
+        ("3.1.6", true),
+        ("3.1.7", false),
+    ] {
+        let package = package("django", version, PackageType::PyPi);
+        assert_eq!(django.affects(&package), affected, "{}", version);
+    }
+
+    let range = Range {
+        range_type: RangeType::Semver,
+        repo: None,
+        events: vec![
+            Event::Limit("2.0.0".into()),
+            Event::Introduced("1.0.0".into()),
+        ],
+    };
+    assert!(range.includes(&PackageType::Golang, "v1.5.0"));
+    assert!(!range.includes(&PackageType::Golang, "v0.9.0"));
+    assert!(!range.includes(&PackageType::Golang, "v2.1.0"));
+}
+
+#[test]
+fn database_matches_job() {
+    let database: AdvisoryDatabase = [
+        "GHSA-xvch-5gv4-984h",
+        "PYSEC-2021-19",
+        "MAL-2022-1",
+        "withdrawn",
+    ]
+    .iter()
+    .map(|name| advisory(name))
+    .collect();
+    assert_eq!(database.len(), 3);
+
+    let job: JobStatusResponse<PackageStatusExtended> = job();
+    let mut matched = Vec::new();
+    for status in &job.packages {
+        let descriptor = package(
+            &status.basic_status.name,
+            &status.basic_status.version,
+            status.package_type.clone(),
+        );
+        for issue in database.issues(&descriptor) {
+            matched.push((descriptor.name.clone(), issue.tag.unwrap()));
+        }
+    }
+    assert_eq!(
+        matched,
+        [
+            ("event-stream".to_string(), "MAL-2022-1".to_string()),
+            ("minimist".to_string(), "GHSA-xvch-5gv4-984h".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn export_roundtrip() {
+    let modified = DateTime::from_timestamp(1678406400, 0).unwrap();
+    let vulnerability = Vulnerability {
+        cve: vec!["CVE-2021-44906".into(), "CVE-2021-0001".into()],
+        base_severity: 9.8,
+        risk_level: RiskLevel::Critical,
+        title: "Prototype Pollution".into(),
+        description: "Prototype pollution in setKey().".into(),
+        remediation: "Upgrade to 1.2.6.".into(),
+    };
+    let advisory = Advisory::from_vulnerability("CVE-2021-44906", &vulnerability, modified);
+    assert_eq!(advisory.aliases, ["CVE-2021-0001"]);
+
+    let json: serde_json::Value = serde_json::from_str(&advisory.to_json()).unwrap();
+    assert_eq!(json["id"], "CVE-2021-44906");
+    assert_eq!(json["modified"], "2023-03-10T00:00:00Z");
+    assert_eq!(json["database_specific"]["severity"], "critical");
+
+    let parsed = Advisory::from_json(&advisory.to_json()).unwrap();
+    assert_eq!(parsed, advisory);
+    assert_eq!(parsed.vulnerability(), vulnerability);
+
+    let job: JobStatusResponse<PackageStatusExtended> = job();
+    let minimist = &job.packages[1];
+    let descriptor = package(
+        &minimist.basic_status.name,
+        &minimist.basic_status.version,
+        minimist.package_type.clone(),
+    );
+    let issue = &minimist.issues[0].issue;
+    let advisory = Advisory::from_issue(issue, &descriptor, modified).unwrap();
+    assert_eq!(advisory.id, "CVE-2021-44906");
+    assert_eq!(
+        advisory.affected[0].package.purl.as_deref(),
+        Some("pkg:npm/minimist@1.2.5")
+    );
+    assert!(advisory.affects(&descriptor));
+    assert_eq!(&advisory.issue().severity, &issue.severity);
+
+    let author_issue = &job.packages[0].issues[1].issue;
+    assert!(Advisory::from_issue(author_issue, &descriptor, modified).is_none());
+}
+
+#[test]
+fn ecosystems() {
+    for package_type in [
+        PackageType::Npm,
+        PackageType::PyPi,
+        PackageType::Maven,
+        PackageType::RubyGems,
+        PackageType::Nuget,
+        PackageType::Cargo,
+        PackageType::Golang,
+        PackageType::Composer,
+        PackageType::Hex,
+        PackageType::Pub,
+        PackageType::Swift,
+    ] {
+        assert_eq!(
+            osv::package_type(osv::ecosystem(&package_type)),
+            package_type
+        );
+    }
+    assert_eq!(
+        osv::package_type("Debian:11"),
+        PackageType::Other("Debian".into())
+    );
+}
@@ -0,0 +1,202 @@
+use std::collections::HashSet;
+
+use phylum_types::error::Error;
+use phylum_types::types::job::SubmitPackageRequest;
+use phylum_types::types::package::{PackageDescriptor, PackageDescriptorAndLockfile, PackageType};
+use phylum_types::types::package_name::PackageName;
+use uuid::Uuid;
+
+fn canonical(package_type: PackageType, name: &str) -> String {
+    match PackageName::new(package_type, name) {
+        Ok(name) => name.canonical().into(),
+        Err(err) => panic!("{}", err),
+    }
+}
+
+fn reason(package_type: PackageType, name: &str) -> String {
+    match PackageName::new(package_type, name) {
+        Err(Error::InvalidName { reason, .. }) => reason,
+        result => panic!("{name:?} was accepted: {:?}", result),
+    }
+}
+
+#[test]
+fn pypi_names_follow_pep_503() {
+    let cases = [
+        ("Django", "django"),
+        ("zope.interface", "zope-interface"),
+        ("Zope_Interface", "zope-interface"),
+        ("typing__extensions", "typing-extensions"),
+        ("ruamel.yaml.clib", "ruamel-yaml-clib"),
+    ];
+    for (name, expected) in cases {
+        assert_eq!(canonical(PackageType::PyPi, name), expected, "{name}");
+    }
+
+    assert_eq!(
+        reason(PackageType::PyPi, "-requests"),
+        "name must start and end with a letter or digit"
+    );
+    assert_eq!(
+        reason(PackageType::PyPi, "requests!"),
+        "name contains invalid characters"
+    );
+}
+
+#[test]
+fn npm_scope_rules() {
+    assert_eq!(canonical(PackageType::Npm, "@Types/Node"), "@types/node");
+    assert_eq!(canonical(PackageType::Npm, "left-pad"), "left-pad");
+    assert_eq!(canonical(PackageType::Npm, "lodash.merge"), "lodash.merge");
+
+    let invalid = [
+        ("@/pkg", "scope is empty"),
+        ("@types", "scoped name must be `@scope/name`"),
+        ("@types/", "name is empty"),
+        ("@types/node/extra", "name contains invalid characters"),
+        ("@ty pes/node", "name contains whitespace"),
+        (".hidden", "name can't start with `.` or `_`"),
+        ("_private", "name can't start with `.` or `_`"),
+        ("", "name is empty"),
+    ];
+    for (name, expected) in invalid {
+        assert_eq!(reason(PackageType::Npm, name), expected, "{name}");
+    }
+    assert!(PackageName::new(PackageType::Npm, "a".repeat(215)).is_err());
+}
+
+#[test]
+fn maven_group_and_artifact() {
+    assert_eq!(
+        canonical(PackageType::Maven, "org.apache.commons:commons-lang3"),
+        "org.apache.commons:commons-lang3"
+    );
+
+    for name in ["commons-lang3", ":commons-lang3", "org.apache.commons:"] {
+        assert_eq!(
+            reason(PackageType::Maven, name),
+            "expected `groupId:artifactId`",
+            "{name}"
+        );
+    }
+    assert_eq!(
+        reason(PackageType::Maven, "org.apache:commons:lang3"),
+        "name contains invalid characters"
+    );
+}
+
+#[test]
+fn crates_io_names() {
+    // crates.io treats `-` and `_` and case as equivalent.
+    let serde_json = PackageName::new(PackageType::Cargo, "serde_json").unwrap();
+    let spelled = PackageName::new(PackageType::Cargo, "Serde-JSON").unwrap();
+    assert_eq!(serde_json.canonical(), "serde-json");
+    assert_eq!(serde_json, spelled);
+    assert_eq!(spelled.to_string(), "Serde-JSON");
+
+    let invalid = [
+        ("1password", "name must start with a letter"),
+        ("_serde", "name must start with a letter"),
+        ("serde.json", "name contains invalid characters"),
+    ];
+    for (name, expected) in invalid {
+        assert_eq!(reason(PackageType::Cargo, name), expected, "{name}");
+    }
+    assert!(PackageName::new(PackageType::Cargo, "a".repeat(65)).is_err());
+}
+
+#[test]
+fn names_compare_by_canonical_form() {
+    let names: HashSet<PackageName> = ["Django", "django", "DJANGO"]
+        .iter()
+        .map(|name| PackageName::new(PackageType::PyPi, *name).unwrap())
+        .collect();
+    assert_eq!(names.len(), 1);
+
+    // The same canonical name in another ecosystem is a different package.
+    let npm = PackageName::new(PackageType::Npm, "django").unwrap();
+    assert!(!names.contains(&npm));
+
+    let err = PackageName::new(PackageType::Composer, "monolog").unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Invalid composer package name \"monolog\": expected `vendor/package`"
+    );
+}
+
+#[test]
+fn submitted_packages_are_deduplicated() {
+    let package = |package_type, name: &str, version: &str, lockfile: Option<&str>| {
+        PackageDescriptorAndLockfile {
+            package_descriptor: PackageDescriptor {
+                name: name.into(),
+                version: version.into(),
+                package_type,
+            },
+            lockfile: lockfile.map(String::from),
+        }
+    };
+
+    let mut request = SubmitPackageRequest {
+        packages: vec![
+            package(
+                PackageType::PyPi,
+                "Django",
+                "4.2.7",
+                Some("requirements.txt"),
+            ),
+            package(
+                PackageType::PyPi,
+                "django",
+                "4.2.7",
+                Some("requirements.txt"),
+            ),
+            // Different version, lockfile or ecosystem are kept.
+            package(
+                PackageType::PyPi,
+                "django",
+                "4.2.8",
+                Some("requirements.txt"),
+            ),
+            package(PackageType::PyPi, "django", "4.2.7", Some("poetry.lock")),
+            package(
+                PackageType::Npm,
+                "django",
+                "4.2.7",
+                Some("requirements.txt"),
+            ),
+            // Invalid names are compared as they are spelled.
+            package(PackageType::PyPi, "-bad-", "1.0.0", None),
+            package(PackageType::PyPi, "-BAD-", "1.0.0", None),
+            package(PackageType::PyPi, "-bad-", "1.0.0", None),
+        ],
+        is_user: true,
+        project: Uuid::nil(),
+        label: "main".into(),
+        group_name: None,
+    };
+    request.dedup_packages();
+
+    let remaining: Vec<(&str, &str, Option<&str>)> = request
+        .packages
+        .iter()
+        .map(|package| {
+            (
+                package.package_descriptor.name.as_str(),
+                package.package_descriptor.version.as_str(),
+                package.lockfile.as_deref(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        remaining,
+        [
+            ("Django", "4.2.7", Some("requirements.txt")),
+            ("django", "4.2.8", Some("requirements.txt")),
+            ("django", "4.2.7", Some("poetry.lock")),
+            ("django", "4.2.7", Some("requirements.txt")),
+            ("-bad-", "1.0.0", None),
+            ("-BAD-", "1.0.0", None),
+        ]
+    );
+}
@@ -0,0 +1,138 @@
+use std::convert::TryFrom;
+
+use phylum_types::types::package::{PackageDescriptor, PackageType};
+use phylum_types::types::package_url::PackageUrlDescriptor;
+use proptest::prelude::*;
+
+fn descriptor(package_type: PackageType, name: &str, version: &str) -> PackageDescriptor {
+    PackageDescriptor {
+        name: name.into(),
+        version: version.into(),
+        package_type,
+    }
+}
+
+#[test]
+fn ecosystem_specific_names() {
+    let cases = [
+        (
+            descriptor(PackageType::Npm, "@angular/core", "12.0.0"),
+            "pkg:npm/%40angular/core@12.0.0",
+        ),
+        (
+            descriptor(
+                PackageType::Maven,
+                "org.apache.commons:commons-lang3",
+                "3.12.0",
+            ),
+            "pkg:maven/org.apache.commons/commons-lang3@3.12.0",
+        ),
+        (
+            descriptor(PackageType::PyPi, "Ruamel.YAML", "0.17.21"),
+            "pkg:pypi/ruamel-yaml@0.17.21",
+        ),
+        (
+            descriptor(PackageType::Golang, "github.com/gorilla/mux", "v1.8.0"),
+            "pkg:golang/github.com/gorilla/mux@v1.8.0",
+        ),
+        (
+            descriptor(PackageType::RubyGems, "rails", "7.0.4"),
+            "pkg:gem/rails@7.0.4",
+        ),
+    ];
+
+    for (descriptor, purl) in cases {
+        assert_eq!(descriptor.to_purl().unwrap(), purl);
+    }
+
+    let parsed = PackageDescriptor::try_from("pkg:pypi/Ruamel.YAML@0.17.21").unwrap();
+    assert_eq!(parsed.name, "ruamel-yaml");
+}
+
+#[test]
+fn qualifiers() {
+    let purl = "pkg:maven/org.example/lib@1.0.0?classifier=sources&repository_url=https://repo.example.com/&type=jar";
+    let descriptor = PackageUrlDescriptor::try_from(purl).unwrap();
+
+    assert_eq!(descriptor.package_descriptor.name, "org.example:lib");
+    assert_eq!(descriptor.classifier.as_deref(), Some("sources"));
+    assert_eq!(
+        descriptor.repository_url.as_deref(),
+        Some("https://repo.example.com/")
+    );
+    assert_eq!(descriptor.artifact_type.as_deref(), Some("jar"));
+    assert_eq!(descriptor.to_purl().unwrap(), purl);
+}
+
+#[test]
+fn invalid_purls() {
+    assert!(PackageDescriptor::try_from("pkg:npm/left-pad").is_err());
+    assert!(PackageDescriptor::try_from("pkg:unknown/left-pad@1.0.0").is_err());
+    assert!(PackageDescriptor::try_from("https://example.com").is_err());
+    assert!(descriptor(PackageType::Maven, "no-group", "1.0.0")
+        .to_purl()
+        .is_err());
+}
+
+fn version() -> impl Strategy<Value = String> {
+    "[0-9]{1,3}\\.[0-9]{1,3}\\.[0-9]{1,3}(-[a-z0-9.]{1,8})?(\\+[a-z0-9]{1,4})?"
+}
+
+fn descriptors() -> impl Strategy<Value = PackageDescriptor> {
+    let name_and_type = prop_oneof![
+        "(@[a-z][a-z0-9-]{0,10}/)?[a-z][a-z0-9._-]{0,20}".prop_map(|n| (PackageType::Npm, n)),
+        "[a-z][a-z0-9.]{0,10}[a-z0-9]:[a-zA-Z][a-zA-Z0-9_.-]{0,15}"
+            .prop_map(|n| (PackageType::Maven, n)),
+        "[A-Za-z][A-Za-z0-9._-]{0,15}[A-Za-z0-9]".prop_map(|n| (PackageType::PyPi, n)),
+        "[a-z]{2,8}\\.(com|org)(/[a-zA-Z0-9_.-]{1,10}){1,3}".prop_map(|n| (PackageType::Golang, n)),
+        "[a-z][a-z0-9_-]{0,15}/[a-z][a-z0-9_-]{0,15}".prop_map(|n| (PackageType::Composer, n)),
+        "[a-zA-Z][a-zA-Z0-9_-]{0,20}".prop_map(|n| (PackageType::Cargo, n)),
+        "[a-zA-Z][a-zA-Z0-9_.-]{0,20}".prop_map(|n| (PackageType::RubyGems, n)),
+        "[a-zA-Z][a-zA-Z0-9_.-]{0,20}".prop_map(|n| (PackageType::Nuget, n)),
+    ];
+
+    (name_and_type, version()).prop_map(|((package_type, name), version)| PackageDescriptor {
+        name,
+        version,
+        package_type,
+    })
+}
+
+fn canonical(mut descriptor: PackageDescriptor) -> PackageDescriptor {
+    if descriptor.package_type == PackageType::PyPi {
+        descriptor.name = descriptor.name.to_lowercase().replace(['_', '.'], "-");
+        while descriptor.name.contains("--") {
+            descriptor.name = descriptor.name.replace("--", "-");
+        }
+    }
+    descriptor
+}
+
+proptest! {
+    #[test]
+    fn descriptor_round_trip(descriptor in descriptors()) {
+        let purl = descriptor.to_purl().unwrap();
+        let parsed = PackageDescriptor::try_from(purl.as_str()).unwrap();
+
+        prop_assert_eq!(&parsed, &canonical(descriptor));
+        prop_assert_eq!(parsed.to_purl().unwrap(), purl);
+    }
+
+    #[test]
+    fn qualifier_round_trip(
+        descriptor in descriptors(),
+        repository_url in proptest::option::of("https://[a-z]{1,8}\\.example\\.com/[a-z/]{0,10}"),
+        classifier in proptest::option::of("[a-z0-9]{1,8}"),
+        artifact_type in proptest::option::of("(jar|pom|war|zip)"),
+    ) {
+        let descriptor = PackageUrlDescriptor {
+            package_descriptor: canonical(descriptor),
+            repository_url,
+            classifier,
+            artifact_type,
+        };
+
+        let purl = descriptor.to_purl().unwrap();
+        prop_assert_eq!(PackageUrlDescriptor::try_from(purl.as_str()).unwrap(), descriptor);
+    }
+}
@@ -0,0 +1,557 @@
+use std::collections::HashMap;
+
+use chrono::{TimeZone, Utc};
+use phylum_types::error::Error;
+use phylum_types::policy::ignore::{Suppression, SuppressionApplicator};
+use phylum_types::policy::rules::{Condition, RuleSet, RuleSubject, SubjectIssue};
+use phylum_types::policy::{evaluate, ThresholdFailure};
+use phylum_types::types::common::Status;
+use phylum_types::types::job::Action;
+use phylum_types::types::package::{
+    IgnoredReason, Issue, IssueStatus, PackageDescriptor, PackageStatus, PackageStatusExtended,
+    PackageType, RiskDomain, RiskLevel, RiskType,
+};
+use phylum_types::types::preferences::{IgnoredIssue, RiskThresholds};
+use phylum_types::types::user_settings::Threshold;
+
+fn package(
+    name: &str,
+    total: f64,
+    vectors: &[(&str, f64)],
+    issues: Vec<IssueStatus>,
+) -> PackageStatusExtended {
+    PackageStatusExtended {
+        basic_status: PackageStatus {
+            purl: None,
+            name: name.into(),
+            version: "1.0.0".into(),
+            status: Status::Complete,
+            last_updated: 0,
+            license: None,
+            package_score: Some(total),
+            num_dependencies: 0,
+            num_vulnerabilities: None,
+        },
+        package_type: PackageType::Npm,
+        risk_vectors: vectors
+            .iter()
+            .map(|(key, score)| (key.to_string(), *score))
+            .collect(),
+        dependencies: HashMap::new(),
+        issues,
+    }
+}
+
+fn issue(id: &str, domain: RiskDomain, severity: RiskLevel, ignored: IgnoredReason) -> IssueStatus {
+    IssueStatus {
+        issue: Issue {
+            tag: Some(format!("{id}-tag")),
+            id: Some(id.into()),
+            title: id.into(),
+            description: String::new(),
+            severity,
+            domain,
+        },
+        ignored,
+    }
+}
+
+fn threshold(action: &str, threshold: f32) -> Threshold {
+    Threshold {
+        action: action.parse().unwrap(),
+        active: true,
+        threshold,
+    }
+}
+
+fn inactive() -> Threshold {
+    Threshold {
+        action: Action::Break,
+        active: false,
+        threshold: 1.,
+    }
+}
+
+fn thresholds(total: Threshold, vulnerability: Threshold, malicious: Threshold) -> RiskThresholds {
+    RiskThresholds {
+        total,
+        vulnerability,
+        malicious,
+        author: inactive(),
+        engineering: inactive(),
+        license: inactive(),
+    }
+}
+
+fn failure(
+    name: &str,
+    risk_type: RiskType,
+    score: f32,
+    cutoff: f32,
+    action: Action,
+) -> ThresholdFailure {
+    ThresholdFailure {
+        package: PackageDescriptor {
+            name: name.into(),
+            version: "1.0.0".into(),
+            package_type: PackageType::Npm,
+        },
+        risk_type,
+        score,
+        threshold: cutoff,
+        action,
+    }
+}
+
+#[test]
+fn verdicts() {
+    let vulnerable = || {
+        package(
+            "vulnerable",
+            0.35,
+            &[("vulnerability", 0.35), ("malicious_code", 1.)],
+            vec![issue(
+                "CVE-1",
+                RiskDomain::Vulnerabilities,
+                RiskLevel::High,
+                IgnoredReason::False,
+            )],
+        )
+    };
+    let malicious = || {
+        package(
+            "malicious",
+            0.1,
+            &[("vulnerability", 1.), ("malicious", 0.1)],
+            vec![issue(
+                "MAL-1",
+                RiskDomain::Malicious,
+                RiskLevel::Critical,
+                IgnoredReason::False,
+            )],
+        )
+    };
+    let clean = || {
+        package(
+            "clean",
+            1.,
+            &[("vulnerability", 1.), ("malicious_code", 1.)],
+            vec![],
+        )
+    };
+
+    let cases = vec![
+        (
+            "no packages",
+            vec![],
+            thresholds(
+                threshold("break", 0.6),
+                threshold("warn", 0.6),
+                threshold("break", 0.6),
+            ),
+            vec![],
+            Action::None,
+            vec![],
+        ),
+        (
+            "clean package passes",
+            vec![clean()],
+            thresholds(
+                threshold("break", 0.6),
+                threshold("break", 0.6),
+                threshold("break", 0.6),
+            ),
+            vec![],
+            Action::None,
+            vec![],
+        ),
+        (
+            "inactive thresholds are skipped",
+            vec![vulnerable()],
+            thresholds(inactive(), inactive(), inactive()),
+            vec![],
+            Action::None,
+            vec![],
+        ),
+        (
+            "score equal to cutoff passes",
+            vec![vulnerable()],
+            thresholds(
+                threshold("break", 0.35),
+                threshold("break", 0.35),
+                inactive(),
+            ),
+            vec![],
+            Action::None,
+            vec![],
+        ),
+        (
+            "failure with action none is reported",
+            vec![vulnerable()],
+            thresholds(inactive(), threshold("none", 0.6), inactive()),
+            vec![],
+            Action::None,
+            vec![failure(
+                "vulnerable",
+                RiskType::Vulnerabilities,
+                0.35,
+                0.6,
+                Action::None,
+            )],
+        ),
+        (
+            "strictest action wins",
+            vec![vulnerable(), malicious(), clean()],
+            thresholds(
+                threshold("warn", 0.5),
+                threshold("warn", 0.6),
+                threshold("break", 0.6),
+            ),
+            vec![],
+            Action::Break,
+            vec![
+                failure("malicious", RiskType::TotalRisk, 0.1, 0.5, Action::Warn),
+                failure(
+                    "malicious",
+                    RiskType::MaliciousRisk,
+                    0.1,
+                    0.6,
+                    Action::Break,
+                ),
+                failure("vulnerable", RiskType::TotalRisk, 0.35, 0.5, Action::Warn),
+                failure(
+                    "vulnerable",
+                    RiskType::Vulnerabilities,
+                    0.35,
+                    0.6,
+                    Action::Warn,
+                ),
+            ],
+        ),
+        (
+            "issue ignored in job",
+            vec![package(
+                "vulnerable",
+                0.35,
+                &[("vulnerability", 0.35)],
+                vec![issue(
+                    "CVE-1",
+                    RiskDomain::Vulnerabilities,
+                    RiskLevel::High,
+                    IgnoredReason::FalsePositive,
+                )],
+            )],
+            thresholds(threshold("break", 0.6), threshold("break", 0.6), inactive()),
+            vec![],
+            Action::None,
+            vec![],
+        ),
+        (
+            "issue ignored in preferences",
+            vec![vulnerable()],
+            thresholds(threshold("break", 0.6), threshold("break", 0.6), inactive()),
+            vec![IgnoredIssue {
+                id: "CVE-1".into(),
+                tag: "CVE-1-tag".into(),
+                reason: IgnoredReason::NotRelevant,
+            }],
+            Action::None,
+            vec![],
+        ),
+        (
+            "remaining issues still count",
+            vec![package(
+                "vulnerable",
+                0.35,
+                &[("vulnerability", 0.35)],
+                vec![
+                    issue(
+                        "CVE-1",
+                        RiskDomain::Vulnerabilities,
+                        RiskLevel::High,
+                        IgnoredReason::Other,
+                    ),
+                    issue(
+                        "CVE-2",
+                        RiskDomain::Vulnerabilities,
+                        RiskLevel::Medium,
+                        IgnoredReason::False,
+                    ),
+                ],
+            )],
+            thresholds(threshold("warn", 0.7), threshold("break", 0.6), inactive()),
+            vec![],
+            Action::Warn,
+            vec![failure(
+                "vulnerable",
+                RiskType::TotalRisk,
+                0.65,
+                0.7,
+                Action::Warn,
+            )],
+        ),
+    ];
+
+    for (name, packages, thresholds, ignored, action, failures) in cases {
+        let verdict = evaluate(&packages, &thresholds, &ignored);
+        assert_eq!(verdict.action, action, "{name}");
+        assert_eq!(verdict.failures, failures, "{name}");
+        assert_eq!(verdict.is_pass(), action == Action::None, "{name}");
+    }
+}
+
+#[test]
+fn incomplete_packages_are_pending() {
+    let mut incomplete = package("incomplete", 0., &[("vulnerability", 0.)], vec![]);
+    incomplete.basic_status.status = Status::Incomplete;
+
+    let thresholds = thresholds(threshold("break", 0.6), threshold("break", 0.6), inactive());
+    let verdict = evaluate(&[incomplete], &thresholds, &[]);
+
+    assert_eq!(verdict.action, Action::None);
+    assert!(verdict.failures.is_empty());
+    assert_eq!(verdict.pending.len(), 1);
+    assert_eq!(verdict.pending[0].name, "incomplete");
+    assert!(!verdict.is_pass());
+}
+
+#[test]
+fn verdict_is_independent_of_package_order() {
+    let packages = vec![
+        package("b", 0.2, &[("vulnerability", 0.2)], vec![]),
+        package("a", 0.3, &[("author", 0.1)], vec![]),
+        package("c", 0.4, &[("vulnerability", 0.4)], vec![]),
+    ];
+    let mut reversed = packages.clone();
+    reversed.reverse();
+
+    let thresholds = thresholds(threshold("warn", 0.5), threshold("break", 0.5), inactive());
+    assert_eq!(
+        evaluate(&packages, &thresholds, &[]),
+        evaluate(&reversed, &thresholds, &[])
+    );
+}
+
+const RULES: &str = r#"
+[[rules]]
+name = "No critical malicious code"
+action = "break"
+when = { issue = { domain = "malicious_code", minSeverity = "critical" } }
+
+[[rules]]
+name = "Copyleft license"
+action = "warn"
+when = { license = "GPL-*" }
+
+[[rules]]
+name = "Abandoned direct dependency"
+action = "break"
+when = { all = ["abandoned", "direct"] }
+
+[[rules]]
+name = "Recently published"
+action = "break"
+when = { publishedWithinDays = 7 }
+"#;
+
+#[test]
+fn rules_from_toml_and_json() {
+    let rules: RuleSet = toml::from_str(RULES).unwrap();
+    assert_eq!(rules.rules.len(), 4);
+    assert_eq!(
+        rules.rules[2].when,
+        Condition::All(vec![Condition::Abandoned, Condition::Direct])
+    );
+
+    let json = serde_json::to_string(&rules).unwrap();
+    assert_eq!(serde_json::from_str::<RuleSet>(&json).unwrap(), rules);
+
+    let schema = serde_json::to_value(schemars::schema_for!(RuleSet)).unwrap();
+    assert!(schema["definitions"]["Condition"].is_object());
+}
+
+#[test]
+fn rule_matches() {
+    let rules: RuleSet = toml::from_str(RULES).unwrap();
+    let now = Utc.with_ymd_and_hms(2023, 3, 10, 0, 0, 0).unwrap();
+
+    let subject = |license: &str, severity: RiskLevel, ignored: bool| RuleSubject {
+        package: PackageDescriptor {
+            name: "left-pad".into(),
+            version: "1.0.0".into(),
+            package_type: PackageType::Npm,
+        },
+        license: Some(license.into()),
+        issues: vec![SubjectIssue {
+            tag: Some("CM0001".into()),
+            domain: Some(RiskDomain::Malicious),
+            severity,
+            ignored,
+        }],
+        scores: vec![(RiskType::TotalRisk, 0.5)],
+        is_abandonware: Some(true),
+        maintainers_recently_changed: None,
+        published: Some(Utc.with_ymd_and_hms(2023, 3, 1, 0, 0, 0).unwrap()),
+        direct: None,
+    };
+
+    let names = |subject: &RuleSubject| -> Vec<String> {
+        rules
+            .evaluate(subject, now)
+            .into_iter()
+            .map(|rule| rule.rule)
+            .collect()
+    };
+
+    assert!(names(&subject("MIT", RiskLevel::High, false)).is_empty());
+    assert_eq!(
+        names(&subject("MIT OR gpl-3.0", RiskLevel::Critical, false)),
+        ["No critical malicious code", "Copyleft license"]
+    );
+    assert!(names(&subject("MIT", RiskLevel::Critical, true)).is_empty());
+    assert_eq!(
+        names(&subject("MIT", RiskLevel::Low, false).with_direct(true)),
+        ["Abandoned direct dependency"]
+    );
+
+    let mut recent = subject("MIT", RiskLevel::Low, false);
+    recent.published = Some(Utc.with_ymd_and_hms(2023, 3, 5, 0, 0, 0).unwrap());
+    let matches = rules.evaluate(&recent, now);
+    assert_eq!(matches.len(), 1);
+    assert_eq!(matches[0].action, Action::Break);
+}
+
+#[test]
+fn suppressions() {
+    let now = Utc.with_ymd_and_hms(2023, 3, 10, 0, 0, 0).unwrap();
+    let suppressions: Vec<Suppression> = serde_json::from_str(
+        r#"[
+            {"id": "CVE-1", "reason": "notRelevant", "package": "left-*", "versions": "<2",
+             "justification": "Not reachable", "approvedBy": "security@example.com"},
+            {"tag": "CVE-2-tag", "reason": "falsePositive", "purl": "pkg:npm/other*"},
+            {"id": "CVE-2", "reason": "other", "expiresAt": "2023-03-01T00:00:00Z"},
+            {"tag": "CVE-4-tag", "reason": "other", "versions": "not a range"},
+            {"id": "CVE-3", "reason": "other"}
+        ]"#,
+    )
+    .unwrap();
+    let mut applicator = SuppressionApplicator::new(suppressions.clone(), now);
+
+    let descriptor = |name: &str, version: &str| PackageDescriptor {
+        name: name.into(),
+        version: version.into(),
+        package_type: PackageType::Npm,
+    };
+    let issues = || {
+        vec![
+            issue(
+                "CVE-1",
+                RiskDomain::Vulnerabilities,
+                RiskLevel::High,
+                IgnoredReason::False,
+            ),
+            issue(
+                "CVE-2",
+                RiskDomain::Vulnerabilities,
+                RiskLevel::Low,
+                IgnoredReason::False,
+            ),
+        ]
+    };
+    let reasons = |issues: &[IssueStatus]| -> Vec<IgnoredReason> {
+        issues.iter().map(|issue| issue.ignored).collect()
+    };
+
+    let mut left_pad = issues();
+    assert_eq!(
+        applicator.apply(&descriptor("left-pad", "1.3.0"), &mut left_pad),
+        1
+    );
+    assert_eq!(
+        reasons(&left_pad),
+        [IgnoredReason::NotRelevant, IgnoredReason::False]
+    );
+
+    let mut newer = issues();
+    assert_eq!(
+        applicator.apply(&descriptor("left-pad", "2.0.0"), &mut newer),
+        0
+    );
+
+    let mut other = issues();
+    assert_eq!(
+        applicator.apply(&descriptor("other-pkg", "1.0.0"), &mut other),
+        1
+    );
+    assert_eq!(
+        reasons(&other),
+        [IgnoredReason::False, IgnoredReason::FalsePositive]
+    );
+
+    let report = applicator.report();
+    assert_eq!(report.expired, [suppressions[2].clone()]);
+    assert_eq!(report.unused, [suppressions[4].clone()]);
+    assert_eq!(report.invalid.len(), 1);
+    assert_eq!(report.invalid[0].suppression, suppressions[3]);
+    assert!(!report.is_empty());
+}
+
+#[test]
+fn suppressions_need_an_issue() {
+    for invalid in [
+        r#"{"reason": "other"}"#,
+        r#"{"reason": "other", "package": "left-pad"}"#,
+        // Unknown fields are rejected instead of silently widening the scope.
+        r#"{"issueId": "CVE-1", "reason": "other"}"#,
+        r#"{"id": "CVE-1", "reason": "other", "version": "<2"}"#,
+    ] {
+        assert!(
+            serde_json::from_str::<Suppression>(invalid).is_err(),
+            "{}",
+            invalid
+        );
+    }
+
+    let schema = serde_json::to_value(schemars::schema_for!(Suppression)).unwrap();
+    assert_eq!(schema["additionalProperties"], false);
+
+    // Suppressions built in code without an id or tag never match.
+    let now = Utc.with_ymd_and_hms(2023, 3, 10, 0, 0, 0).unwrap();
+    let everything = Suppression {
+        id: None,
+        tag: None,
+        reason: IgnoredReason::Other,
+        package: None,
+        versions: None,
+        purl: None,
+        expires_at: None,
+        justification: None,
+        approved_by: None,
+    };
+    let mut applicator = SuppressionApplicator::new(vec![everything.clone()], now);
+    let descriptor = PackageDescriptor {
+        name: "left-pad".into(),
+        version: "1.3.0".into(),
+        package_type: PackageType::Npm,
+    };
+    let mut issues = vec![issue(
+        "CVE-1",
+        RiskDomain::Vulnerabilities,
+        RiskLevel::High,
+        IgnoredReason::False,
+    )];
+    assert_eq!(applicator.apply(&descriptor, &mut issues), 0);
+    assert_eq!(issues[0].ignored, IgnoredReason::False);
+
+    let report = applicator.report();
+    assert_eq!(report.invalid.len(), 1);
+    assert_eq!(report.invalid[0].suppression, everything);
+    assert_eq!(
+        report.invalid[0].reason,
+        "Invalid suppression: needs an `id` or a `tag`"
+    );
+    assert!(matches!(
+        everything.validate(),
+        Err(Error::InvalidSuppression(_))
+    ));
+}
@@ -0,0 +1,112 @@
+mod common;
+
+use std::fs;
+
+use phylum_types::formats::junit::to_junit;
+use phylum_types::formats::markdown::to_markdown;
+use phylum_types::policy::{evaluate, Verdict};
+use phylum_types::types::common::Status;
+use phylum_types::types::job::{Action, JobStatusResponse};
+use phylum_types::types::package::{PackageDescriptor, PackageStatusExtended, RiskType};
+use phylum_types::types::preferences::RiskThresholds;
+
+use common::job;
+
+fn verdict(job: &JobStatusResponse<PackageStatusExtended>) -> Verdict {
+    let thresholds = RiskThresholds::from_project_thresholds(job.thresholds, job.action);
+    evaluate(&job.packages, &thresholds, &[])
+}
+
+/// A job without threshold failures, with a package still processing.
+fn passing_job() -> JobStatusResponse<PackageStatusExtended> {
+    let mut job = job();
+    job.packages.remove(0);
+    job.packages[0].basic_status.status = Status::Incomplete;
+    job.num_incomplete = 1;
+    job.label = None;
+    job
+}
+
+/// Compare a report to its snapshot in `tests/fixtures/reports`.
+///
+/// Run with `UPDATE_SNAPSHOTS=1` to write the snapshot instead.
+fn assert_snapshot(name: &str, actual: &str) {
+    let path = format!(
+        "{}/tests/fixtures/reports/{name}",
+        env!("CARGO_MANIFEST_DIR")
+    );
+    if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+        fs::write(&path, actual).unwrap();
+        return;
+    }
+
+    let expected = fs::read_to_string(&path).unwrap();
+    assert!(
+        expected == actual,
+        "snapshot {} does not match:\n{}",
+        name,
+        actual
+    );
+}
+
+#[test]
+fn junit() {
+    let job = job();
+    assert_snapshot("job.junit.xml", &to_junit(&job, &verdict(&job)));
+}
+
+#[test]
+fn junit_passing() {
+    let job = passing_job();
+    assert_snapshot("passing.junit.xml", &to_junit(&job, &verdict(&job)));
+}
+
+#[test]
+fn markdown() {
+    let job = job();
+    assert_snapshot("job.md", &to_markdown(&job, &verdict(&job)));
+}
+
+#[test]
+fn markdown_passing() {
+    let job = passing_job();
+    assert_snapshot("passing.md", &to_markdown(&job, &verdict(&job)));
+}
+
+#[test]
+fn markdown_reports_failures_with_action() {
+    let job = job();
+    let mut verdict = verdict(&job);
+
+    // A failure without action is not reported.
+    let mut ignored = verdict.failures[1].clone();
+    ignored.package = PackageDescriptor {
+        name: "minimist".into(),
+        version: "1.2.5".into(),
+        package_type: job.packages[1].package_type.clone(),
+    };
+    ignored.risk_type = RiskType::Vulnerabilities;
+    ignored.action = Action::None;
+    verdict.failures.push(ignored);
+
+    // Packages failing a threshold more than once count once.
+    let mut repeated = verdict.failures[0].clone();
+    repeated.action = Action::Warn;
+    verdict.failures.push(repeated);
+
+    let markdown = to_markdown(&job, &verdict);
+    assert!(markdown.contains("❌ **Failed**: 3 thresholds failed."));
+    assert!(markdown.contains("| Total | 0.60 | 0.10 | -0.50 | 1 | 4 |"));
+    assert!(markdown.contains("| Vulnerability | 0.60 | 0.65 | +0.05 | 0 | 1 |"));
+    assert!(!markdown.contains("| minimist@1.2.5 |"));
+}
+
+#[test]
+fn markdown_escaping() {
+    let mut job = passing_job();
+    job.packages[1].issues = job.packages[0].issues.clone();
+    job.packages[1].issues[1].issue.title = "Pipes | and <tags>".into();
+
+    let markdown = to_markdown(&job, &verdict(&job));
+    assert!(markdown.contains("| Pipes \\| and &lt;tags&gt; |"));
+}
@@ -0,0 +1,141 @@
+mod common;
+
+use phylum_types::formats::sarif::to_sarif;
+use phylum_types::types::job::JobStatusResponse;
+use phylum_types::types::package::{
+    PackageDescriptor, PackageDescriptorAndLockfile, PackageStatusExtended, PackageType,
+};
+use serde_json::Value;
+
+use common::{fixture, job};
+
+fn submitted(name: &str, version: &str, lockfile: &str) -> PackageDescriptorAndLockfile {
+    PackageDescriptorAndLockfile {
+        package_descriptor: PackageDescriptor {
+            name: name.into(),
+            version: version.into(),
+            package_type: PackageType::Npm,
+        },
+        lockfile: Some(lockfile.into()),
+    }
+}
+
+fn sarif() -> Value {
+    let job: JobStatusResponse<PackageStatusExtended> = job();
+    let submitted = [
+        submitted("event-stream", "3.3.6", "package-lock.json"),
+        submitted("minimist", "1.2.5", "package-lock.json"),
+        submitted("minimist", "1.2.5", "web/yarn.lock"),
+    ];
+    serde_json::to_value(to_sarif(&job, &submitted)).unwrap()
+}
+
+#[test]
+fn sarif_matches_schema() {
+    let schema: Value = serde_json::from_str(&fixture("sarif/sarif-schema-2.1.0.json")).unwrap();
+    let schema = jsonschema::JSONSchema::compile(&schema).unwrap();
+
+    let sarif = sarif();
+    let errors: Vec<String> = match schema.validate(&sarif) {
+        Ok(()) => Vec::new(),
+        Err(errors) => errors.map(|error| error.to_string()).collect(),
+    };
+    assert!(errors.is_empty(), "{:?}", errors);
+}
+
+#[test]
+fn issues_become_results() {
+    let sarif = sarif();
+    let run = &sarif["runs"][0];
+
+    let rules = run["tool"]["driver"]["rules"].as_array().unwrap();
+    assert_eq!(rules.len(), 5);
+    assert_eq!(rules[0]["id"], "CM0010");
+    assert_eq!(
+        rules[0]["properties"]["tags"],
+        serde_json::json!(["security", "malicious-code"])
+    );
+    assert_eq!(rules[0]["properties"]["security-severity"], "9.5");
+    assert_eq!(rules[1]["id"], "HA0001");
+    assert_eq!(
+        rules[1]["properties"]["tags"],
+        serde_json::json!(["author"])
+    );
+
+    let results = run["results"].as_array().unwrap();
+    assert_eq!(results.len(), 5);
+    assert_eq!(results[0]["level"], "error");
+    assert_eq!(results[2]["level"], "note");
+    assert_eq!(
+        results[0]["locations"][0]["physicalLocation"]["artifactLocation"]["uri"],
+        "package-lock.json"
+    );
+
+    let ignored = &results[3];
+    assert_eq!(ignored["ruleId"], "CVE-2021-44906");
+    assert_eq!(ignored["locations"].as_array().unwrap().len(), 2);
+    assert_eq!(ignored["suppressions"][0]["kind"], "external");
+    assert_eq!(ignored["suppressions"][0]["justification"], "not relevant");
+    assert!(results[4].get("suppressions").is_none());
+}
+
+#[test]
+fn packages_without_lockfile_use_manifest() {
+    let mut job: JobStatusResponse<PackageStatusExtended> = job();
+    let sarif = serde_json::to_value(to_sarif(&job, &[])).unwrap();
+    for result in sarif["runs"][0]["results"].as_array().unwrap() {
+        let locations = result["locations"].as_array().unwrap();
+        assert_eq!(locations.len(), 1);
+        assert_eq!(
+            locations[0]["physicalLocation"]["artifactLocation"]["uri"],
+            "package.json"
+        );
+    }
+
+    // NuGet has no single manifest name, so the location is made up.
+    job.packages[0].package_type = PackageType::Nuget;
+    job.packages[0].basic_status.name = "Newtonsoft.Json".into();
+    let sarif = serde_json::to_value(to_sarif(&job, &[])).unwrap();
+    assert_eq!(
+        sarif["runs"][0]["results"][0]["locations"][0]["physicalLocation"]["artifactLocation"]
+            ["uri"],
+        "nuget/Newtonsoft.Json"
+    );
+}
+
+#[test]
+fn untagged_issues_share_generic_domain_rules() {
+    let mut job: JobStatusResponse<PackageStatusExtended> = job();
+    for package in &mut job.packages {
+        for issue in &mut package.issues {
+            issue.issue.tag = None;
+            issue.issue.id = None;
+        }
+    }
+    let sarif = serde_json::to_value(to_sarif(&job, &[])).unwrap();
+    let rules = sarif["runs"][0]["tool"]["driver"]["rules"]
+        .as_array()
+        .unwrap();
+
+    // Both minimist vulnerabilities share a rule.
+    assert_eq!(rules.len(), 4);
+    let vulnerability = &rules[3];
+    assert_eq!(vulnerability["id"], "vulnerability");
+    assert_eq!(vulnerability["name"], "Vulnerability");
+    assert_eq!(
+        vulnerability["fullDescription"]["text"],
+        "The package has a known vulnerability."
+    );
+    assert_eq!(
+        vulnerability["properties"]["tags"],
+        serde_json::json!(["security", "vulnerability"])
+    );
+
+    let license = &rules[2];
+    assert_eq!(license["id"], "license");
+    assert_eq!(license["name"], "License risk");
+    assert_eq!(
+        license["properties"]["tags"],
+        serde_json::json!(["license"])
+    );
+}
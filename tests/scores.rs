@@ -0,0 +1,137 @@
+//! Scores computed from issues.
+//!
+//! The fixtures under `fixtures/scores` follow the API's response format for
+//! real packages and advisories, but are hand-written: their scores were
+//! derived from the listed severities with the same model as
+//! `RiskScores::from_issues`. These tests check that the conversions apply
+//! that model consistently, not that it matches the scores the API computes.
+
+mod common;
+
+use phylum_types::policy::evaluate;
+use phylum_types::types::job::Action;
+use phylum_types::types::package::{
+    IgnoredReason, Issue, IssueImpacts, Package, PackageStatusExtended, RiskLevel, RiskScores,
+    RiskType,
+};
+use phylum_types::types::preferences::{IgnoredIssue, RiskThresholds};
+use phylum_types::types::user_settings::Threshold;
+
+use common::fixture;
+
+#[test]
+fn package_scores_follow_severities() {
+    for name in ["package_npm.json", "package_pypi.json"] {
+        let package: Package = serde_json::from_str(&fixture(&format!("scores/{}", name))).unwrap();
+        assert_eq!(
+            RiskScores::from(&package.issues[..]),
+            package.risk_scores,
+            "{name}"
+        );
+    }
+}
+
+#[test]
+fn job_scores_follow_severities() {
+    let package: PackageStatusExtended =
+        serde_json::from_str(&fixture("scores/job_package.json")).unwrap();
+    let scores = RiskScores::from(&package.issues[..]);
+
+    let computed = serde_json::to_value(scores).unwrap();
+    for (key, score) in &package.risk_vectors {
+        assert_eq!(computed[key].as_f64(), Some(*score as f32 as f64), "{key}");
+    }
+    assert_eq!(
+        package.basic_status.package_score.map(|score| score as f32),
+        Some(scores.total)
+    );
+
+    let issues: Vec<Issue> = package
+        .issues
+        .iter()
+        .map(|issue| issue.issue.clone())
+        .collect();
+    assert_eq!(RiskScores::from(&issues[..]), scores);
+}
+
+#[test]
+fn ignored_issues_are_skipped() {
+    let mut package: Package = serde_json::from_str(&fixture("scores/package_npm.json")).unwrap();
+
+    package.issues[0].ignored = IgnoredReason::NotRelevant;
+    let scores = RiskScores::from(&package.issues[..]);
+    assert_eq!(scores.vulnerability, 0.65);
+    assert_eq!(scores.engineering, 0.65);
+    assert_eq!(scores.total, 0.65);
+
+    for issue in &mut package.issues {
+        issue.ignored = IgnoredReason::FalsePositive;
+    }
+    let scores = RiskScores::from(&package.issues[..]);
+    assert_eq!(scores, RiskScores::from_issues(None));
+    assert_eq!(scores.total, 1.);
+}
+
+#[test]
+fn impacts_by_severity() {
+    let mut package: Package = serde_json::from_str(&fixture("scores/package_npm.json")).unwrap();
+    package.issues[1].impact = RiskLevel::Info;
+    package.issues[0].ignored = IgnoredReason::Other;
+
+    let all = IssueImpacts::from(&package.issues[..]);
+    assert_eq!((all.info, all.low, all.medium, all.critical), (1, 1, 1, 1));
+    assert_eq!(all.total(), 4);
+
+    let unignored = IssueImpacts::from_list_items(&package.issues, false);
+    assert_eq!(unignored.critical, 0);
+    assert_eq!(unignored.total(), 3);
+
+    let json = serde_json::to_value(IssueImpacts::default()).unwrap();
+    assert!(json.get("info").is_none());
+    let old: IssueImpacts =
+        serde_json::from_str(r#"{"low":1,"medium":0,"high":0,"critical":0}"#).unwrap();
+    assert_eq!(old.info, 0);
+}
+
+#[test]
+fn policy_uses_the_same_scores() {
+    let package: PackageStatusExtended =
+        serde_json::from_str(&fixture("scores/job_package.json")).unwrap();
+    let strict = Threshold {
+        action: Action::Break,
+        active: true,
+        threshold: 1.,
+    };
+    let thresholds = RiskThresholds {
+        total: strict.clone(),
+        author: strict.clone(),
+        engineering: strict.clone(),
+        license: strict.clone(),
+        malicious: strict.clone(),
+        vulnerability: strict,
+    };
+    let failures = |ignored_issues: &[IgnoredIssue]| {
+        let verdict = evaluate(std::slice::from_ref(&package), &thresholds, ignored_issues);
+        let mut scores = RiskScores::from_issues(None);
+        for failure in verdict.failures {
+            *scores.get_mut(failure.risk_type) = failure.score;
+        }
+        scores
+    };
+
+    assert_eq!(failures(&[]), RiskScores::from(&package.issues[..]));
+
+    // Ignoring the malicious code issue leaves the author issue as lowest.
+    let malicious = &package.issues[0].issue;
+    let ignored = [IgnoredIssue {
+        id: malicious.id.clone().unwrap(),
+        tag: malicious.tag.clone().unwrap(),
+        reason: IgnoredReason::FalsePositive,
+    }];
+    let mut remaining = package.clone();
+    remaining.issues.remove(0);
+    let scores = failures(&ignored);
+    assert_eq!(scores, RiskScores::from(&remaining.issues[..]));
+    assert_eq!(scores.get(RiskType::MaliciousRisk), 1.);
+    assert_eq!(scores.total, 0.35);
+}
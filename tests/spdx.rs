@@ -0,0 +1,255 @@
+mod common;
+
+use chrono::{TimeZone, Utc};
+use phylum_types::formats::spdx::SpdxDocument;
+use phylum_types::types::package::{Package, PackageDescriptor, PackageType};
+
+use common::fixture;
+
+fn package(
+    name: &str,
+    version: &str,
+    license: Option<&str>,
+    dependencies: Vec<Package>,
+) -> Package {
+    Package {
+        name: name.into(),
+        version: version.into(),
+        registry: "npm".into(),
+        license: license.map(String::from),
+        dependencies: Some(dependencies),
+        ..Package::default()
+    }
+}
+
+fn document() -> SpdxDocument {
+    let minimist = package("minimist", "1.2.5", Some("MIT"), vec![]);
+    let packages = vec![
+        package(
+            "@storefront/cart",
+            "2.0.0",
+            Some("MIT OR Apache-2.0"),
+            vec![minimist.clone(), package("left-pad", "1.3.0", None, vec![])],
+        ),
+        package("mkdirp", "0.5.5", Some("MIT"), vec![minimist]),
+    ];
+
+    let created = Utc.with_ymd_and_hms(2023, 3, 10, 0, 0, 0).unwrap();
+    let mut document = SpdxDocument::new(
+        "storefront",
+        "https://example.com/spdx/storefront-59482a54",
+        created,
+    );
+    document.add_packages(&packages);
+    document
+}
+
+#[test]
+fn packages_and_relationships() {
+    let document = document();
+    assert_eq!(document.creation_info.created, "2023-03-10T00:00:00Z");
+
+    let ids: Vec<&str> = document
+        .packages
+        .iter()
+        .map(|package| package.spdx_id.as_str())
+        .collect();
+    assert_eq!(
+        ids,
+        [
+            "SPDXRef-Package-npm--storefront-cart-2.0.0",
+            "SPDXRef-Package-npm-minimist-1.2.5",
+            "SPDXRef-Package-npm-left-pad-1.3.0",
+            "SPDXRef-Package-npm-mkdirp-0.5.5",
+        ]
+    );
+
+    let cart = &document.packages[0];
+    assert_eq!(cart.purl(), Some("pkg:npm/%40storefront/cart@2.0.0"));
+    assert_eq!(cart.license_declared.as_deref(), Some("MIT OR Apache-2.0"));
+    assert_eq!(
+        document.packages[2].license_concluded.as_deref(),
+        Some("NOASSERTION")
+    );
+
+    let relationships: Vec<String> = document
+        .relationships
+        .iter()
+        .map(|relationship| {
+            format!(
+                "{} {} {}",
+                relationship.spdx_element_id,
+                relationship.relationship_type,
+                relationship.related_spdx_element
+            )
+        })
+        .collect();
+    assert_eq!(relationships, [
+        "SPDXRef-Package-npm--storefront-cart-2.0.0 DEPENDS_ON SPDXRef-Package-npm-minimist-1.2.5",
+        "SPDXRef-Package-npm--storefront-cart-2.0.0 DEPENDS_ON SPDXRef-Package-npm-left-pad-1.3.0",
+        "SPDXRef-DOCUMENT DESCRIBES SPDXRef-Package-npm--storefront-cart-2.0.0",
+        "SPDXRef-Package-npm-mkdirp-0.5.5 DEPENDS_ON SPDXRef-Package-npm-minimist-1.2.5",
+        "SPDXRef-DOCUMENT DESCRIBES SPDXRef-Package-npm-mkdirp-0.5.5",
+    ]);
+}
+
+#[test]
+fn json_round_trip() {
+    let document = document();
+    let json = document.to_json();
+    assert!(json.contains("\"SPDXID\": \"SPDXRef-DOCUMENT\""));
+    assert_eq!(SpdxDocument::from_json(&json).unwrap(), document);
+}
+
+#[test]
+fn tag_value_round_trip() {
+    let document = document();
+    let text = document.to_tag_value();
+    assert!(text.starts_with("SPDXVersion: SPDX-2.3\nDataLicense: CC0-1.0\n"));
+    assert!(text.contains("ExternalRef: PACKAGE-MANAGER purl pkg:npm/minimist@1.2.5\n"));
+    assert_eq!(SpdxDocument::from_tag_value(&text).unwrap(), document);
+
+    let vendor = SpdxDocument::from_tag_value(&fixture("spdx/vendor.spdx")).unwrap();
+    assert_eq!(
+        SpdxDocument::from_tag_value(&vendor.to_tag_value()).unwrap(),
+        vendor
+    );
+    assert_eq!(SpdxDocument::from_json(&vendor.to_json()).unwrap(), vendor);
+}
+
+#[test]
+fn descriptors_from_document() {
+    let vendor = SpdxDocument::from_tag_value(&fixture("spdx/vendor.spdx")).unwrap();
+    assert_eq!(vendor.creation_info.creators.len(), 2);
+    assert_eq!(vendor.relationships.len(), 3);
+    assert!(vendor.packages[2].files_analyzed);
+
+    let pypi = |name: &str, version: &str| PackageDescriptor {
+        name: name.into(),
+        version: version.into(),
+        package_type: PackageType::PyPi,
+    };
+    assert_eq!(
+        vendor.package_descriptors(),
+        [pypi("requests", "2.31.0"), pypi("urllib3", "2.0.4"),]
+    );
+
+    let descriptors = document().package_descriptors();
+    assert_eq!(descriptors.len(), 4);
+    assert_eq!(descriptors[0].name, "@storefront/cart");
+
+    assert!(vendor.invalid_purls().is_empty());
+
+    assert!(SpdxDocument::from_tag_value("SPDXVersion: SPDX-3.0\n").is_err());
+    assert!(SpdxDocument::from_tag_value("SPDXVersion SPDX-2.3\n").is_err());
+}
+
+#[test]
+fn file_and_snippet_sections_are_skipped() {
+    let document = SpdxDocument::from_tag_value(&fixture("spdx/files.spdx")).unwrap();
+    assert_eq!(document.spdx_id, "SPDXRef-DOCUMENT");
+
+    // File SPDXIDs don't replace the ID of the package before them.
+    let ids: Vec<&str> = document
+        .packages
+        .iter()
+        .map(|package| package.spdx_id.as_str())
+        .collect();
+    assert_eq!(
+        ids,
+        [
+            "SPDXRef-Package-billing-worker",
+            "SPDXRef-Package-celery",
+            "SPDXRef-Package-kombu",
+            "SPDXRef-Package-redis",
+        ]
+    );
+    assert_eq!(
+        document.packages[0].license_declared.as_deref(),
+        Some("LicenseRef-Vendor-EULA")
+    );
+    assert_eq!(document.has_extracted_licensing_infos.len(), 1);
+    assert_eq!(
+        document.has_extracted_licensing_infos[0].name.as_deref(),
+        Some("Vendor EULA")
+    );
+    assert_eq!(document.relationships.len(), 5);
+
+    // The package URL without a version doesn't stop the import, but is
+    // reported.
+    let pypi = |name: &str, version: &str| PackageDescriptor {
+        name: name.into(),
+        version: version.into(),
+        package_type: PackageType::PyPi,
+    };
+    assert_eq!(
+        document.package_descriptors(),
+        [pypi("celery", "5.3.1"), pypi("redis", "4.6.0")]
+    );
+    let invalid: Vec<&str> = document
+        .invalid_purls()
+        .iter()
+        .map(|(purl, _)| *purl)
+        .collect();
+    assert_eq!(invalid, ["pkg:pypi/kombu"]);
+}
+
+#[test]
+fn declared_licenses_are_spdx_expressions() {
+    let packages = vec![
+        package("cart", "2.0.0", Some("MIT/Apache-2.0"), vec![]),
+        package("minimist", "1.2.5", Some("UNKNOWN"), vec![]),
+        package("vendored", "1.0.0", Some("Acme Commercial License"), vec![]),
+        package("mkdirp", "0.5.5", Some("apache-2.0 OR Acme-EULA"), vec![]),
+    ];
+    let created = Utc.with_ymd_and_hms(2023, 3, 10, 0, 0, 0).unwrap();
+    let mut document = SpdxDocument::new("storefront", "https://example.com/spdx/1", created);
+    document.add_packages(&packages);
+
+    let licenses: Vec<(&str, &str)> = document
+        .packages
+        .iter()
+        .map(|package| {
+            (
+                package.license_concluded.as_deref().unwrap(),
+                package.license_declared.as_deref().unwrap(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        licenses,
+        [
+            ("NOASSERTION", "MIT OR Apache-2.0"),
+            ("NOASSERTION", "NOASSERTION"),
+            ("NOASSERTION", "LicenseRef-Acme-Commercial-License"),
+            ("NOASSERTION", "Apache-2.0 OR LicenseRef-Acme-EULA"),
+        ]
+    );
+
+    // Each reference is defined once, with the text the registry reported.
+    let infos: Vec<(&str, &str)> = document
+        .has_extracted_licensing_infos
+        .iter()
+        .map(|info| (info.license_id.as_str(), info.extracted_text.as_str()))
+        .collect();
+    assert_eq!(
+        infos,
+        [
+            (
+                "LicenseRef-Acme-Commercial-License",
+                "Acme Commercial License"
+            ),
+            ("LicenseRef-Acme-EULA", "Acme-EULA"),
+        ]
+    );
+
+    let text = document.to_tag_value();
+    assert!(text.contains(
+        "LicenseID: LicenseRef-Acme-Commercial-License\nExtractedText: <text>Acme Commercial License</text>\n"
+    ));
+    assert_eq!(SpdxDocument::from_tag_value(&text).unwrap(), document);
+    assert_eq!(
+        SpdxDocument::from_json(&document.to_json()).unwrap(),
+        document
+    );
+}
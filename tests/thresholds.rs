@@ -0,0 +1,96 @@
+use phylum_types::error::Error;
+use phylum_types::types::job::Action;
+use phylum_types::types::preferences::RiskThresholds;
+use phylum_types::types::project::ProjectThresholds;
+use phylum_types::types::user_settings::Threshold;
+
+fn project_thresholds() -> ProjectThresholds {
+    ProjectThresholds {
+        author: 0.4,
+        engineering: 0.,
+        license: 0.6,
+        malicious: 0.5,
+        total: 0.7,
+        vulnerability: 0.3,
+    }
+}
+
+#[test]
+fn project_thresholds_round_trip() {
+    for action in [Action::None, Action::Warn, Action::Break] {
+        let thresholds = RiskThresholds::from_project_thresholds(project_thresholds(), action);
+        assert_eq!(thresholds.malicious.threshold, 0.5);
+        assert_eq!(thresholds.vulnerability.action, action);
+        assert!(!thresholds.engineering.active);
+
+        let (project, converted_action) = thresholds.to_project_thresholds().unwrap();
+        assert_eq!(project, project_thresholds());
+        assert_eq!(converted_action, action);
+
+        assert_eq!(
+            RiskThresholds::from_project_thresholds(project, converted_action),
+            thresholds
+        );
+    }
+}
+
+#[test]
+fn risk_thresholds_round_trip_through_json() {
+    let json = r#"{
+        "total": {"action": "break", "active": true, "threshold": 0.7},
+        "author": {"action": "break", "active": true, "threshold": 0.4},
+        "engineering": {"action": "break", "active": false, "threshold": 0.0},
+        "license": {"action": "break", "active": true, "threshold": 0.6},
+        "malicious": {"action": "break", "active": true, "threshold": 0.5},
+        "vulnerability": {"action": "break", "active": true, "threshold": 0.3}
+    }"#;
+    let thresholds: RiskThresholds = serde_json::from_str(json).unwrap();
+
+    // The legacy `malicious` key is written as `maliciousCode`.
+    let written = serde_json::to_value(&thresholds).unwrap();
+    assert_eq!(written["maliciousCode"]["threshold"], 0.5);
+    assert!(written.get("malicious").is_none());
+    let read: RiskThresholds = serde_json::from_value(written).unwrap();
+    assert_eq!(read, thresholds);
+
+    let (project, action) = thresholds.to_project_thresholds().unwrap();
+    assert_eq!(
+        RiskThresholds::from_project_thresholds(project, action),
+        thresholds
+    );
+}
+
+#[test]
+fn lossy_conversions_are_rejected() {
+    let base = RiskThresholds::from_project_thresholds(project_thresholds(), Action::Break);
+
+    let mut mixed_actions = base.clone();
+    mixed_actions.license.action = Action::Warn;
+
+    let mut inactive_with_cutoff = base.clone();
+    inactive_with_cutoff.total.active = false;
+
+    let mut active_without_cutoff = base;
+    active_without_cutoff.engineering.active = true;
+
+    for thresholds in [mixed_actions, inactive_with_cutoff, active_without_cutoff] {
+        let err = thresholds.to_project_thresholds().unwrap_err();
+        assert!(matches!(err, Error::IncompatibleThresholds(_)), "{:?}", err);
+    }
+
+    // Default thresholds share an action and are all active with a cutoff.
+    assert!(RiskThresholds::default().to_project_thresholds().is_ok());
+}
+
+#[test]
+fn unknown_actions_are_rejected() {
+    let err = "brake".parse::<Action>().unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Unknown action \"brake\", did you mean \"break\"?"
+    );
+    assert_eq!("Warn".parse::<Action>().unwrap(), Action::Warn);
+
+    let threshold = r#"{"action": "brake", "active": true, "threshold": 0.5}"#;
+    assert!(serde_json::from_str::<Threshold>(threshold).is_err());
+}
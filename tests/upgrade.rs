@@ -0,0 +1,143 @@
+mod common;
+
+use phylum_types::types::package::{Package, PackageType, ScoredVersion};
+use phylum_types::types::upgrade::{Recommendation, UpgradeConstraint};
+use phylum_types::types::version::VersionRange;
+
+use common::fixture;
+
+/// minimist 1.2.5 with scores for its other versions.
+fn package(versions: &[(&str, Option<f32>, bool)]) -> Package {
+    let mut package: Package = serde_json::from_str(&fixture("scores/package_npm.json")).unwrap();
+    package.versions = versions
+        .iter()
+        .map(|(version, score, yanked)| ScoredVersion {
+            version: (*version).into(),
+            total_risk_score: *score,
+            yanked: *yanked,
+        })
+        .collect();
+    package
+}
+
+fn recommended(package: &Package, constraint: &UpgradeConstraint) -> Option<String> {
+    package
+        .recommend_upgrade(constraint)
+        .map(|recommendation| recommendation.recommended_version)
+}
+
+#[test]
+fn best_score_and_smallest_upgrade() {
+    let package = package(&[
+        ("1.2.4", Some(0.9), false),
+        ("1.2.5", Some(0.1), false),
+        ("1.2.6", Some(0.65), false),
+        ("1.2.7", Some(0.8), false),
+        ("1.2.8", Some(0.8), false),
+        ("2.0.0", Some(1.), false),
+    ]);
+
+    // Older versions are never recommended, ties go to the smaller upgrade.
+    let recommendation = package
+        .recommend_upgrade(&UpgradeConstraint::SameMajor)
+        .unwrap();
+    assert_eq!(recommendation.current_version, "1.2.5");
+    assert_eq!(recommendation.recommended_version, "1.2.7");
+    assert_eq!(recommendation.current_score, 0.1);
+    assert_eq!(recommendation.recommended_score, 0.8);
+    assert_eq!(recommendation.removed_issues, None);
+
+    assert_eq!(
+        recommended(&package, &UpgradeConstraint::Any).as_deref(),
+        Some("2.0.0")
+    );
+}
+
+#[test]
+fn unusable_versions_are_skipped() {
+    let package = package(&[
+        ("1.2.5", Some(0.1), false),
+        ("1.2.6", Some(0.35), false),
+        ("1.2.7", Some(1.), true),
+        ("1.2.8", None, false),
+        ("1.3.0-beta.1", Some(1.), false),
+        ("not a version", Some(1.), false),
+    ]);
+    assert_eq!(
+        recommended(&package, &UpgradeConstraint::Any).as_deref(),
+        Some("1.2.6")
+    );
+
+    // Ranges naming a pre-release allow it.
+    let range = VersionRange::parse(&PackageType::Npm, "^1.3.0-beta.0").unwrap();
+    assert_eq!(
+        recommended(&package, &UpgradeConstraint::Range(range)).as_deref(),
+        Some("1.3.0-beta.1")
+    );
+    let range = VersionRange::parse(&PackageType::Npm, "~1.2.5").unwrap();
+    assert_eq!(
+        recommended(&package, &UpgradeConstraint::Range(range)).as_deref(),
+        Some("1.2.6")
+    );
+}
+
+#[test]
+fn no_better_version() {
+    let unchanged = package(&[("1.2.5", Some(0.6), false), ("1.2.6", Some(0.6), false)]);
+    assert_eq!(recommended(&unchanged, &UpgradeConstraint::Any), None);
+
+    // Without a score for the current version, the package's score is used.
+    let mut unscored = package(&[("1.2.6", Some(0.35), false)]);
+    let recommendation = unscored.recommend_upgrade(&UpgradeConstraint::Any).unwrap();
+    assert_eq!(recommendation.current_score, 0.1);
+
+    unscored.version = "latest".into();
+    assert_eq!(recommended(&unscored, &UpgradeConstraint::Any), None);
+}
+
+#[test]
+fn removed_issues() {
+    let current = package(&[("1.2.5", Some(0.1), false), ("1.2.6", Some(0.65), false)]);
+
+    // 1.2.6 fixes both prototype pollution issues.
+    let mut fixed = current.clone();
+    fixed.version = "1.2.6".into();
+    fixed.issues.drain(..2);
+
+    let recommendation = current
+        .recommend_upgrade(&UpgradeConstraint::SameMajor)
+        .unwrap()
+        .with_issues(&current, &fixed);
+    let removed = recommendation.removed_issues.unwrap();
+    assert_eq!((removed.critical, removed.medium, removed.low), (1, 1, 0));
+    assert_eq!(
+        recommendation.to_string(),
+        "1.2.5 → 1.2.6 removes 1 critical and 1 medium issues, total 0.10 → 0.65"
+    );
+
+    let recommendation = Recommendation {
+        removed_issues: None,
+        ..recommendation
+    };
+    assert_eq!(
+        recommendation.to_string(),
+        "1.2.5 → 1.2.6, total 0.10 → 0.65"
+    );
+}
+
+#[test]
+fn yanked_is_optional() {
+    let version: ScoredVersion =
+        serde_json::from_str(r#"{"version":"1.2.7","total_risk_score":0.8}"#).unwrap();
+    assert!(!version.yanked);
+    assert!(serde_json::to_value(&version)
+        .unwrap()
+        .get("yanked")
+        .is_none());
+
+    let version = ScoredVersion {
+        yanked: true,
+        ..version
+    };
+    assert_eq!(serde_json::to_value(&version).unwrap()["yanked"], true);
+}
@@ -0,0 +1,116 @@
+mod common;
+
+use phylum_types::error::Error;
+use phylum_types::types::job::Action;
+use phylum_types::types::user_settings::{Setting, UserSettings};
+
+use common::fixture;
+
+const PROJECT: &str = "1c0b6f4c-3b4b-4b4e-9b1a-4c5b8a1e6f3d";
+
+fn project_thresholds(settings: &UserSettings) -> Vec<&str> {
+    let mut names: Vec<&str> = match &settings.projects[PROJECT] {
+        Setting::Project(project) => project.thresholds.keys().map(String::as_str).collect(),
+        setting => panic!("expected project settings, got {:?}", setting),
+    };
+    names.sort_unstable();
+    names
+}
+
+#[test]
+fn legacy_settings_load_as_current() {
+    let current = UserSettings::load(&fixture("user_settings/current.yaml")).unwrap();
+    assert_eq!(current.version, UserSettings::CURRENT_VERSION);
+    assert_eq!(
+        project_thresholds(&current),
+        ["maliciousCode", "total", "vulnerability"]
+    );
+    assert_eq!(
+        current.projects["labels"],
+        Setting::DefaultLabel(
+            vec![("default_label".into(), "main".into())]
+                .into_iter()
+                .collect()
+        )
+    );
+
+    for fixture_name in ["legacy.yaml", "current.yaml"] {
+        let settings =
+            UserSettings::load(&fixture(&format!("user_settings/{fixture_name}"))).unwrap();
+        assert_eq!(settings, current, "{fixture_name}");
+    }
+}
+
+#[test]
+fn missing_version_is_version_one() {
+    let settings = UserSettings::load(&fixture("user_settings/unversioned.yaml")).unwrap();
+    assert_eq!(settings.version, UserSettings::CURRENT_VERSION);
+
+    let thresholds = match &settings.projects[PROJECT] {
+        Setting::Project(project) => &project.thresholds,
+        setting => panic!("expected project settings, got {:?}", setting),
+    };
+    assert_eq!(thresholds["maliciousCode"].action, Action::Warn);
+}
+
+#[test]
+fn migrated_settings_load_unchanged() {
+    let settings = UserSettings::load(&fixture("user_settings/legacy.yaml")).unwrap();
+    let written = serde_yaml::to_string(&settings).unwrap();
+    assert_eq!(UserSettings::load(&written).unwrap(), settings);
+}
+
+#[test]
+fn current_threshold_name_wins() {
+    let settings = format!(
+        r#"
+version: 1
+projects:
+  {PROJECT}:
+    thresholds:
+      malicious:
+        action: warn
+        active: true
+        threshold: 0.5
+      maliciousCode:
+        action: break
+        active: true
+        threshold: 0.9
+"#
+    );
+    let settings = UserSettings::load(&settings).unwrap();
+    assert_eq!(settings.version, 2);
+    assert_eq!(project_thresholds(&settings), ["maliciousCode"]);
+
+    let thresholds = match &settings.projects[PROJECT] {
+        Setting::Project(project) => &project.thresholds,
+        setting => panic!("expected project settings, got {:?}", setting),
+    };
+    assert_eq!(thresholds["maliciousCode"].action, Action::Break);
+}
+
+#[test]
+fn future_versions_are_rejected() {
+    match UserSettings::load(&fixture("user_settings/future.yaml")) {
+        Err(Error::UnsupportedSettingsVersion { version, latest }) => {
+            assert_eq!(version, 3);
+            assert_eq!(latest, UserSettings::CURRENT_VERSION);
+        }
+        result => panic!("expected unsupported version, got {:?}", result),
+    }
+}
+
+#[test]
+fn ambiguous_settings_are_rejected() {
+    let settings = "version: 1\nprojects:\n  labels:\n    default_label: main\n    other: {}\n";
+    assert!(matches!(
+        UserSettings::load(settings),
+        Err(Error::InvalidSettings(_))
+    ));
+
+    let settings = "version: 1\nprojects:\n  labels:\n    thresholds:\n      total: 0.5\n";
+    assert!(matches!(
+        UserSettings::load(settings),
+        Err(Error::InvalidSettings(_))
+    ));
+}
@@ -0,0 +1,384 @@
+mod common;
+
+use std::cmp::Ordering;
+
+use phylum_types::types::package::{Package, PackageDescriptor, PackageType, ScoredVersion};
+use phylum_types::types::version::{compare_versions, EcosystemVersion, VersionRange};
+
+use common::fixture;
+
+fn version(package_type: PackageType, version: &str) -> EcosystemVersion {
+    EcosystemVersion::parse(&package_type, version).unwrap()
+}
+
+fn matches(package_type: PackageType, range: &str, version: &str) -> bool {
+    let range = VersionRange::parse(&package_type, range).unwrap();
+    EcosystemVersion::parse(&package_type, version)
+        .unwrap()
+        .satisfies(&range)
+}
+
+fn package(version: &str, latest: Option<&str>, versions: &[&str]) -> Package {
+    let mut package: Package = serde_json::from_str(&fixture("scores/package_npm.json")).unwrap();
+    package.version = version.into();
+    package.latest_version = latest.map(String::from);
+    package.versions = versions
+        .iter()
+        .map(|version| ScoredVersion {
+            version: (*version).into(),
+            total_risk_score: None,
+            yanked: false,
+        })
+        .collect();
+    package
+}
+
+#[test]
+fn numeric_components_compare_as_numbers() {
+    for package_type in [
+        PackageType::Npm,
+        PackageType::Cargo,
+        PackageType::Pub,
+        PackageType::Hex,
+        PackageType::Golang,
+        PackageType::Composer,
+        PackageType::Nuget,
+        PackageType::PyPi,
+        PackageType::Maven,
+        PackageType::RubyGems,
+        PackageType::Conda,
+    ] {
+        let older = version(package_type.clone(), "1.9.0");
+        let newer = version(package_type.clone(), "1.10.0");
+        assert!(older < newer, "{:?}", package_type);
+        assert_eq!(
+            compare_versions(&package_type, "1.10.0", "1.9.0"),
+            Ordering::Greater,
+            "{:?}",
+            package_type
+        );
+    }
+}
+
+#[test]
+fn semver_ordering() {
+    let ordered = [
+        "1.0.0-alpha",
+        "1.0.0-alpha.1",
+        "1.0.0-alpha.beta",
+        "1.0.0-beta",
+        "1.0.0-beta.2",
+        "1.0.0-beta.11",
+        "1.0.0-rc.1",
+        "1.0.0",
+        "1.0.1",
+        "1.10.0",
+    ];
+    for pair in ordered.windows(2) {
+        let (a, b) = (pair[0], pair[1]);
+        assert!(
+            version(PackageType::Npm, a) < version(PackageType::Npm, b),
+            "{} < {}",
+            a,
+            b
+        );
+        assert_eq!(
+            compare_versions(&PackageType::Npm, a, b),
+            Ordering::Less,
+            "{} < {}",
+            a,
+            b
+        );
+    }
+
+    // Build metadata doesn't take part in the ordering.
+    assert_eq!(
+        version(PackageType::Npm, "1.0.0+build.1"),
+        version(PackageType::Npm, "1.0.0")
+    );
+    assert_eq!(version(PackageType::Npm, "v1.2.3").as_str(), "v1.2.3");
+}
+
+#[test]
+fn npm_requires_semver() {
+    for invalid in [
+        "1", "1.2", "1.2.3.4", "01.2.3", "1.02.3", "1.2.3-01", "1.2.x", "latest", "",
+    ] {
+        assert!(
+            EcosystemVersion::parse(&PackageType::Npm, invalid).is_err(),
+            "{}",
+            invalid
+        );
+        assert!(
+            EcosystemVersion::parse(&PackageType::Cargo, invalid).is_err(),
+            "{}",
+            invalid
+        );
+    }
+
+    // Non-semver versions sort after all valid ones.
+    assert_eq!(
+        compare_versions(&PackageType::Npm, "1.2", "1.0.0"),
+        Ordering::Greater
+    );
+    assert_eq!(
+        compare_versions(&PackageType::Npm, "1.2.3", "1.2.3.4"),
+        Ordering::Less
+    );
+
+    // Ecosystems without strict semver accept shorter versions.
+    assert_eq!(
+        version(PackageType::Nuget, "1.2"),
+        version(PackageType::Nuget, "1.2.0.0")
+    );
+    assert_eq!(
+        version(PackageType::Composer, "1.2"),
+        version(PackageType::Composer, "1.2.0")
+    );
+}
+
+#[test]
+fn npm_and_cargo_ranges() {
+    let cases = [
+        // Bare versions are exact for npm and caret requirements for Cargo.
+        ("1.2.3", "1.2.3", true, true),
+        ("1.2.3", "1.4.0", false, true),
+        ("1.2.3", "2.0.0", false, false),
+        // Both use the same caret rules below 1.0.
+        ("^0.2.3", "0.2.9", true, true),
+        ("^0.2.3", "0.3.0", false, false),
+        ("^0.0.3", "0.0.4", false, false),
+        ("0.2.3", "0.2.9", false, true),
+        // Pre-releases only match ranges naming the same release.
+        ("^1.2.3-beta.1", "1.2.3-beta.2", true, true),
+        ("^1.2.3", "1.3.0-beta.1", false, false),
+        ("~1.2.3", "1.2.9", true, true),
+        ("~1.2.3", "1.3.0", false, false),
+        (">=1.0.0, <2.0.0", "1.5.0", true, true),
+        ("1.x", "1.9.0", true, true),
+        ("*", "3.0.0", true, true),
+    ];
+    for (range, version, npm, cargo) in cases {
+        assert_eq!(
+            matches(PackageType::Npm, range, version),
+            npm,
+            "npm {} {}",
+            range,
+            version
+        );
+        assert_eq!(
+            matches(PackageType::Cargo, range, version),
+            cargo,
+            "cargo {} {}",
+            range,
+            version
+        );
+    }
+
+    // npm alternatives and hyphen ranges.
+    assert!(matches(PackageType::Npm, "^1.0.0 || ^3.0.0", "3.1.0"));
+    assert!(!matches(PackageType::Npm, "^1.0.0 || ^3.0.0", "2.0.0"));
+    assert!(matches(PackageType::Npm, "1.2 - 2.3", "2.3.9"));
+    assert!(!matches(PackageType::Npm, "1.2 - 2.3", "2.4.0"));
+}
+
+#[test]
+fn pep440_ordering() {
+    let ordered = [
+        "1.0.dev0",
+        "1.0a1.dev1",
+        "1.0a1",
+        "1.0b1",
+        "1.0rc1",
+        "1.0",
+        "1.0+local.1",
+        "1.0.post1.dev0",
+        "1.0.post1",
+        "1.1",
+        "1!0.1",
+    ];
+    for pair in ordered.windows(2) {
+        let (a, b) = (pair[0], pair[1]);
+        assert!(
+            version(PackageType::PyPi, a) < version(PackageType::PyPi, b),
+            "{} < {}",
+            a,
+            b
+        );
+    }
+
+    // Spellings are normalized.
+    assert_eq!(
+        version(PackageType::PyPi, "1.0"),
+        version(PackageType::PyPi, "1.0.0")
+    );
+    assert_eq!(
+        version(PackageType::PyPi, "1.0alpha1"),
+        version(PackageType::PyPi, "1.0a1")
+    );
+    assert_eq!(
+        version(PackageType::PyPi, "1.0-post1"),
+        version(PackageType::PyPi, "1.0.post1")
+    );
+    assert_eq!(
+        version(PackageType::PyPi, "v1.0RC1"),
+        version(PackageType::PyPi, "1.0rc1")
+    );
+
+    assert!(version(PackageType::PyPi, "1.0rc1").is_prerelease());
+    assert!(version(PackageType::PyPi, "1.0.dev0").is_prerelease());
+    assert!(!version(PackageType::PyPi, "1.0.post1").is_prerelease());
+}
+
+#[test]
+fn pep440_ranges() {
+    let cases = [
+        (">=1.0,<2.0", "1.5", true),
+        (">=1.0,<2.0", "2.0rc1", false),
+        (">=1.0rc1", "1.0rc2", true),
+        ("~=1.4.2", "1.4.9", true),
+        ("~=1.4.2", "1.5.0", false),
+        ("==1.4.*", "1.4.7", true),
+        ("==1.4.*", "1.5", false),
+        ("!=1.4.2", "1.4.2", false),
+        (">1.0", "1.0.post1", false),
+        ("<2.0", "2.0.dev0", false),
+    ];
+    for (range, version, expected) in cases {
+        assert_eq!(
+            matches(PackageType::PyPi, range, version),
+            expected,
+            "{} {}",
+            range,
+            version
+        );
+    }
+}
+
+#[test]
+fn maven_qualifiers() {
+    let ordered = [
+        "1.0-alpha-1",
+        "1.0-beta-1",
+        "1.0-milestone-1",
+        "1.0-rc-1",
+        "1.0-SNAPSHOT",
+        "1.0",
+        "1.0-sp-1",
+        "1.0-unknown",
+        "1.0.1",
+    ];
+    for pair in ordered.windows(2) {
+        let (a, b) = (pair[0], pair[1]);
+        assert!(
+            version(PackageType::Maven, a) < version(PackageType::Maven, b),
+            "{} < {}",
+            a,
+            b
+        );
+    }
+
+    for (a, b) in [
+        ("1.0", "1.0.0"),
+        ("1.0-ga", "1.0"),
+        ("1.0-final", "1.0"),
+        ("1.0.RELEASE", "1.0"),
+        ("1.0-a1", "1.0-alpha-1"),
+        ("1.0-CR1", "1.0-rc-1"),
+    ] {
+        assert_eq!(
+            version(PackageType::Maven, a),
+            version(PackageType::Maven, b),
+            "{} = {}",
+            a,
+            b
+        );
+    }
+
+    assert!(matches(PackageType::Maven, "[1.0,2.0)", "1.5"));
+    assert!(!matches(PackageType::Maven, "[1.0,2.0)", "2.0"));
+    assert!(matches(PackageType::Maven, "(,1.0],[1.2,)", "1.2"));
+    assert!(!matches(PackageType::Maven, "(,1.0],[1.2,)", "1.1"));
+}
+
+#[test]
+fn rubygems_and_nuget() {
+    assert!(version(PackageType::RubyGems, "1.0.0.pre") < version(PackageType::RubyGems, "1.0.0"));
+    assert!(matches(PackageType::RubyGems, "~> 2.2", "2.9"));
+    assert!(!matches(PackageType::RubyGems, "~> 2.2", "3.0"));
+    assert!(matches(PackageType::RubyGems, "~> 2.2.0", "2.2.5"));
+    assert!(!matches(PackageType::RubyGems, "~> 2.2.0", "2.3.0"));
+
+    assert_eq!(
+        version(PackageType::Nuget, "1.0.0-BETA"),
+        version(PackageType::Nuget, "1.0.0-beta")
+    );
+    assert!(matches(PackageType::Nuget, "1.0", "3.0.0"));
+    assert!(matches(PackageType::Nuget, "1.2.*", "1.2.7"));
+    assert!(!matches(PackageType::Nuget, "1.2.*", "1.3.0"));
+}
+
+#[test]
+fn descriptors_sort_by_version() {
+    let descriptor = |version: &str| PackageDescriptor {
+        name: "lodash".into(),
+        version: version.into(),
+        package_type: PackageType::Npm,
+    };
+
+    let mut descriptors = [
+        descriptor("1.10.0"),
+        descriptor("not-a-version"),
+        descriptor("1.9.0"),
+        descriptor("1.10.0-rc.1"),
+    ];
+    descriptors.sort();
+    let versions: Vec<&str> = descriptors.iter().map(|d| d.version.as_str()).collect();
+    assert_eq!(
+        versions,
+        ["1.9.0", "1.10.0-rc.1", "1.10.0", "not-a-version"]
+    );
+}
+
+#[test]
+fn versions_sorted() {
+    let package = package(
+        "1.9.0",
+        None,
+        &["1.10.0", "1.2.0", "1.10.0-rc.1", "bogus", "1.9.0"],
+    );
+    let versions: Vec<&str> = package
+        .versions_sorted()
+        .iter()
+        .map(|version| version.version.as_str())
+        .collect();
+    assert_eq!(
+        versions,
+        ["1.2.0", "1.9.0", "1.10.0-rc.1", "1.10.0", "bogus"]
+    );
+
+    // Unknown registries fall back to a lenient comparison.
+    let mut package = package;
+    package.registry = "somewhere".into();
+    let versions: Vec<&str> = package
+        .versions_sorted()
+        .iter()
+        .map(|version| version.version.as_str())
+        .filter(|version| version.starts_with('1'))
+        .collect();
+    assert_eq!(versions, ["1.2.0", "1.9.0", "1.10.0-rc.1", "1.10.0"]);
+}
+
+#[test]
+fn is_latest() {
+    // The registry's latest version wins over `versions`.
+    assert!(!package("1.9.0", Some("1.10.0"), &["1.9.0"]).is_latest());
+    assert!(package("1.10.0", Some("1.10.0"), &["1.9.0", "1.11.0"]).is_latest());
+
+    // Otherwise the highest known version is the latest.
+    assert!(!package("1.9.0", None, &["1.9.0", "1.10.0"]).is_latest());
+    assert!(package("1.10.0", None, &["1.9.0", "1.10.0", "1.10.0-rc.1"]).is_latest());
+
+    // Without any known versions there is nothing newer.
+    assert!(package("1.0.0", None, &[]).is_latest());
+}
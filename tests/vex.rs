@@ -0,0 +1,350 @@
+mod common;
+
+use chrono::{DateTime, TimeZone, Utc};
+use phylum_types::formats::vex::csaf::CsafVex;
+use phylum_types::formats::vex::openvex::OpenVex;
+use phylum_types::formats::vex::{ignored_reason, vex_status, Justification, VexStatus};
+use phylum_types::policy::ignore::{Suppression, SuppressionApplicator};
+use phylum_types::types::package::{IgnoredReason, PackageDescriptor, PackageType};
+use phylum_types::types::preferences::IgnoredIssue;
+
+use common::{fixture, job};
+
+fn now() -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2023, 3, 10, 0, 0, 0).unwrap()
+}
+
+/// The job's issues are not ignored, except CVE-2020-7598 which is ignored
+/// in the project's preferences.
+fn ignored_issues() -> Vec<IgnoredIssue> {
+    vec![IgnoredIssue {
+        id: "0d3c5a2b-91e4-5b0a-8d61-1f35d1c8a0f7".into(),
+        tag: "CVE-2020-7598".into(),
+        reason: IgnoredReason::Other,
+    }]
+}
+
+/// Apply suppressions to the issues of the job, after clearing all ignored
+/// issues. Returns the ignored reasons by issue tag.
+fn apply(suppressions: Vec<Suppression>) -> Vec<(String, IgnoredReason)> {
+    let mut job = job();
+    let mut applicator = SuppressionApplicator::new(suppressions, now());
+
+    let mut reasons = Vec::new();
+    for status in &mut job.packages {
+        let package = PackageDescriptor {
+            name: status.basic_status.name.clone(),
+            version: status.basic_status.version.clone(),
+            package_type: status.package_type.clone(),
+        };
+        for issue in &mut status.issues {
+            issue.ignored = IgnoredReason::False;
+        }
+
+        applicator.apply(&package, &mut status.issues);
+        for issue in &status.issues {
+            reasons.push((issue.issue.tag.clone().unwrap(), issue.ignored));
+        }
+    }
+    reasons
+}
+
+fn reason(reasons: &[(String, IgnoredReason)], tag: &str) -> IgnoredReason {
+    reasons
+        .iter()
+        .find(|(issue, _)| issue == tag)
+        .map(|(_, reason)| *reason)
+        .unwrap()
+}
+
+#[test]
+fn status_mapping() {
+    assert_eq!(
+        vex_status(IgnoredReason::NotRelevant),
+        (
+            VexStatus::NotAffected,
+            Some(Justification::VulnerableCodeNotInExecutePath)
+        )
+    );
+
+    for reason in [
+        IgnoredReason::False,
+        IgnoredReason::FalsePositive,
+        IgnoredReason::NotRelevant,
+        IgnoredReason::Other,
+    ] {
+        let (status, justification) = vex_status(reason);
+        assert_eq!(ignored_reason(status, justification), reason);
+    }
+
+    assert_eq!(
+        ignored_reason(VexStatus::Fixed, None),
+        IgnoredReason::FalsePositive
+    );
+    assert_eq!(
+        ignored_reason(
+            VexStatus::NotAffected,
+            Some(Justification::InlineMitigationsAlreadyExist)
+        ),
+        IgnoredReason::NotRelevant
+    );
+    assert_eq!(
+        ignored_reason(VexStatus::UnderInvestigation, None),
+        IgnoredReason::False
+    );
+}
+
+#[test]
+fn openvex_from_job() {
+    let timestamp = now();
+    let vex = OpenVex::from_job(&job(), &ignored_issues(), "Storefront Team", timestamp);
+    let json: serde_json::Value = serde_json::from_str(&vex.to_json()).unwrap();
+
+    assert_eq!(json["@context"], "https://openvex.dev/ns/v0.2.0");
+    assert_eq!(json["@id"], "urn:uuid:59482a54-423b-448d-8325-f171c9dc336b");
+    assert_eq!(json["timestamp"], "2023-03-10T00:00:00Z");
+
+    let statements = json["statements"].as_array().unwrap();
+    assert_eq!(statements.len(), 3);
+
+    assert_eq!(statements[0]["vulnerability"]["name"], "CM0010");
+    assert_eq!(statements[0]["status"], "affected");
+    assert_eq!(
+        statements[0]["products"][0]["@id"],
+        "pkg:npm/event-stream@3.3.6"
+    );
+    assert!(statements[0]["action_statement"].is_string());
+
+    assert_eq!(statements[1]["vulnerability"]["name"], "CVE-2021-44906");
+    assert_eq!(statements[1]["status"], "not_affected");
+    assert_eq!(
+        statements[1]["justification"],
+        "vulnerable_code_not_in_execute_path"
+    );
+    assert!(statements[1].get("impact_statement").is_none());
+
+    assert_eq!(statements[2]["vulnerability"]["name"], "CVE-2020-7598");
+    assert_eq!(statements[2]["status"], "not_affected");
+    assert!(statements[2].get("justification").is_none());
+    assert!(statements[2]["impact_statement"].is_string());
+}
+
+#[test]
+fn csaf_from_job() {
+    let timestamp = now();
+    let vex = CsafVex::from_job(
+        &job(),
+        &ignored_issues(),
+        "Storefront Team",
+        "https://storefront.example.com",
+        timestamp,
+    );
+    let json: serde_json::Value = serde_json::from_str(&vex.to_json()).unwrap();
+
+    assert_eq!(json["document"]["category"], "csaf_vex");
+    assert_eq!(json["document"]["csaf_version"], "2.0");
+    assert_eq!(json["document"]["title"], "VEX for storefront");
+    assert_eq!(
+        json["document"]["tracking"]["id"],
+        "59482a54-423b-448d-8325-f171c9dc336b"
+    );
+
+    let products = json["product_tree"]["full_product_names"]
+        .as_array()
+        .unwrap();
+    assert_eq!(products.len(), 2);
+    assert_eq!(products[1]["name"], "minimist 1.2.5");
+    assert_eq!(products[1]["product_id"], "pkg:npm/minimist@1.2.5");
+    assert_eq!(
+        products[1]["product_identification_helper"]["purl"],
+        "pkg:npm/minimist@1.2.5"
+    );
+
+    let vulnerabilities = json["vulnerabilities"].as_array().unwrap();
+    assert_eq!(vulnerabilities.len(), 3);
+
+    assert!(vulnerabilities[0].get("cve").is_none());
+    assert_eq!(vulnerabilities[0]["ids"][0]["text"], "CM0010");
+    assert_eq!(
+        vulnerabilities[0]["product_status"]["known_affected"][0],
+        "pkg:npm/event-stream@3.3.6"
+    );
+    assert_eq!(
+        vulnerabilities[0]["remediations"][0]["category"],
+        "vendor_fix"
+    );
+
+    assert_eq!(vulnerabilities[1]["cve"], "CVE-2021-44906");
+    assert_eq!(
+        vulnerabilities[1]["product_status"]["known_not_affected"][0],
+        "pkg:npm/minimist@1.2.5"
+    );
+    assert_eq!(
+        vulnerabilities[1]["flags"][0]["label"],
+        "vulnerable_code_not_in_execute_path"
+    );
+
+    assert_eq!(vulnerabilities[2]["cve"], "CVE-2020-7598");
+    assert_eq!(vulnerabilities[2]["threats"][0]["category"], "impact");
+    assert!(vulnerabilities[2].get("flags").is_none());
+}
+
+#[test]
+fn generated_documents_round_trip() {
+    let timestamp = now();
+    let openvex = OpenVex::from_job(&job(), &ignored_issues(), "Storefront Team", timestamp);
+    let openvex = OpenVex::from_json(&openvex.to_json()).unwrap();
+    let csaf = CsafVex::from_job(
+        &job(),
+        &ignored_issues(),
+        "Storefront Team",
+        "https://storefront.example.com",
+        timestamp,
+    );
+    let csaf = CsafVex::from_json(&csaf.to_json()).unwrap();
+
+    for suppressions in [openvex.suppressions(), csaf.suppressions()] {
+        let reasons = apply(suppressions);
+        assert_eq!(reason(&reasons, "CM0010"), IgnoredReason::False);
+        assert_eq!(
+            reason(&reasons, "CVE-2021-44906"),
+            IgnoredReason::NotRelevant
+        );
+        assert_eq!(reason(&reasons, "CVE-2020-7598"), IgnoredReason::Other);
+        assert_eq!(reason(&reasons, "HA0001"), IgnoredReason::False);
+    }
+}
+
+#[test]
+fn import_openvex() {
+    let vex = OpenVex::from_json(&fixture("vex/vendor.openvex.json")).unwrap();
+    assert_eq!(vex.version, 2);
+
+    let suppressions = vex.suppressions();
+    assert_eq!(suppressions.len(), 3);
+    assert_eq!(suppressions[0].tag.as_deref(), Some("GHSA-vh95-rmgr-6w4m"));
+    assert_eq!(suppressions[1].tag.as_deref(), Some("CVE-2020-7598"));
+    assert_eq!(suppressions[1].purl.as_deref(), Some("pkg:npm/minimist"));
+    assert_eq!(
+        suppressions[1].justification.as_deref(),
+        Some("vulnerable_code_cannot_be_controlled_by_adversary")
+    );
+    assert_eq!(
+        suppressions[1].approved_by.as_deref(),
+        Some("Example Security Team")
+    );
+
+    let reasons = apply(suppressions);
+    assert_eq!(
+        reason(&reasons, "CVE-2020-7598"),
+        IgnoredReason::NotRelevant
+    );
+    assert_eq!(reason(&reasons, "CVE-2021-44906"), IgnoredReason::False);
+    // The statement is about a different version.
+    assert_eq!(reason(&reasons, "CM0010"), IgnoredReason::False);
+}
+
+#[test]
+fn import_matches_canonical_names() {
+    let vex = OpenVex::from_json(
+        r#"{
+            "@context": "https://openvex.dev/ns/v0.2.0",
+            "@id": "https://example.com/vex/2023-002",
+            "author": "Example Security Team",
+            "timestamp": "2023-03-01T12:00:00Z",
+            "version": 1,
+            "statements": [
+                {
+                    "vulnerability": { "name": "CVE-2023-0001" },
+                    "products": [
+                        { "@id": "pkg:npm/@storefront/cart@2.0.0" },
+                        { "@id": "pkg:pypi/Django" },
+                        { "@id": "pkg:pypi/" }
+                    ],
+                    "status": "not_affected",
+                    "justification": "component_not_present"
+                }
+            ]
+        }"#,
+    )
+    .unwrap();
+    // The invalid package URL is skipped.
+    assert_eq!(vex.suppressions().len(), 2);
+
+    let mut issues = job().packages[1].issues.clone();
+    issues.truncate(1);
+    issues[0].issue.tag = Some("CVE-2023-0001".into());
+    issues[0].ignored = IgnoredReason::False;
+
+    let package = |name: &str, version: &str, package_type: PackageType| PackageDescriptor {
+        name: name.into(),
+        version: version.into(),
+        package_type,
+    };
+    let cases = [
+        (package("@storefront/cart", "2.0.0", PackageType::Npm), true),
+        (
+            package("@storefront/cart", "2.0.1", PackageType::Npm),
+            false,
+        ),
+        (package("django", "4.2.1", PackageType::PyPi), true),
+        (package("Django", "3.2.0", PackageType::PyPi), true),
+        (package("django-rest", "4.2.1", PackageType::PyPi), false),
+    ];
+    for (package, suppressed) in cases {
+        let mut applicator = SuppressionApplicator::new(vex.suppressions(), now());
+        let mut issues = issues.clone();
+        applicator.apply(&package, &mut issues);
+        assert_eq!(
+            issues[0].ignored != IgnoredReason::False,
+            suppressed,
+            "{:?}",
+            package
+        );
+    }
+}
+
+#[test]
+fn import_csaf() {
+    let vex = CsafVex::from_json(&fixture("vex/vendor.csaf.json")).unwrap();
+    let purls = vex.purls();
+    assert_eq!(purls.len(), 2);
+    assert_eq!(purls["minimist-1.2.6"], "pkg:npm/minimist@1.2.6");
+
+    let suppressions = vex.suppressions();
+    assert_eq!(suppressions.len(), 2);
+    assert_eq!(suppressions[0].reason, IgnoredReason::FalsePositive);
+    assert_eq!(
+        suppressions[0].purl.as_deref(),
+        Some("pkg:npm/minimist@1.2.6")
+    );
+
+    let reasons = apply(suppressions);
+    assert_eq!(
+        reason(&reasons, "CVE-2020-7598"),
+        IgnoredReason::FalsePositive
+    );
+    assert_eq!(reason(&reasons, "CVE-2021-44906"), IgnoredReason::False);
+
+    let mut applicator = SuppressionApplicator::new(vex.suppressions(), now());
+    let mut issues = job().packages[1].issues.clone();
+    let fixed = PackageDescriptor {
+        name: "minimist".into(),
+        version: "1.2.6".into(),
+        package_type: PackageType::Npm,
+    };
+    issues[0].ignored = IgnoredReason::False;
+    assert_eq!(applicator.apply(&fixed, &mut issues), 1);
+    assert_eq!(issues[0].ignored, IgnoredReason::FalsePositive);
+}
+
+#[test]
+fn invalid_documents() {
+    let openvex = fixture("vex/vendor.openvex.json").replace("openvex.dev", "example.com");
+    assert!(OpenVex::from_json(&openvex).is_err());
+    assert!(OpenVex::from_json("{}").is_err());
+
+    let csaf = fixture("vex/vendor.csaf.json").replace("csaf_vex", "csaf_base");
+    assert!(CsafVex::from_json(&csaf).is_err());
+    assert!(CsafVex::from_json(&fixture("vex/vendor.openvex.json")).is_err());
+}